@@ -0,0 +1,225 @@
+//! Detection of the payload of compressed streams.
+//!
+//! Requires the `decompress` feature.
+//!
+//! Single-stream compressors (gzip, bzip2, xz, zstd and LZ4) only tell that the data
+//! is compressed. The functions in this module decompress a bounded prefix of
+//! the stream and run the detection again on the result, so that e.g. a gzipped
+//! tarball is reported as `tar.gz` rather than `gz`.
+//!
+//! # Examples
+//!
+//! ```rust
+//! let buf = include_bytes!("../testdata/sample.tar.lz4");
+//! let kind = infer::decompress::get(buf).expect("payload type is known");
+//!
+//! assert_eq!(kind.payload().extension(), "tar");
+//! assert_eq!(kind.extension(), "tar.zst.lz4");
+//! ```
+
+use std::io::{self, Read, Write};
+
+use crate::{archive, Infer, Type, INFER};
+
+/// Default number of decompressed bytes inspected per compression layer.
+pub const DEFAULT_LIMIT: usize = 64 * 1024;
+
+/// Maximum number of nested compression layers that are unwrapped.
+pub const MAX_DEPTH: usize = 4;
+
+/// A compressed stream and the type of its payload.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Compressed {
+    layers: Vec<Type>,
+    payload: Type,
+}
+
+impl Compressed {
+    /// Returns the compression layers, outermost first.
+    #[must_use]
+    pub fn layers(&self) -> &[Type] {
+        &self.layers
+    }
+
+    /// Returns the type of the decompressed payload.
+    #[must_use]
+    pub const fn payload(&self) -> Type {
+        self.payload
+    }
+
+    /// Returns the compound file extension, e.g. `tar.gz`.
+    #[must_use]
+    pub fn extension(&self) -> String {
+        let mut extension = String::from(self.payload.extension());
+        for layer in self.layers.iter().rev() {
+            extension.push('.');
+            extension.push_str(layer.extension());
+        }
+        extension
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Codec {
+    Gzip,
+    Bzip2,
+    Xz,
+    Zstd,
+    Lz4,
+}
+
+impl Codec {
+    fn detect(buf: &[u8]) -> Option<Codec> {
+        if archive::is_gz(buf) {
+            Some(Codec::Gzip)
+        } else if archive::is_bz2(buf) {
+            Some(Codec::Bzip2)
+        } else if archive::is_xz(buf) {
+            Some(Codec::Xz)
+        } else if archive::is_zst(buf) {
+            Some(Codec::Zstd)
+        } else if archive::is_lz4(buf) {
+            Some(Codec::Lz4)
+        } else {
+            None
+        }
+    }
+
+    /// Decompresses at most `limit` bytes of `buf`.
+    ///
+    /// The input is usually truncated, so decoding errors end the output
+    /// instead of discarding it.
+    fn decompress(self, buf: &[u8], limit: usize) -> Vec<u8> {
+        let mut out = Vec::new();
+        let take = u64::try_from(limit).unwrap_or(u64::MAX);
+
+        // Errors are expected for truncated input, everything decoded up to it is kept.
+        match self {
+            Codec::Gzip => {
+                let _ = flate2::read::GzDecoder::new(buf)
+                    .take(take)
+                    .read_to_end(&mut out);
+            }
+            Codec::Bzip2 => {
+                let _ = bzip2::read::BzDecoder::new(buf)
+                    .take(take)
+                    .read_to_end(&mut out);
+            }
+            Codec::Xz => {
+                let mut writer = LimitWriter {
+                    out: &mut out,
+                    limit,
+                };
+                let _ = lzma_rs::xz_decompress(&mut &buf[..], &mut writer);
+            }
+            Codec::Zstd => {
+                if let Ok(decoder) = ruzstd::StreamingDecoder::new(skip_frames(buf)) {
+                    let _ = decoder.take(take).read_to_end(&mut out);
+                }
+            }
+            Codec::Lz4 => {
+                let _ = lz4_flex::frame::FrameDecoder::new(skip_frames(buf))
+                    .take(take)
+                    .read_to_end(&mut out);
+            }
+        }
+
+        out
+    }
+}
+
+/// Writer discarding everything past `limit` bytes.
+struct LimitWriter<'a> {
+    out: &'a mut Vec<u8>,
+    limit: usize,
+}
+
+impl Write for LimitWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let room = self.limit - self.out.len();
+        if room == 0 {
+            return Err(io::ErrorKind::WriteZero.into());
+        }
+        let len = buf.len().min(room);
+        self.out.extend_from_slice(&buf[..len]);
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Skips the leading zstd/LZ4 skippable frames.
+fn skip_frames(mut buf: &[u8]) -> &[u8] {
+    while buf.len() >= 8 {
+        let magic = u32::from_le_bytes(buf[0..4].try_into().unwrap());
+        if magic & 0xFFFF_FFF0 != 0x184D_2A50 {
+            break;
+        }
+        let len = u32::from_le_bytes(buf[4..8].try_into().unwrap());
+        let Some(next) = usize::try_from(len).ok().and_then(|len| buf.get(8 + len..)) else {
+            break;
+        };
+        buf = next;
+    }
+    buf
+}
+
+impl Infer {
+    /// Returns the type of the payload of a compressed buffer, inspecting
+    /// at most [`DEFAULT_LIMIT`] decompressed bytes per compression layer.
+    ///
+    /// # Examples
+    ///
+    /// See [`get`](./decompress/fn.get.html).
+    #[must_use]
+    pub fn get_compressed(&self, buf: &[u8]) -> Option<Compressed> {
+        self.get_compressed_with_limit(buf, DEFAULT_LIMIT)
+    }
+
+    /// Returns the type of the payload of a compressed buffer, inspecting
+    /// at most `limit` decompressed bytes per compression layer.
+    ///
+    /// Returns `None` if the buffer is not compressed or the payload type is unknown.
+    #[must_use]
+    pub fn get_compressed_with_limit(&self, buf: &[u8], limit: usize) -> Option<Compressed> {
+        let codec = Codec::detect(buf)?;
+        let mut layers = vec![self.get(buf)?];
+        let mut data = codec.decompress(buf, limit);
+
+        loop {
+            let payload = self.get(&data)?;
+            match Codec::detect(&data) {
+                Some(codec) if layers.len() < MAX_DEPTH => {
+                    layers.push(payload);
+                    data = codec.decompress(&data, limit);
+                }
+                _ => return Some(Compressed { layers, payload }),
+            }
+        }
+    }
+}
+
+/// Returns the type of the payload of a compressed buffer.
+///
+/// # Examples
+///
+/// ```rust
+/// let buf = include_bytes!("../testdata/sample.tar.zst");
+/// let kind = infer::decompress::get(buf).expect("payload type is known");
+///
+/// assert_eq!(kind.layers()[0].extension(), "zst");
+/// assert_eq!(kind.payload().mime_type(), "application/x-tar");
+/// ```
+#[must_use]
+pub fn get(buf: &[u8]) -> Option<Compressed> {
+    INFER.get_compressed(buf)
+}
+
+/// Returns the type of the payload of a compressed buffer, inspecting
+/// at most `limit` decompressed bytes per compression layer.
+#[must_use]
+pub fn get_with_limit(buf: &[u8], limit: usize) -> Option<Compressed> {
+    INFER.get_compressed_with_limit(buf, limit)
+}
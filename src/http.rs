@@ -0,0 +1,279 @@
+//! Content type sniffing of HTTP messages.
+//!
+//! Requires the `http` feature.
+//!
+//! The helpers in this module take the headers of an [`http::Request`] or
+//! [`http::Response`] and the first bytes of its body. They compute the
+//! effective `Content-Type` a browser would use, honoring
+//! `X-Content-Type-Options: nosniff`, and compare the declared `Content-Type`
+//! with the type detected by [`crate::get`].
+//!
+//! # Examples
+//!
+//! ```rust
+//! let response = http::Response::builder()
+//!     .header("Content-Type", "image/jpeg")
+//!     .body(())
+//!     .unwrap();
+//! let body = include_bytes!("../testdata/sample.png");
+//!
+//! let sniffed = infer::http::sniff_response(&response, body);
+//! assert_eq!(sniffed.effective_content_type(), "image/png");
+//! assert_eq!(sniffed.detected().map(|kind| kind.mime_type()), Some("image/png"));
+//! assert!(sniffed.is_mismatch());
+//! ```
+
+use ::http::header::{HeaderMap, CONTENT_TYPE, X_CONTENT_TYPE_OPTIONS};
+use ::http::{Request, Response};
+
+use crate::sniff::{self, essence, is_xml};
+use crate::{MatcherType, Type};
+
+/// MIME types that declare no particular type.
+const GENERIC_TYPES: &[&str] = &[
+    "application/octet-stream",
+    "application/unknown",
+    "binary/octet-stream",
+    "unknown/unknown",
+    "*/*",
+];
+
+/// Legacy and unregistered aliases of MIME types, with the registered type
+/// they are normalized to before the declared and detected types are compared.
+///
+/// TrueType and OpenType fonts are both detected as SFNT, and WOFF 2.0 fonts as
+/// WOFF, so the types of each format are normalized to the detected one.
+const ALIASES: &[(&str, &str)] = &[
+    ("application/font-sfnt", "font/sfnt"),
+    ("application/font-woff", "font/woff"),
+    ("application/font-woff2", "font/woff"),
+    ("application/vnd.ms-opentype", "font/sfnt"),
+    ("application/x-7z", "application/x-7z-compressed"),
+    ("application/x-bzip", "application/x-bzip2"),
+    (
+        "application/x-dosexec",
+        "application/vnd.microsoft.portable-executable",
+    ),
+    ("application/x-flac", "audio/flac"),
+    ("application/x-font-opentype", "font/sfnt"),
+    ("application/x-font-otf", "font/sfnt"),
+    ("application/x-font-truetype", "font/sfnt"),
+    ("application/x-font-ttf", "font/sfnt"),
+    ("application/x-font-woff", "font/woff"),
+    ("application/x-gzip", "application/gzip"),
+    (
+        "application/x-msdownload",
+        "application/vnd.microsoft.portable-executable",
+    ),
+    ("application/x-pdf", "application/pdf"),
+    ("application/x-rar", "application/vnd.rar"),
+    ("application/x-rar-compressed", "application/vnd.rar"),
+    ("application/x-sqlite3", "application/vnd.sqlite3"),
+    ("application/x-zip", "application/zip"),
+    ("application/x-zip-compressed", "application/zip"),
+    ("application/x-zstd", "application/zstd"),
+    ("audio/aiff", "audio/x-aiff"),
+    ("audio/mp3", "audio/mpeg"),
+    ("audio/mpeg3", "audio/mpeg"),
+    ("audio/vnd.wave", "audio/wav"),
+    ("audio/wave", "audio/wav"),
+    ("audio/x-flac", "audio/flac"),
+    ("audio/x-m4a", "audio/m4a"),
+    ("audio/x-midi", "audio/midi"),
+    ("audio/x-mp3", "audio/mpeg"),
+    ("audio/x-mpeg", "audio/mpeg"),
+    ("audio/x-wav", "audio/wav"),
+    ("font/otf", "font/sfnt"),
+    ("font/ttf", "font/sfnt"),
+    ("font/woff2", "font/woff"),
+    ("font/x-woff", "font/woff"),
+    ("image/ico", "image/vnd.microsoft.icon"),
+    ("image/jpg", "image/jpeg"),
+    ("image/pjpeg", "image/jpeg"),
+    ("image/x-bmp", "image/bmp"),
+    ("image/x-icon", "image/vnd.microsoft.icon"),
+    ("image/x-ms-bmp", "image/bmp"),
+    ("image/x-png", "image/png"),
+    ("image/x-tiff", "image/tiff"),
+    ("video/avi", "video/x-msvideo"),
+    ("video/flv", "video/x-flv"),
+    ("video/msvideo", "video/x-msvideo"),
+    ("video/vnd.avi", "video/x-msvideo"),
+];
+
+/// JavaScript MIME type essences, which are all equivalent.
+/// <https://mimesniff.spec.whatwg.org/#javascript-mime-type>
+const JAVASCRIPT_TYPES: &[&str] = &[
+    "application/ecmascript",
+    "application/javascript",
+    "application/x-ecmascript",
+    "application/x-javascript",
+    "text/ecmascript",
+    "text/javascript",
+    "text/javascript1.0",
+    "text/javascript1.1",
+    "text/javascript1.2",
+    "text/javascript1.3",
+    "text/javascript1.4",
+    "text/javascript1.5",
+    "text/jscript",
+    "text/livescript",
+    "text/x-ecmascript",
+    "text/x-javascript",
+];
+
+/// Newline-delimited JSON types, which are compatible with JSON.
+const JSON_LINES_TYPES: &[&str] = &[
+    "application/x-ndjson",
+    "application/ndjson",
+    "application/jsonl",
+    "application/x-jsonlines",
+];
+
+/// Content type information of an HTTP message.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sniffed<'a> {
+    declared: Option<&'a str>,
+    effective: &'a str,
+    detected: Option<Type>,
+    no_sniff: bool,
+}
+
+impl<'a> Sniffed<'a> {
+    /// Returns the declared `Content-Type`, if any.
+    #[must_use]
+    pub const fn declared_content_type(&self) -> Option<&'a str> {
+        self.declared
+    }
+
+    /// Returns the `Content-Type` a browser would use, see [`sniff::sniff`].
+    #[must_use]
+    pub const fn effective_content_type(&self) -> &'a str {
+        self.effective
+    }
+
+    /// Returns the type detected from the body by [`crate::get`].
+    #[must_use]
+    pub const fn detected(&self) -> Option<Type> {
+        self.detected
+    }
+
+    /// Returns whether the message has `X-Content-Type-Options: nosniff`.
+    #[must_use]
+    pub const fn is_no_sniff(&self) -> bool {
+        self.no_sniff
+    }
+
+    /// Returns whether the declared `Content-Type` contradicts the detected type.
+    ///
+    /// Messages without a declared or a detected type, or with a generic declared
+    /// type such as `application/octet-stream`, are not reported. Types of the
+    /// same family are compatible, after legacy aliases such as `audio/x-wav`
+    /// are normalized to the registered type:
+    ///
+    /// - any `text/*` type with any textual type, e.g. `text/plain` with `text/csv`
+    /// - XML types, `*/xml` and `*+xml`, with each other
+    /// - JSON types, `*/json`, `*+json` and newline-delimited JSON, with each other
+    /// - the JavaScript aliases, e.g. `application/javascript` and `text/javascript`
+    /// - animated images with the type of their still format
+    #[must_use]
+    pub fn is_mismatch(&self) -> bool {
+        let (Some(declared), Some(detected)) = (self.declared, self.detected) else {
+            return false;
+        };
+        let declared = normalize(essence(declared));
+        let is_textual = detected.matcher_type() == MatcherType::Text;
+        let detected = normalize(detected.mime_type());
+
+        if declared.eq_ignore_ascii_case(detected)
+            || GENERIC_TYPES
+                .iter()
+                .any(|generic| declared.eq_ignore_ascii_case(generic))
+        {
+            return false;
+        }
+
+        let compatible = (is_textual && starts_with_ignore_case(declared, "text/"))
+            || (is_xml(declared) && is_xml(detected))
+            || (is_json(declared) && is_json(detected))
+            || (is_one_of(declared, JAVASCRIPT_TYPES) && is_one_of(detected, JAVASCRIPT_TYPES))
+            || match detected {
+                "image/apng" => declared.eq_ignore_ascii_case("image/png"),
+                "image/x-animated-gif" => declared.eq_ignore_ascii_case("image/gif"),
+                "image/x-animated-webp" => declared.eq_ignore_ascii_case("image/webp"),
+                _ => false,
+            };
+        !compatible
+    }
+}
+
+/// Returns the content type information of a message from its headers and the
+/// first bytes of its body.
+#[must_use]
+pub fn sniff_headers<'a>(headers: &'a HeaderMap, body: &[u8]) -> Sniffed<'a> {
+    let declared = headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|value| !value.is_empty());
+    let no_sniff = is_no_sniff(headers);
+
+    Sniffed {
+        declared,
+        effective: sniff::sniff(body, declared, no_sniff),
+        detected: crate::get(body),
+        no_sniff,
+    }
+}
+
+/// Returns the content type information of a request and the first bytes of its body.
+#[must_use]
+pub fn sniff_request<'a, T>(request: &'a Request<T>, body: &[u8]) -> Sniffed<'a> {
+    sniff_headers(request.headers(), body)
+}
+
+/// Returns the content type information of a response and the first bytes of its body.
+#[must_use]
+pub fn sniff_response<'a, T>(response: &'a Response<T>, body: &[u8]) -> Sniffed<'a> {
+    sniff_headers(response.headers(), body)
+}
+
+/// Returns whether the first `X-Content-Type-Options` value is `nosniff`, as
+/// browsers only consider that one.
+fn is_no_sniff(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(X_CONTENT_TYPE_OPTIONS)
+        .iter()
+        .next()
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(',').next())
+        .is_some_and(|value| value.trim().eq_ignore_ascii_case("nosniff"))
+}
+
+fn starts_with_ignore_case(s: &str, prefix: &str) -> bool {
+    s.len() >= prefix.len() && s.as_bytes()[..prefix.len()].eq_ignore_ascii_case(prefix.as_bytes())
+}
+
+fn ends_with_ignore_case(s: &str, suffix: &str) -> bool {
+    s.len() >= suffix.len()
+        && s.as_bytes()[s.len() - suffix.len()..].eq_ignore_ascii_case(suffix.as_bytes())
+}
+
+/// Returns whether an essence is a JSON type, including newline-delimited JSON.
+fn is_json(essence: &str) -> bool {
+    ends_with_ignore_case(essence, "/json")
+        || ends_with_ignore_case(essence, "+json")
+        || is_one_of(essence, JSON_LINES_TYPES)
+}
+
+fn is_one_of(essence: &str, types: &[&str]) -> bool {
+    types.iter().any(|t| essence.eq_ignore_ascii_case(t))
+}
+
+/// Returns the registered type of a legacy alias, or the essence itself.
+fn normalize(essence: &str) -> &str {
+    ALIASES
+        .iter()
+        .find(|(alias, _)| essence.eq_ignore_ascii_case(alias))
+        .map_or(essence, |(_, registered)| registered)
+}
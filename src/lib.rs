@@ -68,15 +68,25 @@ assert_eq!(kind.extension(), "foo");
 
 #![crate_name = "infer"]
 #![doc(html_root_url = "https://docs.rs/infer/latest")]
-#![forbid(unsafe_code)]
+#![cfg_attr(not(feature = "mmap"), forbid(unsafe_code))]
+#![cfg_attr(feature = "mmap", deny(unsafe_code))]
 #![allow(clippy::struct_field_names)]
 #![cfg_attr(not(feature = "std"), no_std)]
 
 #[cfg(feature = "alloc")]
 extern crate alloc;
 
+#[cfg(feature = "decompress")]
+pub mod decompress;
+#[cfg(feature = "http")]
+pub mod http;
 mod map;
 mod matchers;
+#[cfg(feature = "mmap")]
+mod mmap;
+pub mod sniff;
+#[cfg(feature = "walk")]
+pub mod walk;
 
 #[cfg(feature = "alloc")]
 use alloc::vec::Vec;
@@ -250,15 +260,45 @@ impl Infer {
     #[cfg(feature = "std")]
     pub fn get_from_path<P: AsRef<Path>>(&self, path: P) -> io::Result<Option<Type>> {
         let file = File::open(path)?;
+        self.get_from_file(file)
+    }
+
+    /// Returns the file type of the file given a path, matching against
+    /// a memory-mapped view of the whole file rather than its first 8 KiB.
+    ///
+    /// Files that can not be mapped, like pipes and other special files,
+    /// fall back to the buffered read of [`get_from_path`](#method.get_from_path).
+    ///
+    /// The file must not be truncated or modified by another process while it
+    /// is matched: reading a page past the new end of a truncated file raises
+    /// `SIGBUS`, which terminates the process.
+    ///
+    /// # Examples
+    ///
+    /// See [`get_from_path_mmap`](./fn.get_from_path_mmap.html).
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if `path` does not exist or the user does not have
+    /// permission to read it.
+    #[cfg(feature = "mmap")]
+    pub fn get_from_path_mmap<P: AsRef<Path>>(&self, path: P) -> io::Result<Option<Type>> {
+        let file = File::open(path)?;
 
-        let limit = file
-            .metadata()
-            .ok()
-            .and_then(|m| usize::try_from(std::cmp::min(m.len(), 8192)).ok())
-            .map_or(0, |len| len + 1);
-        let mut bytes = Vec::with_capacity(limit);
-        file.take(8192).read_to_end(&mut bytes)?;
+        if file.metadata()?.is_file() {
+            // Empty files can not be mapped on every platform.
+            if let Ok(map) = mmap::map(&file) {
+                return Ok(self.get(&map));
+            }
+        }
 
+        self.get_from_file(file)
+    }
+
+    #[cfg(feature = "std")]
+    fn get_from_file(&self, mut file: File) -> io::Result<Option<Type>> {
+        let len = file.metadata().ok().map(|m| m.len());
+        let bytes = read_regions(&mut file, len)?;
         Ok(self.get(&bytes))
     }
 
@@ -356,6 +396,16 @@ impl Infer {
         self.is_type(buf, MatcherType::Doc)
     }
 
+    /// Determines whether a buffer is a file system type.
+    ///
+    /// # Examples
+    ///
+    /// See [`is_filesystem`](./fn.is_filesystem.html).
+    #[must_use]
+    pub fn is_filesystem(&self, buf: &[u8]) -> bool {
+        self.is_type(buf, MatcherType::Filesystem)
+    }
+
     /// Determines whether a buffer is a font type.
     ///
     /// # Examples
@@ -444,6 +494,52 @@ impl Infer {
     }
 }
 
+/// Reads the first 8 KiB of a file of length `len`, followed by the regions past
+/// them that some formats are classified by, if they are present.
+#[cfg(feature = "std")]
+fn read_regions<R>(r: &mut R, len: Option<u64>) -> io::Result<Vec<u8>>
+where
+    R: Read + io::Seek,
+{
+    let limit = len
+        .and_then(|len| usize::try_from(std::cmp::min(len, 8192)).ok())
+        .map_or(0, |len| len + 1);
+    let mut bytes = Vec::with_capacity(limit);
+    r.by_ref().take(8192).read_to_end(&mut bytes)?;
+
+    // ZIP based formats are classified by the central directory at the end of the file,
+    // which is appended to the prefix when the file is larger.
+    if let Some(len) = len.filter(|&len| len > bytes.len() as u64) {
+        if matchers::zip::is_local_file_header(&bytes) {
+            if let Some(central_directory) = matchers::zip::read_central_directory(r, len)? {
+                bytes.extend_from_slice(&central_directory);
+            }
+        } else {
+            // Disc images are classified by the volume descriptors after the 32 KiB
+            // system area, Btrfs and ZFS by their superblock at 64 and 16 KiB, and
+            // other disk images by a footer in the last 512 bytes. They are only
+            // read past the prefix when they are present.
+            if let Some(descriptors) =
+                matchers::disk::read_volume_descriptors(r, bytes.len() as u64, len)?
+            {
+                bytes.extend_from_slice(&descriptors);
+            }
+            if let Some(superblock) =
+                matchers::filesystem::read_superblock(r, bytes.len() as u64, len)?
+            {
+                bytes.extend_from_slice(&superblock);
+            }
+            if len > bytes.len() as u64 {
+                if let Some(footer) = matchers::disk::read_footer(r, len)? {
+                    bytes.extend_from_slice(&footer);
+                }
+            }
+        }
+    }
+
+    Ok(bytes)
+}
+
 impl Default for Infer {
     fn default() -> Self {
         Infer::new()
@@ -471,6 +567,10 @@ pub fn get(buf: &[u8]) -> Option<Type> {
 
 /// Returns the file type of the file given a path.
 ///
+/// The first 8 KiB of the file are read, along with the central directory of
+/// ZIP archives, the volume descriptors or footer of disk images and the
+/// superblock of file systems, which are further into the file.
+///
 /// # Errors
 ///
 /// Returns an error if we fail to read the path.
@@ -490,6 +590,31 @@ pub fn get_from_path<P: AsRef<Path>>(path: P) -> io::Result<Option<Type>> {
     INFER.get_from_path(path)
 }
 
+/// Returns the file type of the file given a path, matching against
+/// a memory-mapped view of the whole file.
+///
+/// The file must not be truncated or modified by another process meanwhile,
+/// see [`Infer::get_from_path_mmap`].
+///
+/// # Errors
+///
+/// Returns an error if we fail to open or read the path.
+///
+/// # Examples
+///
+/// ```rust
+/// let kind = infer::get_from_path_mmap("testdata/sample.doc")
+///     .expect("file read successfully")
+///     .expect("file type is known");
+///
+/// assert_eq!(kind.mime_type(), "application/msword");
+/// assert_eq!(kind.extension(), "doc");
+/// ```
+#[cfg(feature = "mmap")]
+pub fn get_from_path_mmap<P: AsRef<Path>>(path: P) -> io::Result<Option<Type>> {
+    INFER.get_from_path_mmap(path)
+}
+
 /// Determines whether a buffer is of given extension.
 ///
 /// # Examples
@@ -605,6 +730,19 @@ pub fn is_document(buf: &[u8]) -> bool {
     INFER.is_document(buf)
 }
 
+/// Determines whether a buffer is a file system type.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::fs;
+/// assert!(infer::is_filesystem(&fs::read("testdata/sample.ext4").unwrap()));
+/// ```
+#[must_use]
+pub fn is_filesystem(buf: &[u8]) -> bool {
+    INFER.is_filesystem(buf)
+}
+
 /// Determines whether a buffer is a font type.
 ///
 /// # Examples
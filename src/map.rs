@@ -7,6 +7,7 @@ pub enum MatcherType {
     Audio,
     Book,
     Doc,
+    Filesystem,
     Font,
     Image,
     Text,
@@ -28,7 +29,7 @@ macro_rules! matcher_map {
     };
 }
 
-// Order: Application, Image, Video, Audio, Font, Document, Archive, Text.
+// Order: Application, Image, Video, Audio, Font, Document, Archive, Filesystem, Text.
 // The above order should be preserved when adding new types since
 // it may affect match result and/or performances.
 matcher_map!(
@@ -39,17 +40,47 @@ matcher_map!(
         "wasm",
         matchers::app::is_wasm
     ),
+    (
+        MatcherType::App,
+        "application/x-object",
+        "o",
+        matchers::app::is_elf_relocatable
+    ),
+    (
+        MatcherType::App,
+        "application/x-sharedlib",
+        "so",
+        matchers::app::is_elf_shared_library
+    ),
+    (
+        MatcherType::App,
+        "application/x-pie-executable",
+        "elf",
+        matchers::app::is_elf_pie
+    ),
+    (
+        MatcherType::App,
+        "application/x-coredump",
+        "core",
+        matchers::app::is_core_dump
+    ),
     (
         MatcherType::App,
         "application/x-executable",
         "elf",
         matchers::app::is_elf
     ),
+    (
+        MatcherType::App,
+        "application/efi",
+        "efi",
+        matchers::app::is_efi
+    ),
     (
         MatcherType::App,
         "application/vnd.microsoft.portable-executable",
-        "exe",
-        matchers::app::is_exe
+        "sys",
+        matchers::app::is_sys
     ),
     (
         MatcherType::App,
@@ -57,18 +88,54 @@ matcher_map!(
         "dll",
         matchers::app::is_dll
     ),
+    (
+        MatcherType::App,
+        "application/vnd.microsoft.portable-executable",
+        "exe",
+        matchers::app::is_exe
+    ),
     (
         MatcherType::App,
         "application/java",
         "class",
         matchers::app::is_java
     ),
+    (
+        MatcherType::App,
+        "application/x-java-jmod",
+        "jmod",
+        matchers::app::is_jmod
+    ),
+    (
+        MatcherType::App,
+        "application/x-java-jimage",
+        "jimage",
+        matchers::app::is_jimage
+    ),
+    (
+        MatcherType::App,
+        "application/x-kotlin-metadata",
+        "kotlin_module",
+        matchers::app::is_kotlin_metadata
+    ),
     (
         MatcherType::App,
         "application/x-llvm",
         "bc",
         matchers::app::is_llvm
     ),
+    (
+        MatcherType::App,
+        "application/x-mach-dylib",
+        "dylib",
+        matchers::app::is_mach_dylib
+    ),
+    (
+        MatcherType::App,
+        "application/x-mach-bundle",
+        "bundle",
+        matchers::app::is_mach_bundle
+    ),
     (
         MatcherType::App,
         "application/x-mach-binary",
@@ -105,12 +172,96 @@ matcher_map!(
         "pem",
         matchers::app::is_pem
     ),
+    (
+        MatcherType::App,
+        "application/x-udf-image",
+        "iso",
+        matchers::app::is_udf
+    ),
+    (
+        MatcherType::App,
+        "application/x-iso9660-image",
+        "iso",
+        matchers::app::is_iso
+    ),
+    (
+        MatcherType::App,
+        "application/x-apple-diskimage",
+        "dmg",
+        matchers::app::is_dmg
+    ),
+    (
+        MatcherType::App,
+        "application/x-vhd-disk",
+        "vhd",
+        matchers::app::is_vhd
+    ),
+    (
+        MatcherType::App,
+        "application/x-vhdx-disk",
+        "vhdx",
+        matchers::app::is_vhdx
+    ),
+    (
+        MatcherType::App,
+        "application/x-vmdk-disk",
+        "vmdk",
+        matchers::app::is_vmdk
+    ),
+    (
+        MatcherType::App,
+        "application/x-vdi-disk",
+        "vdi",
+        matchers::app::is_vdi
+    ),
+    (
+        MatcherType::App,
+        "application/x-android-sparse-image",
+        "img",
+        matchers::app::is_android_sparse
+    ),
     (
         MatcherType::App,
         "application/x-qemu-disk",
         "qcow2",
         matchers::app::is_qcow2
     ),
+    (
+        MatcherType::App,
+        "application/java-archive",
+        "jar",
+        matchers::app::is_jar
+    ),
+    (
+        MatcherType::App,
+        "application/vnd.android.package-archive",
+        "apk",
+        matchers::app::is_apk
+    ),
+    (
+        MatcherType::App,
+        "application/x-android-app-bundle",
+        "aab",
+        matchers::app::is_aab
+    ),
+    (
+        MatcherType::App,
+        "application/x-ios-app",
+        "ipa",
+        matchers::app::is_ipa
+    ),
+    (
+        MatcherType::App,
+        "application/x-xpinstall",
+        "xpi",
+        matchers::app::is_xpi
+    ),
+    (
+        MatcherType::App,
+        "application/vsix",
+        "vsix",
+        matchers::app::is_vsix
+    ),
     // Book
     (
         MatcherType::Book,
@@ -137,18 +288,48 @@ matcher_map!(
         "jp2",
         matchers::image::is_jpeg2000
     ),
+    (
+        MatcherType::Image,
+        "image/jpx",
+        "jpx",
+        matchers::image::is_jpx
+    ),
+    (
+        MatcherType::Image,
+        "image/jpm",
+        "jpm",
+        matchers::image::is_jpm
+    ),
+    (
+        MatcherType::Image,
+        "image/apng",
+        "apng",
+        matchers::image::is_apng
+    ),
     (
         MatcherType::Image,
         "image/png",
         "png",
         matchers::image::is_png
     ),
+    (
+        MatcherType::Image,
+        "image/x-animated-gif",
+        "gif",
+        matchers::image::is_animated_gif
+    ),
     (
         MatcherType::Image,
         "image/gif",
         "gif",
         matchers::image::is_gif
     ),
+    (
+        MatcherType::Image,
+        "image/x-animated-webp",
+        "webp",
+        matchers::image::is_animated_webp
+    ),
     (
         MatcherType::Image,
         "image/webp",
@@ -161,6 +342,12 @@ matcher_map!(
         "cr2",
         matchers::image::is_cr2
     ),
+    (
+        MatcherType::Image,
+        "image/x-canon-cr3",
+        "cr3",
+        matchers::image::is_cr3
+    ),
     (
         MatcherType::Image,
         "image/tiff",
@@ -191,18 +378,48 @@ matcher_map!(
         "ico",
         matchers::image::is_ico
     ),
+    (
+        MatcherType::Image,
+        "application/x-navi-animation",
+        "ani",
+        matchers::image::is_ani
+    ),
+    (
+        MatcherType::Image,
+        "application/vnd.corel-draw",
+        "cdr",
+        matchers::image::is_cdr
+    ),
     (
         MatcherType::Image,
         "image/heif",
         "heif",
         matchers::image::is_heif
     ),
+    (
+        MatcherType::Image,
+        "image/heif-sequence",
+        "heifs",
+        matchers::image::is_heif_sequence
+    ),
+    (
+        MatcherType::Image,
+        "image/heic-sequence",
+        "heics",
+        matchers::image::is_heic_sequence
+    ),
     (
         MatcherType::Image,
         "image/avif",
         "avif",
         matchers::image::is_avif
     ),
+    (
+        MatcherType::Image,
+        "image/avif-sequence",
+        "avifs",
+        matchers::image::is_avif_sequence
+    ),
     (
         MatcherType::Image,
         "image/jxl",
@@ -227,6 +444,12 @@ matcher_map!(
         "dwg",
         matchers::image::is_dwg
     ),
+    (
+        MatcherType::Image,
+        "image/svg+xml",
+        "svg",
+        matchers::image::is_svg
+    ),
     // Video
     (
         MatcherType::Video,
@@ -240,6 +463,36 @@ matcher_map!(
         "m4v",
         matchers::video::is_m4v
     ),
+    (
+        MatcherType::Video,
+        "video/3gpp",
+        "3gp",
+        matchers::video::is_3gp
+    ),
+    (
+        MatcherType::Video,
+        "video/3gpp2",
+        "3g2",
+        matchers::video::is_3g2
+    ),
+    (
+        MatcherType::Video,
+        "video/x-f4v",
+        "f4v",
+        matchers::video::is_f4v
+    ),
+    (
+        MatcherType::Video,
+        "video/iso.segment",
+        "m4s",
+        matchers::video::is_m4s
+    ),
+    (
+        MatcherType::Video,
+        "video/mj2",
+        "mj2",
+        matchers::video::is_mj2
+    ),
     (
         MatcherType::Video,
         "video/x-matroska",
@@ -301,6 +554,18 @@ matcher_map!(
         "m4a",
         matchers::audio::is_m4a
     ),
+    (
+        MatcherType::Audio,
+        "audio/x-m4b",
+        "m4b",
+        matchers::audio::is_m4b
+    ),
+    (
+        MatcherType::Audio,
+        "audio/mp4",
+        "f4a",
+        matchers::audio::is_f4a
+    ),
     // has to come before ogg
     (
         MatcherType::Audio,
@@ -326,6 +591,42 @@ matcher_map!(
         "wav",
         matchers::audio::is_wav
     ),
+    (
+        MatcherType::Audio,
+        "audio/x-rf64",
+        "rf64",
+        matchers::audio::is_rf64
+    ),
+    (
+        MatcherType::Audio,
+        "audio/x-bw64",
+        "bw64",
+        matchers::audio::is_bw64
+    ),
+    (
+        MatcherType::Audio,
+        "audio/x-soundfont",
+        "sf2",
+        matchers::audio::is_sf2
+    ),
+    (
+        MatcherType::Audio,
+        "audio/dls",
+        "dls",
+        matchers::audio::is_dls
+    ),
+    (
+        MatcherType::Audio,
+        "audio/midi",
+        "rmi",
+        matchers::audio::is_rmi
+    ),
+    (
+        MatcherType::Audio,
+        "audio/qcelp",
+        "qcp",
+        matchers::audio::is_qcp
+    ),
     (
         MatcherType::Audio,
         "audio/amr",
@@ -418,6 +719,30 @@ matcher_map!(
         "pptx",
         matchers::doc::is_pptx
     ),
+    (
+        MatcherType::Doc,
+        "application/vnd.ms-outlook",
+        "msg",
+        matchers::doc::is_msg
+    ),
+    (
+        MatcherType::Doc,
+        "application/vnd.visio",
+        "vsd",
+        matchers::doc::is_vsd
+    ),
+    (
+        MatcherType::Doc,
+        "application/vnd.ms-publisher",
+        "pub",
+        matchers::doc::is_pub
+    ),
+    (
+        MatcherType::Doc,
+        "application/encrypted",
+        "cfb",
+        matchers::doc::is_encrypted_ooxml
+    ),
     // OpenDocument
     (
         MatcherType::Doc,
@@ -437,6 +762,30 @@ matcher_map!(
         "odp",
         matchers::odf::is_odp
     ),
+    (
+        MatcherType::Doc,
+        "application/vnd.oasis.opendocument.text",
+        "fodt",
+        matchers::odf::is_fodt
+    ),
+    (
+        MatcherType::Doc,
+        "application/vnd.oasis.opendocument.spreadsheet",
+        "fods",
+        matchers::odf::is_fods
+    ),
+    (
+        MatcherType::Doc,
+        "application/vnd.oasis.opendocument.presentation",
+        "fodp",
+        matchers::odf::is_fodp
+    ),
+    (
+        MatcherType::Doc,
+        "application/vnd.oasis.opendocument.graphics",
+        "fodg",
+        matchers::odf::is_fodg
+    ),
     // Archive
     (
         MatcherType::Archive,
@@ -606,13 +955,213 @@ matcher_map!(
         "msi",
         matchers::archive::is_msi
     ),
+    (
+        MatcherType::Archive,
+        "application/x-ms-patch",
+        "msp",
+        matchers::archive::is_msp
+    ),
+    (
+        MatcherType::Archive,
+        "application/x-ms-transform",
+        "mst",
+        matchers::archive::is_mst
+    ),
+    (
+        MatcherType::Archive,
+        "application/x-ms-thumbnail-cache",
+        "db",
+        matchers::archive::is_thumbs_db
+    ),
+    (
+        MatcherType::Archive,
+        "application/x-ole-storage",
+        "cfb",
+        matchers::archive::is_ole_storage
+    ),
     (
         MatcherType::Archive,
         "application/x-cpio",
         "cpio",
         matchers::archive::is_cpio
     ),
+    // Filesystem
+    (
+        MatcherType::Filesystem,
+        "application/x-ext2-image",
+        "ext2",
+        matchers::filesystem::is_ext2
+    ),
+    (
+        MatcherType::Filesystem,
+        "application/x-ext3-image",
+        "ext3",
+        matchers::filesystem::is_ext3
+    ),
+    (
+        MatcherType::Filesystem,
+        "application/x-ext4-image",
+        "ext4",
+        matchers::filesystem::is_ext4
+    ),
+    (
+        MatcherType::Filesystem,
+        "application/x-xfs-image",
+        "xfs",
+        matchers::filesystem::is_xfs
+    ),
+    (
+        MatcherType::Filesystem,
+        "application/x-btrfs-image",
+        "btrfs",
+        matchers::filesystem::is_btrfs
+    ),
+    (
+        MatcherType::Filesystem,
+        "application/vnd.squashfs",
+        "squashfs",
+        matchers::filesystem::is_squashfs
+    ),
+    (
+        MatcherType::Filesystem,
+        "application/x-erofs-image",
+        "erofs",
+        matchers::filesystem::is_erofs
+    ),
+    (
+        MatcherType::Filesystem,
+        "application/x-f2fs-image",
+        "f2fs",
+        matchers::filesystem::is_f2fs
+    ),
+    (
+        MatcherType::Filesystem,
+        "application/x-exfat-image",
+        "exfat",
+        matchers::filesystem::is_exfat
+    ),
+    (
+        MatcherType::Filesystem,
+        "application/x-ntfs-image",
+        "ntfs",
+        matchers::filesystem::is_ntfs
+    ),
+    (
+        MatcherType::Filesystem,
+        "application/x-fat12-image",
+        "fat12",
+        matchers::filesystem::is_fat12
+    ),
+    (
+        MatcherType::Filesystem,
+        "application/x-fat16-image",
+        "fat16",
+        matchers::filesystem::is_fat16
+    ),
+    (
+        MatcherType::Filesystem,
+        "application/x-fat32-image",
+        "fat32",
+        matchers::filesystem::is_fat32
+    ),
+    (
+        MatcherType::Filesystem,
+        "application/x-hfsplus-image",
+        "hfs",
+        matchers::filesystem::is_hfs_plus
+    ),
+    (
+        MatcherType::Filesystem,
+        "application/x-apfs-image",
+        "apfs",
+        matchers::filesystem::is_apfs
+    ),
+    (
+        MatcherType::Filesystem,
+        "application/x-zfs-image",
+        "zfs",
+        matchers::filesystem::is_zfs
+    ),
+    (
+        MatcherType::Filesystem,
+        "application/x-luks-image",
+        "luks",
+        matchers::filesystem::is_luks
+    ),
     // Text
+    // XML dialects have to come before html and xml
+    (
+        MatcherType::Text,
+        "application/rss+xml",
+        "rss",
+        matchers::text::is_rss
+    ),
+    (
+        MatcherType::Text,
+        "application/atom+xml",
+        "atom",
+        matchers::text::is_atom
+    ),
+    (
+        MatcherType::Text,
+        "application/vnd.google-earth.kml+xml",
+        "kml",
+        matchers::text::is_kml
+    ),
+    (
+        MatcherType::Text,
+        "application/gpx+xml",
+        "gpx",
+        matchers::text::is_gpx
+    ),
+    (
+        MatcherType::Text,
+        "application/xhtml+xml",
+        "xhtml",
+        matchers::text::is_xhtml
+    ),
+    (
+        MatcherType::Text,
+        "application/xslt+xml",
+        "xsl",
+        matchers::text::is_xslt
+    ),
+    (
+        MatcherType::Text,
+        "application/x-plist",
+        "plist",
+        matchers::text::is_plist
+    ),
+    (
+        MatcherType::Text,
+        "application/vnd.recordare.musicxml+xml",
+        "musicxml",
+        matchers::text::is_musicxml
+    ),
+    (
+        MatcherType::Text,
+        "application/xliff+xml",
+        "xlf",
+        matchers::text::is_xliff
+    ),
+    (
+        MatcherType::Text,
+        "application/docbook+xml",
+        "dbk",
+        matchers::text::is_docbook
+    ),
+    (
+        MatcherType::Text,
+        "application/vnd.android.resource+xml",
+        "xml",
+        matchers::text::is_android_xml
+    ),
+    (
+        MatcherType::Text,
+        "text/x-maven+xml",
+        "pom",
+        matchers::text::is_pom
+    ),
     (
         MatcherType::Text,
         "text/html",
@@ -620,10 +1169,122 @@ matcher_map!(
         matchers::text::is_html
     ),
     (MatcherType::Text, "text/xml", "xml", matchers::text::is_xml),
+    (
+        MatcherType::Text,
+        "text/x-python",
+        "py",
+        matchers::text::is_python
+    ),
+    (
+        MatcherType::Text,
+        "text/javascript",
+        "js",
+        matchers::text::is_javascript
+    ),
+    (
+        MatcherType::Text,
+        "text/x-perl",
+        "pl",
+        matchers::text::is_perl
+    ),
+    (
+        MatcherType::Text,
+        "application/x-ruby",
+        "rb",
+        matchers::text::is_ruby
+    ),
+    (
+        MatcherType::Text,
+        "application/x-php",
+        "php",
+        matchers::text::is_php
+    ),
+    (
+        MatcherType::Text,
+        "text/x-lua",
+        "lua",
+        matchers::text::is_lua
+    ),
+    (
+        MatcherType::Text,
+        "application/x-awk",
+        "awk",
+        matchers::text::is_awk
+    ),
     (
         MatcherType::Text,
         "text/x-shellscript",
         "sh",
         matchers::text::is_shellscript
+    ),
+    // JSON based formats have to come before json
+    (
+        MatcherType::Text,
+        "application/schema+json",
+        "json",
+        matchers::text::is_json_schema
+    ),
+    (
+        MatcherType::Text,
+        "application/x-ipynb+json",
+        "ipynb",
+        matchers::text::is_ipynb
+    ),
+    (
+        MatcherType::Text,
+        "model/gltf+json",
+        "gltf",
+        matchers::text::is_gltf
+    ),
+    (
+        MatcherType::Text,
+        "application/json",
+        "map",
+        matchers::text::is_source_map
+    ),
+    (
+        MatcherType::Text,
+        "application/topo+json",
+        "topojson",
+        matchers::text::is_topojson
+    ),
+    (
+        MatcherType::Text,
+        "application/geo+json",
+        "geojson",
+        matchers::text::is_geojson
+    ),
+    (
+        MatcherType::Text,
+        "application/manifest+json",
+        "webmanifest",
+        matchers::text::is_webmanifest
+    ),
+    (
+        MatcherType::Text,
+        "application/x-ndjson",
+        "ndjson",
+        matchers::text::is_ndjson
+    ),
+    (
+        MatcherType::Text,
+        "application/json",
+        "json",
+        matchers::text::is_json
+    ),
+    // delimited text has to come after json, whose lines may look delimited
+    (
+        MatcherType::Text,
+        "text/tab-separated-values",
+        "tsv",
+        matchers::text::is_tsv
+    ),
+    (MatcherType::Text, "text/csv", "csv", matchers::text::is_csv),
+    // has to come last, most other types are not plain text
+    (
+        MatcherType::Text,
+        "text/plain",
+        "txt",
+        matchers::text::is_plain_text
     )
 );
@@ -1,3 +1,10 @@
+use super::disk;
+use super::elf::{self, ObjectType};
+use super::java;
+use super::macho::{self, FileType};
+use super::pe::{self, Pe, Subsystem};
+use super::zip::{self, Container};
+
 /// Returns whether a buffer is a wasm.
 ///
 /// # Examples
@@ -21,7 +28,10 @@ pub fn is_wasm(buf: &[u8]) -> bool {
         && buf[7] == 0x00
 }
 
-/// Returns whether a buffer is an EXE. DLL and EXE have the same magic number, so returns true also for a DLL.
+/// Returns whether a buffer is an EXE.
+///
+/// DOS executables and images whose PE header is not within the buffer are
+/// reported as EXE too.
 ///
 /// # Example
 ///
@@ -31,13 +41,38 @@ pub fn is_wasm(buf: &[u8]) -> bool {
 /// ```
 #[must_use]
 pub fn is_exe(buf: &[u8]) -> bool {
-    buf.len() > 1 && buf[0] == 0x4D && buf[1] == 0x5A
+    buf.len() > 1
+        && buf[0] == 0x4D
+        && buf[1] == 0x5A
+        && pe::info(buf).map_or(true, |pe| {
+            !pe.is_dll() && !is_driver(&pe) && !pe.subsystem().is_efi()
+        })
 }
 
-/// Returns whether a buffer is a DLL. DLL and EXE have the same magic number, so returns true also for an EXE.
+/// Returns whether a buffer is a DLL, identified by the `IMAGE_FILE_DLL`
+/// characteristic of its PE header.
 #[must_use]
 pub fn is_dll(buf: &[u8]) -> bool {
-    is_exe(buf)
+    pe::info(buf).is_some_and(|pe| pe.is_dll() && !pe.subsystem().is_efi())
+}
+
+/// Returns whether a buffer is a Windows driver: an image for the native
+/// subsystem which is not a DLL.
+///
+/// Native applications, such as `autochk.exe`, are reported as drivers too.
+#[must_use]
+pub fn is_sys(buf: &[u8]) -> bool {
+    pe::info(buf).is_some_and(|pe| !pe.is_dll() && is_driver(&pe))
+}
+
+/// Returns whether a buffer is an EFI application, driver or ROM image.
+#[must_use]
+pub fn is_efi(buf: &[u8]) -> bool {
+    pe::info(buf).is_some_and(|pe| pe.subsystem().is_efi())
+}
+
+fn is_driver(pe: &Pe) -> bool {
+    pe.subsystem() == Subsystem::Native
 }
 
 /// Returns whether a buffer is an ELF.
@@ -46,26 +81,119 @@ pub fn is_elf(buf: &[u8]) -> bool {
     buf.len() > 52 && buf[0] == 0x7F && buf[1] == 0x45 && buf[2] == 0x4C && buf[3] == 0x46
 }
 
+/// Returns whether a buffer is an ELF relocatable object file.
+#[must_use]
+pub fn is_elf_relocatable(buf: &[u8]) -> bool {
+    elf_object_type(buf) == Some(ObjectType::Relocatable)
+}
+
+/// Returns whether a buffer is an ELF shared library.
+#[must_use]
+pub fn is_elf_shared_library(buf: &[u8]) -> bool {
+    elf_object_type(buf) == Some(ObjectType::SharedLibrary)
+}
+
+/// Returns whether a buffer is an ELF position-independent executable.
+#[must_use]
+pub fn is_elf_pie(buf: &[u8]) -> bool {
+    elf_object_type(buf) == Some(ObjectType::PieExecutable)
+}
+
+/// Returns whether a buffer is an ELF core dump.
+#[must_use]
+pub fn is_core_dump(buf: &[u8]) -> bool {
+    elf_object_type(buf) == Some(ObjectType::Core)
+}
+
+fn elf_object_type(buf: &[u8]) -> Option<ObjectType> {
+    is_elf(buf)
+        .then(|| elf::info(buf))
+        .flatten()
+        .map(|elf| elf.object_type())
+}
+
 /// Returns whether a buffer is compiled Java bytecode.
+///
+/// The class file version is available from [`java::info`].
 #[must_use]
 pub fn is_java(buf: &[u8]) -> bool {
-    if buf.len() < 8 || [0xca, 0xfe, 0xba, 0xbe] != buf[0..4] {
-        return false;
-    }
+    java::info(buf).is_some()
+}
 
-    //Checking the next 4 bytes are greater than or equal to 45 to distinguish from Mach-O binaries
-    //Mach-O "Fat" binaries also use 0xCAFEBABE as magic bytes to start the file
-    //Java are always Big Endian, after the magic bytes there are 2 bytes for the class file's
-    //minor version and then 2 bytes for the major version
-    //https://docs.oracle.com/javase/specs/jvms/se20/html/jvms-4.html
-    let minor_major_bytes = [buf[4], buf[5], buf[6], buf[7]];
-    if u32::from_be_bytes(minor_major_bytes) < 45 {
-        //Java class files start at a major version of 45 and a minor of 0
-        //So a value less than this shouldn't be a Java class file
+/// Returns whether a buffer is a Java module (JMOD).
+#[must_use]
+pub fn is_jmod(buf: &[u8]) -> bool {
+    // "JM", version 1.0, followed by a zip archive
+    buf.len() > 8 && buf[..4] == *b"JM\x01\x00" && buf[4..8] == *b"PK\x03\x04"
+}
+
+/// Returns whether a buffer is a Java runtime image, such as the `lib/modules`
+/// file of a JDK.
+#[must_use]
+pub fn is_jimage(buf: &[u8]) -> bool {
+    // magic 0xCAFEDADA and major version 1, in the byte order of the platform
+    buf.len() > 28
+        && (buf[..8] == [0xDA, 0xDA, 0xFE, 0xCA, 0x00, 0x00, 0x01, 0x00]
+            || buf[..8] == [0xCA, 0xFE, 0xDA, 0xDA, 0x00, 0x01, 0x00, 0x00])
+}
+
+/// Returns whether a buffer is Kotlin metadata, such as a `.kotlin_module` file.
+///
+/// The version header has to be followed by the protocol buffer of the module,
+/// whose first field lists the parts of a package, starting with its name.
+#[must_use]
+pub fn is_kotlin_metadata(buf: &[u8]) -> bool {
+    // number of version components, then major, minor and patch version
+    let int = |i: usize| {
+        buf.get(i..i + 4)
+            .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    };
+    let is_version = int(0) == Some(3)
+        && matches!(int(4), Some(1 | 2))
+        && int(8).is_some_and(|minor| minor < 100)
+        && int(12).is_some_and(|patch| patch < 100);
+
+    // flags follow the version since Kotlin 1.4
+    is_version
+        && [16, 20]
+            .iter()
+            .any(|&i| is_kotlin_module(&buf[i.min(buf.len())..]))
+}
+
+/// Returns whether a buffer starts with the `package_parts` or `metadata_parts`
+/// field of a Kotlin module, which may be cut off.
+fn is_kotlin_module(buf: &[u8]) -> bool {
+    let Some((&(0x0A | 0x12), rest)) = buf.split_first() else {
+        return false;
+    };
+    let Some((len, rest)) = varint(rest) else {
+        return false;
+    };
+    // the name of the package comes first, and may be empty for the root package
+    let Some((&0x0A, rest)) = rest.split_first() else {
         return false;
+    };
+    let Some((name_len, name)) = varint(rest) else {
+        return false;
+    };
+
+    name_len < len
+        && name
+            .iter()
+            .take(name_len as usize)
+            .all(|&b| matches!(b, b'.' | b'_' | b'$') || b.is_ascii_alphanumeric() || b >= 0x80)
+}
+
+/// Reads a protocol buffer varint of at most 32 bits.
+fn varint(buf: &[u8]) -> Option<(u32, &[u8])> {
+    let mut value = 0;
+    for (i, &b) in buf.iter().enumerate().take(5) {
+        value |= u32::from(b & 0x7F) << (7 * i);
+        if b & 0x80 == 0 {
+            return Some((value, &buf[i + 1..]));
+        }
     }
-    //For due dillegence confirm that the major bytes are greater than or equal to 45
-    u16::from_be_bytes([buf[6], buf[7]]) >= 45
+    None
 }
 
 /// Returns whether a buffer is LLVM Bitcode.
@@ -87,7 +215,7 @@ pub fn is_mach(buf: &[u8]) -> bool {
     match buf[0..4] {
         [width, 0xfa, 0xed, 0xfe] if width == 0xcf || width == 0xce => true,
         [0xfe, 0xed, 0xfa, width] if width == 0xcf || width == 0xce => true,
-        [0xca, 0xfe, 0xba, 0xbe] if buf.len() >= 8 => {
+        [0xca, 0xfe, 0xba, 0xbe | 0xbf] if buf.len() >= 8 => {
             //Checking the next 4 bytes are less than 45 to distinguish from Java class files
             //which also use 0xCAFEBABE as magic bytes
             //Fat Mach-O binaries are always Big Endian
@@ -97,6 +225,28 @@ pub fn is_mach(buf: &[u8]) -> bool {
     }
 }
 
+/// Returns whether a buffer is a Mach-O dynamic library.
+///
+/// Universal binaries are checked by the header of their first slice within
+/// the buffer.
+#[must_use]
+pub fn is_mach_dylib(buf: &[u8]) -> bool {
+    mach_file_type(buf) == Some(FileType::Dylib)
+}
+
+/// Returns whether a buffer is a Mach-O loadable bundle, such as a plugin.
+///
+/// Universal binaries are checked by the header of their first slice within
+/// the buffer.
+#[must_use]
+pub fn is_mach_bundle(buf: &[u8]) -> bool {
+    mach_file_type(buf) == Some(FileType::Bundle)
+}
+
+fn mach_file_type(buf: &[u8]) -> Option<FileType> {
+    macho::info(buf).map(|macho| macho.file_type())
+}
+
 /// Returns whether a buffer is a Dalvik Executable (DEX).
 #[must_use]
 pub fn is_dex(buf: &[u8]) -> bool {
@@ -173,9 +323,116 @@ pub fn is_pem(buf: &[u8]) -> bool {
         && buf[10] == b' '
 }
 
+/// Returns whether a buffer is an ISO 9660 disc image.
+///
+/// The volume descriptors start at 32 KiB, see [`crate::get_from_path`] for
+/// how they are read from files.
+#[must_use]
+pub fn is_iso(buf: &[u8]) -> bool {
+    disk::is_iso9660(buf)
+}
+
+/// Returns whether a buffer is a UDF disc image, including ISO 9660 bridge
+/// discs with a UDF file system.
+#[must_use]
+pub fn is_udf(buf: &[u8]) -> bool {
+    disk::is_udf(buf)
+}
+
+/// Returns whether a buffer is an Apple disk image (DMG), which ends with a
+/// `koly` trailer.
+#[must_use]
+pub fn is_dmg(buf: &[u8]) -> bool {
+    disk::is_dmg(buf)
+}
+
+/// Returns whether a buffer is a Virtual PC/Hyper-V disk (VHD).
+#[must_use]
+pub fn is_vhd(buf: &[u8]) -> bool {
+    disk::is_vhd(buf)
+}
+
+/// Returns whether a buffer is a Hyper-V disk (VHDX).
+#[must_use]
+pub fn is_vhdx(buf: &[u8]) -> bool {
+    // https://learn.microsoft.com/en-us/openspecs/windows_protocols/ms-vhdx
+    buf.len() > 8 && buf.starts_with(b"vhdxfile")
+}
+
+/// Returns whether a buffer is a VMware disk (VMDK), either a sparse extent or
+/// a text descriptor.
+#[must_use]
+pub fn is_vmdk(buf: &[u8]) -> bool {
+    // https://web.archive.org/web/2020/https://www.vmware.com/app/vmdk/?src=vmdk
+    let version = |buf: &[u8]| u32::from_le_bytes([buf[4], buf[5], buf[6], buf[7]]);
+    buf.len() > 8
+        && ((buf.starts_with(b"KDMV") && (1..=3).contains(&version(buf)))
+            || (buf.starts_with(b"COWD") && version(buf) == 1)
+            || buf.starts_with(b"# Disk DescriptorFile"))
+}
+
+/// Returns whether a buffer is a VirtualBox disk (VDI).
+#[must_use]
+pub fn is_vdi(buf: &[u8]) -> bool {
+    // the header starts with a text, e.g. "<<< Oracle VM VirtualBox Disk Image >>>\n",
+    // followed by the magic at 0x40
+    buf.len() > 0x44 && buf[..4] == *b"<<< " && buf[0x40..0x44] == [0x7F, 0x10, 0xDA, 0xBE]
+}
+
+/// Returns whether a buffer is an Android sparse image.
+#[must_use]
+pub fn is_android_sparse(buf: &[u8]) -> bool {
+    // https://android.googlesource.com/platform/system/core/+/master/libsparse/sparse_format.h
+    // magic, major version 1 and header lengths of 28 and 12 bytes
+    buf.len() > 12
+        && buf[..4] == [0x3A, 0xFF, 0x26, 0xED]
+        && buf[4..6] == [1, 0]
+        && buf[8..10] == [28, 0]
+        && buf[10..12] == [12, 0]
+}
+
 /// Returns whether a buffer is a QCOW2 disk.
 #[must_use]
 pub fn is_qcow2(buf: &[u8]) -> bool {
     // https://github.com/qemu/qemu/blob/master/docs/interop/qcow2.txt
     buf.len() > 4 && buf[0] == b'Q' && buf[1] == b'F' && buf[2] == b'I' && buf[3] == 0xFB
 }
+
+/// Returns whether a buffer is a Java archive (JAR).
+#[must_use]
+pub fn is_jar(buf: &[u8]) -> bool {
+    zip::package(buf) == Some(Container::JAR)
+}
+
+/// Returns whether a buffer is an Android application package (APK).
+#[must_use]
+pub fn is_apk(buf: &[u8]) -> bool {
+    zip::package(buf) == Some(Container::APK)
+}
+
+/// Returns whether a buffer is an Android App Bundle (AAB).
+#[must_use]
+pub fn is_aab(buf: &[u8]) -> bool {
+    zip::package(buf) == Some(Container::AAB)
+}
+
+/// Returns whether a buffer is an iOS application archive (IPA).
+#[must_use]
+pub fn is_ipa(buf: &[u8]) -> bool {
+    zip::package(buf) == Some(Container::IPA)
+}
+
+/// Returns whether a buffer is a Mozilla extension (XPI).
+///
+/// WebExtensions have to be signed by addons.mozilla.org, legacy extensions
+/// have an `install.rdf`.
+#[must_use]
+pub fn is_xpi(buf: &[u8]) -> bool {
+    zip::package(buf) == Some(Container::XPI)
+}
+
+/// Returns whether a buffer is a Visual Studio extension (VSIX).
+#[must_use]
+pub fn is_vsix(buf: &[u8]) -> bool {
+    zip::package(buf) == Some(Container::VSIX)
+}
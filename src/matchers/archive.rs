@@ -1,5 +1,7 @@
 use core::convert::{TryFrom, TryInto};
 
+use super::doc::{self, DocType};
+
 /// Returns whether a buffer is an ePub.
 #[must_use]
 pub fn is_epub(buf: &[u8]) -> bool {
@@ -361,15 +363,33 @@ pub fn is_lz4(buf: &[u8]) -> bool {
 /// Returns whether a buffer is a MSI Windows Installer archive.
 #[must_use]
 pub fn is_msi(buf: &[u8]) -> bool {
-    buf.len() > 7
-        && buf[0] == 0xD0
-        && buf[1] == 0xCF
-        && buf[2] == 0x11
-        && buf[3] == 0xE0
-        && buf[4] == 0xA1
-        && buf[5] == 0xB1
-        && buf[6] == 0x1A
-        && buf[7] == 0xE1
+    doc::ole2(buf) == Some(DocType::MSI)
+}
+
+/// Returns whether a buffer is a MSP Windows Installer patch.
+#[must_use]
+pub fn is_msp(buf: &[u8]) -> bool {
+    doc::ole2(buf) == Some(DocType::MSP)
+}
+
+/// Returns whether a buffer is a MST Windows Installer transform.
+#[must_use]
+pub fn is_mst(buf: &[u8]) -> bool {
+    doc::ole2(buf) == Some(DocType::MST)
+}
+
+/// Returns whether a buffer is a Windows thumbnail cache (Thumbs.db).
+#[must_use]
+pub fn is_thumbs_db(buf: &[u8]) -> bool {
+    doc::ole2(buf) == Some(DocType::THUMBS)
+}
+
+/// Returns whether a buffer is a Compound File Binary (OLE2) storage.
+///
+/// This matches any compound file, including those of more specific types.
+#[must_use]
+pub fn is_ole_storage(buf: &[u8]) -> bool {
+    doc::is_ole2(buf)
 }
 
 /// Returns whether a buffer is a CPIO archive.
@@ -1,3 +1,9 @@
+use super::audio_info;
+use super::isobmff::{self, Kind};
+use super::riff;
+
+pub use super::audio_info::AudioInfo;
+
 /// Returns whether a buffer is MIDI data.
 #[must_use]
 pub fn is_midi(buf: &[u8]) -> bool {
@@ -19,15 +25,20 @@ pub fn is_mp3(buf: &[u8]) -> bool {
 /// Returns whether a buffer is M4A data.
 #[must_use]
 pub fn is_m4a(buf: &[u8]) -> bool {
-    buf.len() > 10
-        && ((buf[4] == 0x66
-            && buf[5] == 0x74
-            && buf[6] == 0x79
-            && buf[7] == 0x70
-            && buf[8] == 0x4D
-            && buf[9] == 0x34
-            && buf[10] == 0x41)
-            || (buf[0] == 0x4D && buf[1] == 0x34 && buf[2] == 0x41 && buf[3] == 0x20))
+    isobmff::kind(buf) == Some(Kind::M4a)
+        || (buf.len() > 10 && buf[0] == 0x4D && buf[1] == 0x34 && buf[2] == 0x41 && buf[3] == 0x20)
+}
+
+/// Returns whether a buffer is M4B audiobook data.
+#[must_use]
+pub fn is_m4b(buf: &[u8]) -> bool {
+    isobmff::kind(buf) == Some(Kind::M4b)
+}
+
+/// Returns whether a buffer is Adobe Flash F4A audio data.
+#[must_use]
+pub fn is_f4a(buf: &[u8]) -> bool {
+    isobmff::kind(buf) == Some(Kind::F4a)
 }
 
 /// Returns whether a buffer is OGG data.
@@ -63,15 +74,43 @@ pub fn is_flac(buf: &[u8]) -> bool {
 /// Returns whether a buffer is WAV data.
 #[must_use]
 pub fn is_wav(buf: &[u8]) -> bool {
-    buf.len() > 11
-        && buf[0] == 0x52
-        && buf[1] == 0x49
-        && buf[2] == 0x46
-        && buf[3] == 0x46
-        && buf[8] == 0x57
-        && buf[9] == 0x41
-        && buf[10] == 0x56
-        && buf[11] == 0x45
+    riff::kind(buf) == Some(riff::Kind::Wav)
+}
+
+/// Returns whether a buffer is RF64 (64-bit WAVE) data.
+#[must_use]
+pub fn is_rf64(buf: &[u8]) -> bool {
+    riff::kind(buf) == Some(riff::Kind::Rf64)
+}
+
+/// Returns whether a buffer is BW64 (Broadcast Wave 64) data.
+#[must_use]
+pub fn is_bw64(buf: &[u8]) -> bool {
+    riff::kind(buf) == Some(riff::Kind::Bw64)
+}
+
+/// Returns whether a buffer is SoundFont 2 (SF2) data.
+#[must_use]
+pub fn is_sf2(buf: &[u8]) -> bool {
+    riff::kind(buf) == Some(riff::Kind::Sf2)
+}
+
+/// Returns whether a buffer is Downloadable Sounds (DLS) data.
+#[must_use]
+pub fn is_dls(buf: &[u8]) -> bool {
+    riff::kind(buf) == Some(riff::Kind::Dls)
+}
+
+/// Returns whether a buffer is RIFF MIDI (RMI) data.
+#[must_use]
+pub fn is_rmi(buf: &[u8]) -> bool {
+    riff::kind(buf) == Some(riff::Kind::Rmi)
+}
+
+/// Returns whether a buffer is Qualcomm PureVoice (QCP) data.
+#[must_use]
+pub fn is_qcp(buf: &[u8]) -> bool {
+    riff::kind(buf) == Some(riff::Kind::Qcp)
 }
 
 /// Returns whether a buffer is AMR data.
@@ -119,3 +158,25 @@ pub fn is_ape(buf: &[u8]) -> bool {
     // ref: https://github.com/fernandotcl/monkeys-audio/blob/master/src/MACLib/APEHeader.h
     buf.len() > 4 && buf[0] == b'M' && buf[1] == b'A' && buf[2] == b'C' && buf[3] == b' '
 }
+
+/// Returns the sample rate, channel count, bit depth and duration of an audio
+/// stream, from its header.
+///
+/// WAV (including RF64 and BW64), FLAC, AIFF, DSF, APE, Ogg Opus, Vorbis and
+/// FLAC, and MP3 are supported. The duration is only available if the header
+/// tells the number of samples, e.g. from the Xing header of a VBR MP3.
+///
+/// # Examples
+///
+/// ```rust
+/// let buf = include_bytes!("../../testdata/sample.ape");
+/// let info = infer::audio::info(buf).expect("APE header is valid");
+///
+/// assert_eq!(info.sample_rate(), 44100);
+/// assert_eq!(info.channels(), 2);
+/// assert_eq!(info.bit_depth(), Some(16));
+/// ```
+#[must_use]
+pub fn info(buf: &[u8]) -> Option<AudioInfo> {
+    audio_info::info(buf)
+}
@@ -0,0 +1,319 @@
+//! Audio stream parameter parsing.
+//!
+//! Sample rates, channel counts and bit depths are read from the headers of
+//! the supported formats, without decoding any audio data.
+
+use core::convert::TryInto;
+
+use super::{u16_be, u16_le, u32_be, u32_le, u64_be, u64_le};
+use core::time::Duration;
+
+/// Maximum number of chunks that are walked.
+const MAX_CHUNKS: usize = 64;
+
+/// Parameters of an audio stream.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct AudioInfo {
+    sample_rate: u32,
+    channels: u16,
+    bit_depth: Option<u8>,
+    samples: Option<u64>,
+}
+
+impl AudioInfo {
+    /// Returns the number of samples per second and channel.
+    #[must_use]
+    pub const fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Returns the number of channels.
+    #[must_use]
+    pub const fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    /// Returns the number of bits per sample of uncompressed and lossless
+    /// streams.
+    #[must_use]
+    pub const fn bit_depth(&self) -> Option<u8> {
+        self.bit_depth
+    }
+
+    /// Returns the duration of the stream, if the header tells the number of
+    /// samples.
+    #[must_use]
+    pub fn duration(&self) -> Option<Duration> {
+        let samples = self.samples?;
+        let rate = u64::from(self.sample_rate);
+        if rate == 0 {
+            return None;
+        }
+        let nanos = (samples % rate) * 1_000_000_000 / rate;
+        Some(Duration::from_secs(samples / rate) + Duration::from_nanos(nanos))
+    }
+}
+
+/// Returns the parameters of an audio stream.
+pub(crate) fn info(buf: &[u8]) -> Option<AudioInfo> {
+    match buf {
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'A', b'V', b'E', ..]
+        | [b'R' | b'B', b'F' | b'W', b'6', b'4', _, _, _, _, b'W', b'A', b'V', b'E', ..] => {
+            wav(buf)
+        }
+        [b'f', b'L', b'a', b'C', ..] => flac(buf),
+        [b'F', b'O', b'R', b'M', _, _, _, _, b'A', b'I', b'F', b'F' | b'C', ..] => aiff(buf),
+        [b'D', b'S', b'D', b' ', ..] => dsf(buf),
+        [b'M', b'A', b'C', b' ', ..] => ape(buf),
+        [b'O', b'g', b'g', b'S', ..] => ogg(buf),
+        _ => mp3(buf),
+    }
+}
+
+fn wav(buf: &[u8]) -> Option<AudioInfo> {
+    let mut format = None;
+    let mut long_data_len = None;
+    let mut pos = 12;
+    for _ in 0..MAX_CHUNKS {
+        let id = buf.get(pos..pos + 4)?;
+        let len = u32_le(buf, pos + 4)?;
+        let body = pos + 8;
+        match id {
+            // sizes of RF64 and BW64 files which do not fit in 32 bits
+            b"ds64" => long_data_len = u64_le(buf, body + 8),
+            b"fmt " => format = Some(body),
+            b"data" => {
+                let fmt = format?;
+                let tag = u16_le(buf, fmt)?;
+                let channels = u16_le(buf, fmt + 2)?;
+                let block_align = u16_le(buf, fmt + 12)?;
+                let bits = u16_le(buf, fmt + 14)?;
+                let data_len = match (len, long_data_len) {
+                    (0xFFFF_FFFF, Some(long)) => long,
+                    _ => u64::from(len),
+                };
+                // PCM, IEEE float and extensible formats
+                let uncompressed = matches!(tag, 1 | 3 | 0xFFFE) && block_align > 0;
+
+                return Some(AudioInfo {
+                    sample_rate: u32_le(buf, fmt + 4)?,
+                    channels,
+                    bit_depth: u8::try_from(bits).ok().filter(|&bits| bits > 0),
+                    samples: uncompressed.then(|| data_len / u64::from(block_align)),
+                });
+            }
+            _ => {}
+        }
+        // chunks are padded to an even length
+        pos = body.checked_add(usize::try_from(len).ok()?)?;
+        pos += pos & 1;
+    }
+    None
+}
+
+fn flac(buf: &[u8]) -> Option<AudioInfo> {
+    // the first metadata block is always STREAMINFO
+    if buf.get(4)? & 0x7F != 0 {
+        return None;
+    }
+    // sample rate (20 bits), channels - 1 (3 bits), bits per sample - 1
+    // (5 bits) and total samples (36 bits)
+    let bits = u64_be(buf, 18)?;
+    Some(AudioInfo {
+        sample_rate: (bits >> 44) as u32,
+        channels: ((bits >> 41) & 0x07) as u16 + 1,
+        bit_depth: Some(((bits >> 36) & 0x1F) as u8 + 1),
+        samples: Some(bits & 0x000F_FFFF_FFFF).filter(|&samples| samples > 0),
+    })
+}
+
+fn aiff(buf: &[u8]) -> Option<AudioInfo> {
+    let mut pos = 12;
+    for _ in 0..MAX_CHUNKS {
+        let id = buf.get(pos..pos + 4)?;
+        let len = u32_be(buf, pos + 4)?;
+        let body = pos + 8;
+        if id == b"COMM" {
+            return Some(AudioInfo {
+                sample_rate: extended_to_u32(buf.get(body + 8..body + 18)?)?,
+                channels: u16_be(buf, body)?,
+                bit_depth: u16_be(buf, body + 6)?.try_into().ok(),
+                samples: Some(u32_be(buf, body + 2)?.into()),
+            });
+        }
+        pos = body.checked_add(usize::try_from(len).ok()?)?;
+        pos += pos & 1;
+    }
+    None
+}
+
+/// Converts the integer part of an 80-bit IEEE 754 extended precision number.
+fn extended_to_u32(bytes: &[u8]) -> Option<u32> {
+    let exponent = i32::from(u16::from_be_bytes([bytes[0], bytes[1]]));
+    let mantissa = u64::from_be_bytes(bytes[2..10].try_into().ok()?);
+    // negative numbers and fractions are not sample rates
+    let shift = 63 - (exponent - 16383);
+    if !(32..=63).contains(&shift) {
+        return None;
+    }
+    (mantissa >> shift).try_into().ok()
+}
+
+fn dsf(buf: &[u8]) -> Option<AudioInfo> {
+    // the DSD chunk is followed by the fmt chunk
+    let fmt = usize::try_from(u64_le(buf, 4)?).ok()?;
+    if buf.get(fmt..fmt.checked_add(4)?)? != b"fmt " {
+        return None;
+    }
+    Some(AudioInfo {
+        sample_rate: u32_le(buf, fmt + 28)?,
+        channels: u32_le(buf, fmt + 24)?.try_into().ok()?,
+        bit_depth: u32_le(buf, fmt + 32)?.try_into().ok(),
+        samples: u64_le(buf, fmt + 36),
+    })
+}
+
+fn ape(buf: &[u8]) -> Option<AudioInfo> {
+    let version = u16_le(buf, 4)?;
+    if version >= 3980 {
+        // the header follows a descriptor of its own length
+        let header = usize::try_from(u32_le(buf, 8)?).ok()?;
+        let blocks_per_frame = u32_le(buf, header + 4)?;
+        let final_frame_blocks = u32_le(buf, header + 8)?;
+        let frames = u32_le(buf, header + 12)?;
+        Some(AudioInfo {
+            sample_rate: u32_le(buf, header + 20)?,
+            channels: u16_le(buf, header + 18)?,
+            bit_depth: u16_le(buf, header + 16)?.try_into().ok(),
+            samples: ape_samples(frames, blocks_per_frame, final_frame_blocks),
+        })
+    } else {
+        let compression = u16_le(buf, 6)?;
+        let flags = u16_le(buf, 8)?;
+        let blocks_per_frame = if version >= 3950 {
+            73728 * 4
+        } else if version >= 3900 || (version >= 3800 && compression == 4000) {
+            73728
+        } else {
+            9216
+        };
+        let bit_depth = if flags & 0x01 != 0 {
+            8
+        } else if flags & 0x08 != 0 {
+            24
+        } else {
+            16
+        };
+        Some(AudioInfo {
+            sample_rate: u32_le(buf, 12)?,
+            channels: u16_le(buf, 10)?,
+            bit_depth: Some(bit_depth),
+            samples: ape_samples(u32_le(buf, 24)?, blocks_per_frame, u32_le(buf, 28)?),
+        })
+    }
+}
+
+fn ape_samples(frames: u32, blocks_per_frame: u32, final_frame_blocks: u32) -> Option<u64> {
+    let full_frames = u64::from(frames.checked_sub(1)?);
+    Some(full_frames * u64::from(blocks_per_frame) + u64::from(final_frame_blocks))
+}
+
+fn ogg(buf: &[u8]) -> Option<AudioInfo> {
+    // the first packet follows the page header and its segment table
+    let packet = 27 + usize::from(*buf.get(26)?);
+    let header = buf.get(packet..)?;
+    if header.starts_with(b"OpusHead") {
+        // Opus is always decoded at 48 kHz, whatever the input sample rate was
+        Some(AudioInfo {
+            sample_rate: 48000,
+            channels: (*header.get(9)?).into(),
+            bit_depth: None,
+            samples: None,
+        })
+    } else if header.starts_with(b"\x01vorbis") {
+        Some(AudioInfo {
+            sample_rate: u32_le(header, 12)?,
+            channels: (*header.get(11)?).into(),
+            bit_depth: None,
+            samples: None,
+        })
+    } else if header.starts_with(b"\x7FFLAC") {
+        // mapping header, then the native FLAC signature and STREAMINFO
+        flac(header.get(9..)?)
+    } else {
+        None
+    }
+}
+
+fn mp3(buf: &[u8]) -> Option<AudioInfo> {
+    let mut pos = 0;
+    if buf.starts_with(b"ID3") {
+        // syncsafe size, excluding the header and the optional footer
+        let size = buf
+            .get(6..10)?
+            .iter()
+            .fold(0, |size, &b| (size << 7) | usize::from(b & 0x7F));
+        let footer = if buf.get(5)? & 0x10 != 0 { 10 } else { 0 };
+        pos = 10 + size + footer;
+    }
+
+    let header = u32_be(buf, pos)?;
+    if header >> 21 != 0x7FF {
+        return None;
+    }
+    let version = (header >> 19) & 0x03;
+    let layer = (header >> 17) & 0x03;
+    let rate_index = (header >> 10) & 0x03;
+    let mono = (header >> 6) & 0x03 == 0x03;
+    if version == 1 || layer == 0 || rate_index == 3 || (header >> 12) & 0x0F == 0x0F {
+        return None;
+    }
+
+    let sample_rate = [44100, 48000, 32000][rate_index as usize]
+        >> match version {
+            // MPEG 1, 2 and 2.5
+            3 => 0,
+            2 => 1,
+            _ => 2,
+        };
+    let samples_per_frame = match (layer, version) {
+        (3, _) => 384,
+        (2, _) | (1, 3) => 1152,
+        _ => 576,
+    };
+
+    Some(AudioInfo {
+        sample_rate,
+        channels: if mono { 1 } else { 2 },
+        bit_depth: None,
+        samples: mp3_frames(buf, pos, version, mono)
+            .map(|frames| u64::from(frames) * samples_per_frame),
+    })
+}
+
+/// Returns the number of frames from the Xing, Info or VBRI header in the
+/// first frame of an MP3.
+fn mp3_frames(buf: &[u8], frame: usize, version: u32, mono: bool) -> Option<u32> {
+    // the Xing header follows the side information
+    let side_info = match (version == 3, mono) {
+        (true, false) => 32,
+        (true, true) | (false, false) => 17,
+        (false, true) => 9,
+    };
+    let xing = frame + 4 + side_info;
+    if matches!(buf.get(xing..xing + 4)?, b"Xing" | b"Info") {
+        // the frame count is only present if the first flag is set
+        let flags = u32_be(buf, xing + 4)?;
+        return if flags & 0x01 != 0 {
+            u32_be(buf, xing + 8)
+        } else {
+            None
+        };
+    }
+
+    let vbri = frame + 4 + 32;
+    if buf.get(vbri..vbri + 4)? == b"VBRI" {
+        return u32_be(buf, vbri + 14);
+    }
+    None
+}
@@ -1,6 +1,12 @@
+use super::zip::{self, Container};
+
 /// Returns whether a buffer is an ePub.
 #[must_use]
 pub fn is_epub(buf: &[u8]) -> bool {
+    if let Some(container) = zip::inspect(buf) {
+        return container == Some(Container::EPUB);
+    }
+
     buf.len() > 57
         && buf[0] == 0x50
         && buf[1] == 0x4B
@@ -0,0 +1,183 @@
+//! Allocation-free reader of Compound File Binary (OLE2) headers and directories.
+//!
+//! Only the parts needed to classify a compound file are read: the CLSID of the
+//! root entry and the names of the entries of the root storage. Sectors that are
+//! not contained in the buffer are treated as missing.
+//!
+//! See [MS-CFB](https://learn.microsoft.com/en-us/openspecs/windows_protocols/ms-cfb/).
+
+use core::convert::TryInto;
+
+const HEADER_LEN: usize = 512;
+const DIRECTORY_ENTRY_LEN: usize = 128;
+const HEADER_DIFAT_LEN: usize = 109;
+
+const MAX_REGULAR_SECTOR: u32 = 0xFFFF_FFFA;
+const NO_STREAM: u32 = 0xFFFF_FFFF;
+
+const OBJECT_STORAGE: u8 = 1;
+const OBJECT_STREAM: u8 = 2;
+const OBJECT_ROOT: u8 = 5;
+
+/// Maximum depth of the red-black tree of the root storage that is searched.
+const MAX_TREE_DEPTH: usize = 64;
+/// Maximum number of entries of the root storage that are visited.
+const MAX_ROOT_ENTRIES: usize = 4096;
+
+/// A compound file whose header has been validated.
+pub(crate) struct CompoundFile<'a> {
+    buf: &'a [u8],
+    sector_shift: u32,
+    first_directory_sector: u32,
+}
+
+impl<'a> CompoundFile<'a> {
+    pub(crate) fn new(buf: &'a [u8]) -> Option<CompoundFile<'a>> {
+        let header = buf.get(..HEADER_LEN)?;
+        if header[..8] != [0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1]
+            || header[28..30] != [0xFE, 0xFF]
+        {
+            return None;
+        }
+
+        let sector_shift = u32::from(u16_at(header, 30));
+        match (u16_at(header, 26), sector_shift) {
+            (3, 9) | (4, 12) => {}
+            _ => return None,
+        }
+
+        Some(CompoundFile {
+            buf,
+            sector_shift,
+            first_directory_sector: u32_at(header, 48),
+        })
+    }
+
+    /// Returns the CLSID of the root entry, in the byte order of its string representation.
+    pub(crate) fn root_clsid(&self) -> Option<[u8; 16]> {
+        let root = self.directory_entry(0)?;
+        if root[66] != OBJECT_ROOT {
+            return None;
+        }
+
+        let clsid = &root[80..96];
+        let mut uuid = [0; 16];
+        uuid[..4].copy_from_slice(&u32_at(clsid, 0).to_be_bytes());
+        uuid[4..6].copy_from_slice(&u16_at(clsid, 4).to_be_bytes());
+        uuid[6..8].copy_from_slice(&u16_at(clsid, 6).to_be_bytes());
+        uuid[8..].copy_from_slice(&clsid[8..]);
+        Some(uuid)
+    }
+
+    /// Returns whether the root storage contains an entry with the given name,
+    /// compared ASCII case-insensitively.
+    pub(crate) fn has_root_entry(&self, name: &str) -> bool {
+        let Some(root) = self.directory_entry(0) else {
+            return false;
+        };
+
+        let mut stack = [0; MAX_TREE_DEPTH];
+        let mut len = 0;
+        let mut visited = 0;
+        let mut next = u32_at(root, 76);
+
+        loop {
+            // descend to the left as far as possible, then visit and go right
+            while next != NO_STREAM {
+                if len == MAX_TREE_DEPTH {
+                    return false;
+                }
+                stack[len] = next;
+                len += 1;
+                next = match self.directory_entry(next) {
+                    Some(entry) => u32_at(entry, 68),
+                    None => return false,
+                };
+            }
+
+            if len == 0 {
+                return false;
+            }
+            len -= 1;
+            let Some(entry) = self.directory_entry(stack[len]) else {
+                return false;
+            };
+
+            if matches!(entry[66], OBJECT_STORAGE | OBJECT_STREAM) && name_eq(entry, name) {
+                return true;
+            }
+
+            // bounds the walk of malformed, cyclic trees
+            visited += 1;
+            if visited > MAX_ROOT_ENTRIES {
+                return false;
+            }
+            next = u32_at(entry, 72);
+        }
+    }
+
+    fn sector_len(&self) -> usize {
+        1 << self.sector_shift
+    }
+
+    fn sector(&self, sector: u32) -> Option<&'a [u8]> {
+        if sector > MAX_REGULAR_SECTOR {
+            return None;
+        }
+        let start = usize::try_from(sector).ok()?.checked_add(1)? << self.sector_shift;
+        self.buf.get(start..start.checked_add(self.sector_len())?)
+    }
+
+    /// Returns the sector following `sector` in its chain.
+    fn next_sector(&self, sector: u32) -> Option<u32> {
+        let per_sector = u32::try_from(self.sector_len() / 4).ok()?;
+        let fat_index = usize::try_from(sector / per_sector).ok()?;
+        // FAT sectors past the header DIFAT are not looked up.
+        if fat_index >= HEADER_DIFAT_LEN {
+            return None;
+        }
+        let fat_sector = u32_at(self.buf, 76 + fat_index * 4);
+        let fat = self.sector(fat_sector)?;
+        let offset = usize::try_from(sector % per_sector).ok()? * 4;
+        Some(u32_at(fat, offset))
+    }
+
+    fn directory_entry(&self, index: u32) -> Option<&'a [u8]> {
+        let per_sector = self.sector_len() / DIRECTORY_ENTRY_LEN;
+        let index = usize::try_from(index).ok()?;
+        if index >= self.buf.len() / DIRECTORY_ENTRY_LEN {
+            return None;
+        }
+
+        let mut sector = self.first_directory_sector;
+        for _ in 0..index / per_sector {
+            sector = self.next_sector(sector)?;
+        }
+
+        let offset = (index % per_sector) * DIRECTORY_ENTRY_LEN;
+        self.sector(sector)?
+            .get(offset..offset + DIRECTORY_ENTRY_LEN)
+    }
+}
+
+/// Compares the UTF-16 name of a directory entry with an ASCII name.
+fn name_eq(entry: &[u8], name: &str) -> bool {
+    // the length in bytes includes the terminating null character
+    let len = usize::from(u16_at(entry, 64));
+    if len != (name.len() + 1) * 2 || len > 64 {
+        return false;
+    }
+
+    entry[..len - 2]
+        .chunks_exact(2)
+        .zip(name.bytes())
+        .all(|(unit, b)| unit[1] == 0 && unit[0].eq_ignore_ascii_case(&b))
+}
+
+fn u16_at(buf: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes(buf[offset..offset + 2].try_into().unwrap())
+}
+
+fn u32_at(buf: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(buf[offset..offset + 4].try_into().unwrap())
+}
@@ -0,0 +1,269 @@
+//! Delimiter-separated text sniffing.
+//!
+//! The first records of a buffer are parsed with each candidate delimiter,
+//! following the quoting rules of [RFC 4180](https://www.rfc-editor.org/rfc/rfc4180).
+//! A delimiter is accepted if every complete record has the same number of
+//! fields, at least two. The last record may be cut off at the end of the buffer.
+//! Unquoted fields other than numbers must not start with a space after the
+//! delimiter, which rules out prose with a comma on each line.
+
+use super::text::{charset, Charset};
+
+const DELIMITERS: &[u8] = b",\t;|";
+
+/// Maximum number of records that are parsed.
+const MAX_RECORDS: usize = 64;
+/// Maximum number of columns that are examined for a header.
+const MAX_COLUMNS: usize = 64;
+
+/// Dialect of delimiter-separated text, such as CSV or TSV.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct CsvDialect {
+    delimiter: u8,
+    quote: Option<u8>,
+    header: bool,
+}
+
+impl CsvDialect {
+    /// Returns the field delimiter, one of `,`, `\t`, `;` or `|`.
+    #[must_use]
+    pub const fn delimiter(&self) -> u8 {
+        self.delimiter
+    }
+
+    /// Returns the quote character, if any field is quoted.
+    #[must_use]
+    pub const fn quote(&self) -> Option<u8> {
+        self.quote
+    }
+
+    /// Returns whether the first record looks like a header.
+    #[must_use]
+    pub const fn has_header(&self) -> bool {
+        self.header
+    }
+}
+
+/// Number of records and fields per record found with a delimiter.
+struct Shape {
+    records: usize,
+    fields: usize,
+    quoted: bool,
+}
+
+/// Returns the dialect of delimiter-separated text.
+pub(crate) fn dialect(buf: &[u8]) -> Option<CsvDialect> {
+    if !matches!(
+        charset(buf)?,
+        Charset::Ascii | Charset::Utf8 | Charset::Iso8859_1 | Charset::Windows1252
+    ) {
+        return None;
+    }
+    let buf = buf.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(buf);
+
+    // single quotes are only considered if there are no double quotes at all
+    let quote = if buf.contains(&b'"') { b'"' } else { b'\'' };
+
+    let mut best: Option<(u8, Shape)> = None;
+    for &delimiter in DELIMITERS {
+        let Some(shape) = scan(buf, delimiter, quote, |_, _, _, _| {}) else {
+            continue;
+        };
+        if shape.records < 2 || shape.fields < 2 {
+            continue;
+        }
+        // prefer the delimiter splitting records into the most fields
+        if best
+            .as_ref()
+            .map_or(true, |(_, best)| shape.fields > best.fields)
+        {
+            best = Some((delimiter, shape));
+        }
+    }
+
+    let (delimiter, shape) = best?;
+    // prose with a comma on each line
+    if has_spaced_words(buf, delimiter, quote) {
+        return None;
+    }
+    Some(CsvDialect {
+        delimiter,
+        quote: shape.quoted.then_some(quote),
+        header: has_header(buf, delimiter, quote),
+    })
+}
+
+/// Returns whether an unquoted field other than the first of a record starts
+/// with a space, other than before a number.
+fn has_spaced_words(buf: &[u8], delimiter: u8, quote: u8) -> bool {
+    let mut words = false;
+    scan(buf, delimiter, quote, |_, column, field, quoted| {
+        words |= column > 0 && !quoted && field.starts_with(b" ") && !is_number(trim(field));
+    });
+    words
+}
+
+/// Parses the records of a buffer and calls `visit` with the record index,
+/// the column index, the content of each field and whether it is quoted.
+///
+/// Returns `None` if the records have different numbers of fields or if a
+/// quoted field is malformed.
+fn scan<'a>(
+    buf: &'a [u8],
+    delimiter: u8,
+    quote: u8,
+    mut visit: impl FnMut(usize, usize, &'a [u8], bool),
+) -> Option<Shape> {
+    let mut shape = Shape {
+        records: 0,
+        fields: 0,
+        quoted: false,
+    };
+    let mut pos = 0;
+
+    while shape.records < MAX_RECORDS && pos < buf.len() {
+        // blank lines are skipped
+        if matches!(buf[pos], b'\r' | b'\n') {
+            pos += 1;
+            continue;
+        }
+
+        let mut column = 0;
+        let complete = loop {
+            let field;
+            let quoted = buf.get(pos) == Some(&quote);
+            if quoted {
+                let Some(end) = closing_quote(&buf[pos + 1..], quote) else {
+                    // the quoted field is cut off
+                    break false;
+                };
+                field = &buf[pos + 1..pos + 1 + end];
+                pos += end + 2;
+                shape.quoted = true;
+            } else {
+                let end = buf[pos..]
+                    .iter()
+                    .position(|&b| b == delimiter || b == b'\n')
+                    .map_or(buf.len(), |end| pos + end);
+                field = &buf[pos..end];
+                pos = end;
+            }
+
+            visit(
+                shape.records,
+                column,
+                field.strip_suffix(b"\r").unwrap_or(field),
+                quoted,
+            );
+            column += 1;
+
+            match buf.get(pos) {
+                Some(&b) if b == delimiter => pos += 1,
+                Some(b'\n') => {
+                    pos += 1;
+                    break true;
+                }
+                Some(b'\r') if buf.get(pos + 1).map_or(true, |&b| b == b'\n') => {
+                    pos += 2;
+                    break true;
+                }
+                // anything else must not follow a closing quote
+                Some(_) => return None,
+                None => break false,
+            }
+        };
+
+        // a record cut off at the end of the buffer may have fewer fields
+        if shape.records > 0 && column != shape.fields {
+            return (!complete && column < shape.fields).then_some(shape);
+        }
+        shape.fields = column;
+        shape.records += 1;
+        if !complete {
+            break;
+        }
+    }
+    Some(shape)
+}
+
+/// Returns the position of the quote closing a quoted field, skipping
+/// doubled quotes.
+fn closing_quote(buf: &[u8], quote: u8) -> Option<usize> {
+    let mut i = 0;
+    loop {
+        i += buf[i..].iter().position(|&b| b == quote)?;
+        if buf.get(i + 1) != Some(&quote) {
+            return Some(i);
+        }
+        i += 2;
+    }
+}
+
+/// Returns whether the first record is a header: all of its fields are
+/// distinct, non-numeric names, and some column below it is numeric or, in
+/// at least two records, of a fixed width that differs from the name.
+fn has_header(buf: &[u8], delimiter: u8, quote: u8) -> bool {
+    let mut header: [&[u8]; MAX_COLUMNS] = [&[]; MAX_COLUMNS];
+    let mut numeric = [true; MAX_COLUMNS];
+    let mut lengths = [None; MAX_COLUMNS];
+    let mut same_length = [true; MAX_COLUMNS];
+
+    let shape = scan(buf, delimiter, quote, |record, column, field, _| {
+        if column >= MAX_COLUMNS {
+            return;
+        }
+        let field = trim(field);
+        if record == 0 {
+            header[column] = field;
+            return;
+        }
+        numeric[column] &= is_number(field);
+        match lengths[column] {
+            None => lengths[column] = Some(field.len()),
+            Some(len) => same_length[column] &= len == field.len(),
+        }
+    });
+    let Some(shape) = shape else {
+        return false;
+    };
+
+    let header = &header[..shape.fields.min(MAX_COLUMNS)];
+    let names = header
+        .iter()
+        .enumerate()
+        .all(|(i, name)| !name.is_empty() && !is_number(name) && !header[..i].contains(name));
+
+    names
+        && header.iter().enumerate().any(|(i, name)| {
+            numeric[i]
+                || (shape.records > 2
+                    && same_length[i]
+                    && lengths[i].is_some_and(|len| len != name.len()))
+        })
+}
+
+fn is_number(field: &[u8]) -> bool {
+    let digits = field
+        .strip_prefix(b"-")
+        .or_else(|| field.strip_prefix(b"+"))
+        .unwrap_or(field);
+    let mut seen_digit = false;
+    let mut seen_dot = false;
+    for &b in digits {
+        match b {
+            b'0'..=b'9' => seen_digit = true,
+            b'.' if !seen_dot => seen_dot = true,
+            _ => return false,
+        }
+    }
+    seen_digit
+}
+
+fn trim(field: &[u8]) -> &[u8] {
+    let start = field.iter().position(|&b| b != b' ').unwrap_or(field.len());
+    let end = field
+        .iter()
+        .rposition(|&b| b != b' ')
+        .map_or(start, |end| end + 1);
+    &field[start..end]
+}
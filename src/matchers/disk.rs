@@ -0,0 +1,184 @@
+//! Signatures of disk images that live beyond the start of the file.
+//!
+//! ISO 9660 and UDF volumes are recognized by the volume descriptors that
+//! follow the 32 KiB system area, Apple disk images and fixed VHDs by a footer
+//! in their last 512 bytes. The matchers look for them at those offsets of the
+//! buffer, so they need the whole file, or the prefix read by
+//! [`crate::get_from_path`] extended with the following readers.
+
+/// Length of a logical sector of ISO 9660 and UDF volumes.
+const SECTOR_LEN: usize = 2048;
+
+/// Offset of the volume recognition sequence, after the system area.
+const VOLUME_DESCRIPTORS_OFFSET: usize = 16 * SECTOR_LEN;
+
+/// Maximum number of volume descriptors looked at. UDF descriptors follow the
+/// ISO 9660 ones of hybrid discs, e.g. primary, boot record, Joliet and
+/// terminator.
+const MAX_VOLUME_DESCRIPTORS: usize = 16;
+
+/// Length of the footer of Apple disk images and VHDs.
+const FOOTER_LEN: usize = 512;
+
+/// Standard identifiers of the volume structure descriptors, from ECMA-119 and
+/// ECMA-167.
+const ISO_9660: &[u8; 5] = b"CD001";
+const NSR_02: &[u8; 5] = b"NSR02";
+const NSR_03: &[u8; 5] = b"NSR03";
+const IDENTIFIERS: [&[u8; 5]; 7] = [
+    ISO_9660, b"CDW02", b"BEA01", b"BOOT2", NSR_02, NSR_03, b"TEA01",
+];
+
+const KOLY: &[u8; 4] = b"koly";
+const CONECTIX: &[u8; 8] = b"conectix";
+
+/// Returns the identifiers of the volume recognition sequence, which ends at
+/// the first sector without a descriptor.
+fn volume_descriptors(buf: &[u8]) -> impl Iterator<Item = &[u8]> {
+    (0..MAX_VOLUME_DESCRIPTORS)
+        .map(move |i| {
+            let offset = VOLUME_DESCRIPTORS_OFFSET + i * SECTOR_LEN + 1;
+            buf.get(offset..offset + 5)
+        })
+        .take_while(|id| id.is_some_and(is_identifier))
+        .flatten()
+}
+
+fn is_identifier(id: &[u8]) -> bool {
+    IDENTIFIERS.iter().any(|&known| id == known)
+}
+
+/// Returns whether the buffer has an ISO 9660 volume descriptor at the start
+/// of the volume recognition sequence.
+pub(crate) fn is_iso9660(buf: &[u8]) -> bool {
+    volume_descriptors(buf).next() == Some(ISO_9660)
+}
+
+/// Returns whether the volume recognition sequence of the buffer has an NSR
+/// descriptor, which marks a UDF volume.
+pub(crate) fn is_udf(buf: &[u8]) -> bool {
+    volume_descriptors(buf).any(|id| id == NSR_02 || id == NSR_03)
+}
+
+/// Returns the last 512 bytes of the buffer.
+fn footer(buf: &[u8]) -> Option<&[u8]> {
+    buf.get(buf.len().checked_sub(FOOTER_LEN)?..)
+}
+
+/// Returns whether the buffer ends with the `koly` trailer of an Apple disk
+/// image, version 4 with a header length of 512.
+pub(crate) fn is_dmg(buf: &[u8]) -> bool {
+    // http://newosxbook.com/DMG.html
+    footer(buf).is_some_and(|footer| {
+        footer[..4] == *KOLY && footer[4..8] == [0, 0, 0, 4] && footer[8..12] == [0, 0, 2, 0]
+    })
+}
+
+/// Returns whether the buffer starts or ends with a VHD footer.
+///
+/// Dynamic and differencing disks have a copy of the footer at the start,
+/// fixed disks only have the one at the end, which is 511 bytes long in
+/// images created before Virtual PC 2004.
+pub(crate) fn is_vhd(buf: &[u8]) -> bool {
+    buf.starts_with(CONECTIX)
+        || footer(buf)
+            .is_some_and(|footer| footer.starts_with(CONECTIX) || footer[1..].starts_with(CONECTIX))
+}
+
+/// Reads the sectors from the end of the prefix up to the end of the volume
+/// recognition sequence, if the file has one.
+#[cfg(feature = "std")]
+pub(crate) fn read_volume_descriptors<R>(
+    r: &mut R,
+    prefix_len: u64,
+    len: u64,
+) -> std::io::Result<Option<Vec<u8>>>
+where
+    R: std::io::Read + std::io::Seek,
+{
+    use std::io::SeekFrom;
+
+    // the type and identifier of the first descriptor tell whether there is a sequence
+    let start = VOLUME_DESCRIPTORS_OFFSET as u64;
+    let mut header = [0; 6];
+    if prefix_len > start || len < start + header.len() as u64 {
+        return Ok(None);
+    }
+    r.seek(SeekFrom::Start(start))?;
+    r.read_exact(&mut header)?;
+    if !is_identifier(&header[1..]) {
+        return Ok(None);
+    }
+
+    let area_len = (len - start).min((MAX_VOLUME_DESCRIPTORS * SECTOR_LEN) as u64);
+    let mut area = vec![0; usize::try_from(area_len).unwrap_or(0)];
+    area[..header.len()].copy_from_slice(&header);
+    r.read_exact(&mut area[header.len()..])?;
+
+    let mut buf = vec![0; usize::try_from(start - prefix_len).unwrap_or(0)];
+    r.seek(SeekFrom::Start(prefix_len))?;
+    r.read_exact(&mut buf)?;
+    buf.extend_from_slice(&area);
+    Ok(Some(buf))
+}
+
+/// Reads the last 512 bytes of the file, if they are the footer of an Apple
+/// disk image or a VHD.
+#[cfg(feature = "std")]
+pub(crate) fn read_footer<R>(r: &mut R, len: u64) -> std::io::Result<Option<Vec<u8>>>
+where
+    R: std::io::Read + std::io::Seek,
+{
+    use std::io::SeekFrom;
+
+    let Some(start) = len.checked_sub(FOOTER_LEN as u64) else {
+        return Ok(None);
+    };
+    let mut footer = vec![0; FOOTER_LEN];
+    r.seek(SeekFrom::Start(start))?;
+    r.read_exact(&mut footer)?;
+
+    if is_dmg(&footer) || is_vhd(&footer) {
+        Ok(Some(footer))
+    } else {
+        Ok(None)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::{read_volume_descriptors, VOLUME_DESCRIPTORS_OFFSET};
+    use std::io::{self, Cursor, Read, Seek, SeekFrom};
+
+    /// Counts the bytes read from the inner reader.
+    struct Counter<R> {
+        inner: R,
+        read: usize,
+    }
+
+    impl<R: Read> Read for Counter<R> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = self.inner.read(buf)?;
+            self.read += n;
+            Ok(n)
+        }
+    }
+
+    impl<R: Seek> Seek for Counter<R> {
+        fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+            self.inner.seek(pos)
+        }
+    }
+
+    #[test]
+    fn identifier_checked_first() {
+        let buf = vec![0; VOLUME_DESCRIPTORS_OFFSET * 2];
+        let mut r = Counter {
+            inner: Cursor::new(&buf),
+            read: 0,
+        };
+        let got = read_volume_descriptors(&mut r, 8192, buf.len() as u64).unwrap();
+        assert!(got.is_none());
+        assert_eq!(r.read, 6);
+    }
+}
@@ -1,10 +1,12 @@
 use core::convert::TryInto;
 
 use super::compare_bytes;
+use super::compound::CompoundFile;
+use super::zip::{self, Container};
 
 #[allow(clippy::upper_case_acronyms)]
-#[derive(Debug, Eq, PartialEq)]
-enum DocType {
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum DocType {
     DOC,
     DOCX,
     XLS,
@@ -12,6 +14,14 @@ enum DocType {
     PPT,
     PPTX,
     OOXML,
+    MSG,
+    VSD,
+    PUB,
+    MSI,
+    MSP,
+    MST,
+    THUMBS,
+    ENCRYPTED,
 }
 
 /// Returns whether a buffer is Microsoft Word Document (DOC) data.
@@ -50,6 +60,33 @@ pub fn is_pptx(buf: &[u8]) -> bool {
     msooxml(buf) == Some(DocType::PPTX)
 }
 
+/// Returns whether a buffer is Microsoft Outlook Item (MSG) data.
+#[must_use]
+pub fn is_msg(buf: &[u8]) -> bool {
+    ole2(buf) == Some(DocType::MSG)
+}
+
+/// Returns whether a buffer is Microsoft Visio 2003-2010 Drawing (VSD) data.
+#[must_use]
+pub fn is_vsd(buf: &[u8]) -> bool {
+    ole2(buf) == Some(DocType::VSD)
+}
+
+/// Returns whether a buffer is Microsoft Publisher Document (PUB) data.
+#[must_use]
+pub fn is_pub(buf: &[u8]) -> bool {
+    ole2(buf) == Some(DocType::PUB)
+}
+
+/// Returns whether a buffer is a password-encrypted Office Open XML document.
+///
+/// The package is encrypted, so whether it is a DOCX, XLSX or PPTX can not be
+/// told, and the type is the one of encrypted compound files.
+#[must_use]
+pub fn is_encrypted_ooxml(buf: &[u8]) -> bool {
+    ole2(buf) == Some(DocType::ENCRYPTED)
+}
+
 fn msooxml(buf: &[u8]) -> Option<DocType> {
     let signature = [b'P', b'K', 0x03, 0x04];
 
@@ -58,6 +95,16 @@ fn msooxml(buf: &[u8]) -> Option<DocType> {
         return None;
     }
 
+    if let Some(container) = zip::inspect(buf) {
+        return match container {
+            Some(Container::DOCX) => Some(DocType::DOCX),
+            Some(Container::XLSX) => Some(DocType::XLSX),
+            Some(Container::PPTX) => Some(DocType::PPTX),
+            Some(Container::OOXML) => Some(DocType::OOXML),
+            _ => None,
+        };
+    }
+
     let v = check_msooml(buf, 0x1E);
     if v.is_some() {
         return v;
@@ -105,32 +152,115 @@ fn msooxml(buf: &[u8]) -> Option<DocType> {
     Some(DocType::OOXML)
 }
 
+/// Returns whether a buffer starts with a Compound File Binary header.
+pub(crate) fn is_ole2(buf: &[u8]) -> bool {
+    compare_bytes(buf, &[0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1], 0)
+}
+
 #[cfg(feature = "std")]
-fn ole2(buf: &[u8]) -> Option<DocType> {
+pub(crate) fn ole2(buf: &[u8]) -> Option<DocType> {
     use std::io::Cursor;
 
-    if !compare_bytes(buf, &[0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1], 0) {
+    if !is_ole2(buf) {
         return None;
     }
     if let Ok(file) = cfb::CompoundFile::open(Cursor::new(buf)) {
-        return match file.root_entry().clsid().to_string().as_str() {
-            "00020810-0000-0000-c000-000000000046" | "00020820-0000-0000-c000-000000000046" => {
-                Some(DocType::XLS)
-            }
-            "00020906-0000-0000-c000-000000000046" => Some(DocType::DOC),
-            "64818d10-4f9b-11cf-86ea-00aa00b929e8" => Some(DocType::PPT),
-            _ => None,
-        };
+        let clsid = *file.root_entry().clsid().as_bytes();
+        return classify_ole2(&clsid, |name| {
+            file.read_root_storage()
+                .any(|entry| entry.name().eq_ignore_ascii_case(name))
+        });
     }
-    None
+    // The buffer is usually a prefix of the file, which may still contain the directory.
+    compound_ole2(buf)
 }
 
 #[cfg(not(feature = "std"))]
-fn ole2(buf: &[u8]) -> Option<DocType> {
-    if !compare_bytes(buf, &[0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1], 0) {
-        return None;
+pub(crate) fn ole2(buf: &[u8]) -> Option<DocType> {
+    compound_ole2(buf)
+}
+
+fn compound_ole2(buf: &[u8]) -> Option<DocType> {
+    let file = CompoundFile::new(buf)?;
+    classify_ole2(&file.root_clsid()?, |name| file.has_root_entry(name))
+}
+
+/// Builds a CLSID in the byte order of its string representation.
+const fn clsid(data1: u32, data2: u16, data3: u16, data4: [u8; 8]) -> [u8; 16] {
+    let a = data1.to_be_bytes();
+    let b = data2.to_be_bytes();
+    let c = data3.to_be_bytes();
+    let d = data4;
+    [
+        a[0], a[1], a[2], a[3], b[0], b[1], c[0], c[1], d[0], d[1], d[2], d[3], d[4], d[5], d[6],
+        d[7],
+    ]
+}
+
+const OLE: [u8; 8] = [0xC0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46];
+
+const CLSIDS: &[([u8; 16], DocType)] = &[
+    (clsid(0x0002_0810, 0, 0, OLE), DocType::XLS),
+    (clsid(0x0002_0820, 0, 0, OLE), DocType::XLS),
+    (clsid(0x0002_0906, 0, 0, OLE), DocType::DOC),
+    (
+        clsid(
+            0x6481_8D10,
+            0x4F9B,
+            0x11CF,
+            [0x86, 0xEA, 0x00, 0xAA, 0x00, 0xB9, 0x29, 0xE8],
+        ),
+        DocType::PPT,
+    ),
+    (clsid(0x0002_0D0B, 0, 0, OLE), DocType::MSG),
+    (clsid(0x0002_1A13, 0, 0, OLE), DocType::VSD),
+    (clsid(0x0002_1A14, 0, 0, OLE), DocType::VSD),
+    (
+        clsid(
+            0x0002_1201,
+            0,
+            0,
+            [0x00, 0xC0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46],
+        ),
+        DocType::PUB,
+    ),
+    (clsid(0x000C_1084, 0, 0, OLE), DocType::MSI),
+    (clsid(0x000C_1086, 0, 0, OLE), DocType::MSP),
+    (clsid(0x000C_1082, 0, 0, OLE), DocType::MST),
+];
+
+const STREAMS: &[(&str, DocType)] = &[
+    ("WordDocument", DocType::DOC),
+    ("Workbook", DocType::XLS),
+    ("Book", DocType::XLS),
+    ("PowerPoint Document", DocType::PPT),
+    ("__properties_version1.0", DocType::MSG),
+    ("VisioDocument", DocType::VSD),
+    ("Quill", DocType::PUB),
+    ("Catalog", DocType::THUMBS),
+];
+
+/// Classifies a compound file by the CLSID of its root entry, falling back
+/// to the names of the entries of the root storage.
+fn classify_ole2<F>(clsid: &[u8; 16], has_entry: F) -> Option<DocType>
+where
+    F: Fn(&str) -> bool,
+{
+    // Encrypted OOXML packages are wrapped in a compound file without a CLSID.
+    if has_entry("EncryptionInfo") && has_entry("EncryptedPackage") {
+        return Some(DocType::ENCRYPTED);
     }
-    Some(DocType::DOC)
+
+    CLSIDS
+        .iter()
+        .find(|(id, _)| id == clsid)
+        .map(|(_, kind)| *kind)
+        .or_else(|| {
+            STREAMS
+                .iter()
+                .find(|(name, _)| has_entry(name))
+                .map(|(_, kind)| *kind)
+        })
 }
 
 fn check_msooml(buf: &[u8], offset: usize) -> Option<DocType> {
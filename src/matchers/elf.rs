@@ -0,0 +1,412 @@
+//! Executable and Linkable Format (ELF) header parsing.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use infer::elf::{self, Class, Endianness, Machine, ObjectType};
+//!
+//! let buf = include_bytes!("../../testdata/sample_elf");
+//! let elf = elf::info(buf).expect("ELF header is valid");
+//!
+//! assert_eq!(elf.class(), Class::Elf64);
+//! assert_eq!(elf.endianness(), Endianness::Little);
+//! assert_eq!(elf.machine(), Machine::X86_64);
+//! assert_eq!(elf.object_type(), ObjectType::Executable);
+//! ```
+
+use core::convert::TryInto;
+
+const PT_DYNAMIC: u32 = 2;
+const PT_INTERP: u32 = 3;
+const DT_NULL: u64 = 0;
+const DT_SONAME: u64 = 14;
+const DT_FLAGS_1: u64 = 0x6FFF_FFFB;
+const DF_1_PIE: u64 = 0x0800_0000;
+
+/// Maximum number of program headers and dynamic entries that are read.
+const MAX_ENTRIES: usize = 256;
+
+/// Information from the header of an ELF file.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Elf {
+    class: Class,
+    endianness: Endianness,
+    os_abi: OsAbi,
+    machine: Machine,
+    object_type: ObjectType,
+}
+
+impl Elf {
+    /// Returns whether the file is 32 or 64-bit.
+    #[must_use]
+    pub const fn class(&self) -> Class {
+        self.class
+    }
+
+    /// Returns the byte order of the file.
+    #[must_use]
+    pub const fn endianness(&self) -> Endianness {
+        self.endianness
+    }
+
+    /// Returns the operating system ABI the file targets.
+    #[must_use]
+    pub const fn os_abi(&self) -> OsAbi {
+        self.os_abi
+    }
+
+    /// Returns the instruction set architecture of the file.
+    #[must_use]
+    pub const fn machine(&self) -> Machine {
+        self.machine
+    }
+
+    /// Returns the kind of object file.
+    #[must_use]
+    pub const fn object_type(&self) -> ObjectType {
+        self.object_type
+    }
+}
+
+/// Word size of an ELF file.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Class {
+    /// 32-bit.
+    Elf32,
+    /// 64-bit.
+    Elf64,
+}
+
+/// Byte order of an ELF file.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Endianness {
+    /// Little endian.
+    Little,
+    /// Big endian.
+    Big,
+}
+
+/// Operating system ABI, from the `EI_OSABI` byte.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum OsAbi {
+    /// UNIX System V, also used by most Linux binaries.
+    SystemV,
+    /// HP-UX.
+    HpUx,
+    /// NetBSD.
+    NetBsd,
+    /// GNU/Linux.
+    Linux,
+    /// Solaris.
+    Solaris,
+    /// AIX.
+    Aix,
+    /// IRIX.
+    Irix,
+    /// FreeBSD.
+    FreeBsd,
+    /// OpenBSD.
+    OpenBsd,
+    /// ARM EABI.
+    ArmEabi,
+    /// Standalone (embedded) application.
+    Standalone,
+    /// Any other value.
+    Other(u8),
+}
+
+impl OsAbi {
+    fn from_byte(b: u8) -> OsAbi {
+        match b {
+            0 => OsAbi::SystemV,
+            1 => OsAbi::HpUx,
+            2 => OsAbi::NetBsd,
+            3 => OsAbi::Linux,
+            6 => OsAbi::Solaris,
+            7 => OsAbi::Aix,
+            8 => OsAbi::Irix,
+            9 => OsAbi::FreeBsd,
+            12 => OsAbi::OpenBsd,
+            64 => OsAbi::ArmEabi,
+            255 => OsAbi::Standalone,
+            other => OsAbi::Other(other),
+        }
+    }
+}
+
+/// Instruction set architecture, from the `e_machine` field.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Machine {
+    /// No machine.
+    None,
+    /// SPARC.
+    Sparc,
+    /// Intel 80386.
+    X86,
+    /// Motorola 68000.
+    M68k,
+    /// MIPS.
+    Mips,
+    /// PowerPC.
+    PowerPc,
+    /// 64-bit PowerPC.
+    PowerPc64,
+    /// IBM System/390 and z/Architecture.
+    S390,
+    /// ARM, 32-bit.
+    Arm,
+    /// SuperH.
+    SuperH,
+    /// SPARC V9, 64-bit.
+    Sparcv9,
+    /// Intel Itanium.
+    Ia64,
+    /// AMD x86-64.
+    X86_64,
+    /// ARM AArch64.
+    Aarch64,
+    /// RISC-V.
+    RiscV,
+    /// Linux BPF.
+    Bpf,
+    /// LoongArch.
+    LoongArch,
+    /// Any other value.
+    Other(u16),
+}
+
+impl Machine {
+    fn from_u16(value: u16) -> Machine {
+        match value {
+            0 => Machine::None,
+            2 => Machine::Sparc,
+            3 => Machine::X86,
+            4 => Machine::M68k,
+            8 => Machine::Mips,
+            20 => Machine::PowerPc,
+            21 => Machine::PowerPc64,
+            22 => Machine::S390,
+            40 => Machine::Arm,
+            42 => Machine::SuperH,
+            43 => Machine::Sparcv9,
+            50 => Machine::Ia64,
+            62 => Machine::X86_64,
+            183 => Machine::Aarch64,
+            243 => Machine::RiscV,
+            247 => Machine::Bpf,
+            258 => Machine::LoongArch,
+            other => Machine::Other(other),
+        }
+    }
+
+    /// Returns a short name of the architecture, e.g. `x86_64` or `aarch64`.
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Machine::None => "none",
+            Machine::Sparc => "sparc",
+            Machine::X86 => "x86",
+            Machine::M68k => "m68k",
+            Machine::Mips => "mips",
+            Machine::PowerPc => "powerpc",
+            Machine::PowerPc64 => "powerpc64",
+            Machine::S390 => "s390",
+            Machine::Arm => "arm",
+            Machine::SuperH => "sh",
+            Machine::Sparcv9 => "sparcv9",
+            Machine::Ia64 => "ia64",
+            Machine::X86_64 => "x86_64",
+            Machine::Aarch64 => "aarch64",
+            Machine::RiscV => "riscv",
+            Machine::Bpf => "bpf",
+            Machine::LoongArch => "loongarch",
+            Machine::Other(_) => "unknown",
+        }
+    }
+}
+
+/// Kind of ELF object file, from the `e_type` field.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ObjectType {
+    /// Relocatable object file (`ET_REL`), e.g. `.o`.
+    Relocatable,
+    /// Executable at a fixed address (`ET_EXEC`).
+    Executable,
+    /// Position-independent executable (`ET_DYN` with an interpreter or the
+    /// `DF_1_PIE` flag).
+    PieExecutable,
+    /// Shared library (other `ET_DYN` files).
+    SharedLibrary,
+    /// Core dump (`ET_CORE`).
+    Core,
+    /// Any other value, e.g. processor or OS specific ones.
+    Other(u16),
+}
+
+/// Returns the information from the header of an ELF file.
+///
+/// Telling position-independent executables from shared libraries requires
+/// the program headers, and possibly the dynamic section, to be within the buffer.
+/// Otherwise `ET_DYN` files are reported as shared libraries.
+#[must_use]
+pub fn info(buf: &[u8]) -> Option<Elf> {
+    if buf.len() < 52 || buf[..4] != *b"\x7FELF" {
+        return None;
+    }
+
+    let class = match buf[4] {
+        1 => Class::Elf32,
+        2 => Class::Elf64,
+        _ => return None,
+    };
+    let endianness = match buf[5] {
+        1 => Endianness::Little,
+        2 => Endianness::Big,
+        _ => return None,
+    };
+    let reader = Reader {
+        buf,
+        class,
+        endianness,
+    };
+
+    let object_type = match reader.u16(16)? {
+        1 => ObjectType::Relocatable,
+        2 => ObjectType::Executable,
+        3 if reader.is_pie() => ObjectType::PieExecutable,
+        3 => ObjectType::SharedLibrary,
+        4 => ObjectType::Core,
+        other => ObjectType::Other(other),
+    };
+
+    Some(Elf {
+        class,
+        endianness,
+        os_abi: OsAbi::from_byte(buf[7]),
+        machine: Machine::from_u16(reader.u16(18)?),
+        object_type,
+    })
+}
+
+struct Reader<'a> {
+    buf: &'a [u8],
+    class: Class,
+    endianness: Endianness,
+}
+
+impl Reader<'_> {
+    fn u16(&self, offset: usize) -> Option<u16> {
+        let bytes = self
+            .buf
+            .get(offset..offset.checked_add(2)?)?
+            .try_into()
+            .ok()?;
+        Some(match self.endianness {
+            Endianness::Little => u16::from_le_bytes(bytes),
+            Endianness::Big => u16::from_be_bytes(bytes),
+        })
+    }
+
+    fn u32(&self, offset: usize) -> Option<u32> {
+        let bytes = self
+            .buf
+            .get(offset..offset.checked_add(4)?)?
+            .try_into()
+            .ok()?;
+        Some(match self.endianness {
+            Endianness::Little => u32::from_le_bytes(bytes),
+            Endianness::Big => u32::from_be_bytes(bytes),
+        })
+    }
+
+    fn u64(&self, offset: usize) -> Option<u64> {
+        let bytes = self
+            .buf
+            .get(offset..offset.checked_add(8)?)?
+            .try_into()
+            .ok()?;
+        Some(match self.endianness {
+            Endianness::Little => u64::from_le_bytes(bytes),
+            Endianness::Big => u64::from_be_bytes(bytes),
+        })
+    }
+
+    /// Reads an address or offset sized word.
+    fn word(&self, offset: usize) -> Option<usize> {
+        match self.class {
+            Class::Elf32 => self.u32(offset)?.try_into().ok(),
+            Class::Elf64 => self.u64(offset)?.try_into().ok(),
+        }
+    }
+
+    /// Returns whether an `ET_DYN` file is an executable: it is flagged as PIE in
+    /// its dynamic section, or it has an interpreter and no `DT_SONAME`. Shared
+    /// libraries such as `libc.so.6` may have an interpreter too. If the dynamic
+    /// section is past the end of the buffer, the interpreter alone decides.
+    fn is_pie(&self) -> bool {
+        let (phoff, phentsize, phnum) = match self.class {
+            Class::Elf32 => (self.word(28), self.u16(42), self.u16(44)),
+            Class::Elf64 => (self.word(32), self.u16(54), self.u16(56)),
+        };
+        let (Some(phoff), Some(phentsize), Some(phnum)) = (phoff, phentsize, phnum) else {
+            return false;
+        };
+
+        let mut interp = false;
+        let mut dynamic = None;
+        for i in 0..usize::from(phnum).min(MAX_ENTRIES) {
+            let Some(header) = phoff.checked_add(i * usize::from(phentsize)) else {
+                return false;
+            };
+            match self.u32(header) {
+                Some(PT_INTERP) => interp = true,
+                Some(PT_DYNAMIC) => dynamic = Some(header),
+                Some(_) => {}
+                None => break,
+            }
+        }
+
+        let field = match self.class {
+            Class::Elf32 => 4,
+            Class::Elf64 => 8,
+        };
+        let offset = dynamic.and_then(|header| self.word(header.checked_add(field)?));
+        let (pie_flag, soname) = offset.map_or((false, false), |offset| self.dynamic_flags(offset));
+        pie_flag || (interp && !soname)
+    }
+
+    /// Returns whether the dynamic section at `offset` has the `DF_1_PIE` flag,
+    /// and whether it has a `DT_SONAME` entry.
+    fn dynamic_flags(&self, offset: usize) -> (bool, bool) {
+        let entry_len = match self.class {
+            Class::Elf32 => 8,
+            Class::Elf64 => 16,
+        };
+        let (mut pie_flag, mut soname) = (false, false);
+        for i in 0..MAX_ENTRIES {
+            let Some(entry) = offset.checked_add(i * entry_len) else {
+                break;
+            };
+            let (tag, value) = match self.class {
+                Class::Elf32 => (
+                    self.u32(entry).map(u64::from),
+                    entry
+                        .checked_add(4)
+                        .and_then(|value| self.u32(value))
+                        .map(u64::from),
+                ),
+                Class::Elf64 => (
+                    self.u64(entry),
+                    entry.checked_add(8).and_then(|value| self.u64(value)),
+                ),
+            };
+            match (tag, value) {
+                (Some(DT_NULL), _) | (None, _) | (_, None) => break,
+                (Some(DT_FLAGS_1), Some(flags)) => pie_flag = flags & DF_1_PIE != 0,
+                (Some(DT_SONAME), _) => soname = true,
+                _ => {}
+            }
+        }
+        (pie_flag, soname)
+    }
+}
@@ -0,0 +1,632 @@
+//! File system superblock detection, for raw partition images.
+//!
+//! File systems are recognized by the magic of their superblock or boot
+//! sector, which is at a fixed offset from the start of the partition, and
+//! checked for sane values to rule out other files with the same bytes.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use infer::filesystem::{self, Kind};
+//!
+//! let buf = include_bytes!("../../testdata/sample.ext4");
+//! assert!(filesystem::is_ext4(buf));
+//!
+//! let superblock = filesystem::info(buf).expect("superblock is valid");
+//! assert_eq!(superblock.kind(), Kind::Ext4);
+//! assert_eq!(superblock.block_size(), Some(1024));
+//! ```
+
+use core::convert::TryInto;
+
+use super::{u16_be, u16_le, u32_be, u32_le, u64_be, u64_le};
+
+/// Offset of the superblock of ext2/3/4, EROFS, F2FS and HFS+, after the boot
+/// block.
+const SUPERBLOCK_OFFSET: usize = 1024;
+
+const EXT_MAGIC: [u8; 2] = [0x53, 0xEF];
+const EXT_COMPAT_HAS_JOURNAL: u32 = 0x0004;
+/// Incompatible features supported by ext3: file type in directory entries,
+/// journal recovery needed and meta block groups.
+const EXT3_INCOMPAT: u32 = 0x0002 | 0x0004 | 0x0010;
+/// Read-only compatible features supported by ext3: sparse superblocks, large
+/// files and B-tree directories.
+const EXT3_RO_COMPAT: u32 = 0x0001 | 0x0002 | 0x0004;
+
+/// Offset of the primary Btrfs superblock.
+const BTRFS_OFFSET: usize = 0x10000;
+/// Length of the Btrfs superblock fields that are read.
+#[cfg(feature = "std")]
+const BTRFS_LEN: usize = 0x98;
+
+/// Offset of the configuration of the first ZFS vdev label, an XDR encoded
+/// name-value list.
+const ZFS_NVLIST_OFFSET: usize = 0x4000;
+/// Length of the list header and its first pair, the pool version.
+#[cfg(feature = "std")]
+const ZFS_NVLIST_LEN: usize = 48;
+
+/// Kind of file system, or of encrypted volume for LUKS.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Kind {
+    /// Second extended file system.
+    Ext2,
+    /// ext3, ext2 with a journal.
+    Ext3,
+    /// ext4, with features not supported by ext3, e.g. extents.
+    Ext4,
+    /// XFS.
+    Xfs,
+    /// Btrfs.
+    Btrfs,
+    /// SquashFS.
+    SquashFs,
+    /// Enhanced Read-Only File System.
+    Erofs,
+    /// Flash-Friendly File System.
+    F2fs,
+    /// FAT with 12-bit cluster numbers.
+    Fat12,
+    /// FAT with 16-bit cluster numbers.
+    Fat16,
+    /// FAT with 32-bit cluster numbers.
+    Fat32,
+    /// exFAT.
+    ExFat,
+    /// NTFS.
+    Ntfs,
+    /// HFS+, including its case-sensitive variant HFSX.
+    HfsPlus,
+    /// Apple File System container.
+    Apfs,
+    /// ZFS vdev.
+    Zfs,
+    /// LUKS encrypted volume.
+    Luks,
+}
+
+impl Kind {
+    /// Returns a short name of the file system, e.g. `ext4` or `vfat`, as used
+    /// by `mount`.
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Kind::Ext2 => "ext2",
+            Kind::Ext3 => "ext3",
+            Kind::Ext4 => "ext4",
+            Kind::Xfs => "xfs",
+            Kind::Btrfs => "btrfs",
+            Kind::SquashFs => "squashfs",
+            Kind::Erofs => "erofs",
+            Kind::F2fs => "f2fs",
+            Kind::Fat12 | Kind::Fat16 | Kind::Fat32 => "vfat",
+            Kind::ExFat => "exfat",
+            Kind::Ntfs => "ntfs",
+            Kind::HfsPlus => "hfsplus",
+            Kind::Apfs => "apfs",
+            Kind::Zfs => "zfs",
+            Kind::Luks => "crypto_LUKS",
+        }
+    }
+}
+
+/// Information from the superblock of a file system.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Superblock {
+    kind: Kind,
+    version: Option<(u32, u32)>,
+    block_size: Option<u32>,
+}
+
+impl Superblock {
+    /// Returns the kind of file system.
+    #[must_use]
+    pub const fn kind(&self) -> Kind {
+        self.kind
+    }
+
+    /// Returns the major and minor version of the on-disk format, for the file
+    /// systems that record one in their superblock:
+    ///
+    /// - ext2/3/4: revision level, e.g. `(1, 0)`
+    /// - XFS: superblock version, `(4, 0)` or `(5, 0)`
+    /// - SquashFS, F2FS and exFAT: e.g. `(4, 0)`, `(1, 16)` and `(1, 0)`
+    /// - HFS+: `(4, 0)`, or `(5, 0)` for HFSX
+    /// - ZFS: pool version, `(5000, 0)` for pools with feature flags
+    /// - LUKS: `(1, 0)` or `(2, 0)`
+    #[must_use]
+    pub const fn version(&self) -> Option<(u32, u32)> {
+        self.version
+    }
+
+    /// Returns the size of a block, or of a cluster for FAT, exFAT and NTFS,
+    /// in bytes.
+    #[must_use]
+    pub const fn block_size(&self) -> Option<u32> {
+        self.block_size
+    }
+}
+
+/// Returns the information from the superblock of a file system.
+///
+/// The Btrfs superblock is at 64 KiB and the ZFS label at 16 KiB, see
+/// [`crate::get_from_path`] for how they are read from files.
+#[must_use]
+pub fn info(buf: &[u8]) -> Option<Superblock> {
+    ext(buf)
+        .or_else(|| xfs(buf))
+        .or_else(|| btrfs(buf))
+        .or_else(|| squashfs(buf))
+        .or_else(|| erofs(buf))
+        .or_else(|| f2fs(buf))
+        .or_else(|| exfat(buf))
+        .or_else(|| ntfs(buf))
+        .or_else(|| fat(buf))
+        .or_else(|| hfs_plus(buf))
+        .or_else(|| apfs(buf))
+        .or_else(|| zfs(buf))
+        .or_else(|| luks(buf))
+}
+
+/// Returns whether a buffer is an ext2 file system.
+#[must_use]
+pub fn is_ext2(buf: &[u8]) -> bool {
+    ext(buf).is_some_and(|sb| sb.kind == Kind::Ext2)
+}
+
+/// Returns whether a buffer is an ext3 file system.
+#[must_use]
+pub fn is_ext3(buf: &[u8]) -> bool {
+    ext(buf).is_some_and(|sb| sb.kind == Kind::Ext3)
+}
+
+/// Returns whether a buffer is an ext4 file system.
+#[must_use]
+pub fn is_ext4(buf: &[u8]) -> bool {
+    ext(buf).is_some_and(|sb| sb.kind == Kind::Ext4)
+}
+
+/// Returns whether a buffer is an XFS file system.
+#[must_use]
+pub fn is_xfs(buf: &[u8]) -> bool {
+    xfs(buf).is_some()
+}
+
+/// Returns whether a buffer is a Btrfs file system.
+#[must_use]
+pub fn is_btrfs(buf: &[u8]) -> bool {
+    btrfs(buf).is_some()
+}
+
+/// Returns whether a buffer is a SquashFS file system.
+#[must_use]
+pub fn is_squashfs(buf: &[u8]) -> bool {
+    squashfs(buf).is_some()
+}
+
+/// Returns whether a buffer is an EROFS file system.
+#[must_use]
+pub fn is_erofs(buf: &[u8]) -> bool {
+    erofs(buf).is_some()
+}
+
+/// Returns whether a buffer is an F2FS file system.
+#[must_use]
+pub fn is_f2fs(buf: &[u8]) -> bool {
+    f2fs(buf).is_some()
+}
+
+/// Returns whether a buffer is a FAT12 file system.
+#[must_use]
+pub fn is_fat12(buf: &[u8]) -> bool {
+    fat(buf).is_some_and(|sb| sb.kind == Kind::Fat12)
+}
+
+/// Returns whether a buffer is a FAT16 file system.
+#[must_use]
+pub fn is_fat16(buf: &[u8]) -> bool {
+    fat(buf).is_some_and(|sb| sb.kind == Kind::Fat16)
+}
+
+/// Returns whether a buffer is a FAT32 file system.
+#[must_use]
+pub fn is_fat32(buf: &[u8]) -> bool {
+    fat(buf).is_some_and(|sb| sb.kind == Kind::Fat32)
+}
+
+/// Returns whether a buffer is an exFAT file system.
+#[must_use]
+pub fn is_exfat(buf: &[u8]) -> bool {
+    exfat(buf).is_some()
+}
+
+/// Returns whether a buffer is an NTFS file system.
+#[must_use]
+pub fn is_ntfs(buf: &[u8]) -> bool {
+    ntfs(buf).is_some()
+}
+
+/// Returns whether a buffer is an HFS+ or HFSX file system.
+#[must_use]
+pub fn is_hfs_plus(buf: &[u8]) -> bool {
+    hfs_plus(buf).is_some()
+}
+
+/// Returns whether a buffer is an APFS container.
+#[must_use]
+pub fn is_apfs(buf: &[u8]) -> bool {
+    apfs(buf).is_some()
+}
+
+/// Returns whether a buffer is a ZFS vdev, from the configuration of its
+/// first label.
+#[must_use]
+pub fn is_zfs(buf: &[u8]) -> bool {
+    zfs(buf).is_some()
+}
+
+/// Returns whether a buffer is a LUKS encrypted volume.
+#[must_use]
+pub fn is_luks(buf: &[u8]) -> bool {
+    luks(buf).is_some()
+}
+
+fn ext(buf: &[u8]) -> Option<Superblock> {
+    // https://www.kernel.org/doc/html/latest/filesystems/ext4/super.html
+    let sb = buf.get(SUPERBLOCK_OFFSET..SUPERBLOCK_OFFSET + 0x68)?;
+    if sb[0x38..0x3A] != EXT_MAGIC {
+        return None;
+    }
+    let log_block_size = u32_le(sb, 0x18)?;
+    let blocks_per_group = u32_le(sb, 0x20)?;
+    let inodes_per_group = u32_le(sb, 0x28)?;
+    let rev_level = u32_le(sb, 0x4C)?;
+    if log_block_size > 6 || blocks_per_group == 0 || inodes_per_group == 0 || rev_level > 1 {
+        return None;
+    }
+
+    let compat = u32_le(sb, 0x5C)?;
+    let incompat = u32_le(sb, 0x60)?;
+    let ro_compat = u32_le(sb, 0x64)?;
+    let kind = if incompat & !EXT3_INCOMPAT != 0 || ro_compat & !EXT3_RO_COMPAT != 0 {
+        Kind::Ext4
+    } else if compat & EXT_COMPAT_HAS_JOURNAL != 0 {
+        Kind::Ext3
+    } else {
+        Kind::Ext2
+    };
+
+    Some(Superblock {
+        kind,
+        version: Some((rev_level, u32::from(u16_le(sb, 0x3E)?))),
+        block_size: Some(1024 << log_block_size),
+    })
+}
+
+fn xfs(buf: &[u8]) -> Option<Superblock> {
+    // https://git.kernel.org/pub/scm/fs/xfs/xfs-documentation.git
+    if !buf.starts_with(b"XFSB") {
+        return None;
+    }
+    let block_size = u32_be(buf, 4)?;
+    let version = u16_be(buf, 0x64)? & 0x000F;
+    if !is_block_size(block_size, 512, 65536) || !(1..=5).contains(&version) {
+        return None;
+    }
+
+    Some(Superblock {
+        kind: Kind::Xfs,
+        version: Some((u32::from(version), 0)),
+        block_size: Some(block_size),
+    })
+}
+
+fn btrfs(buf: &[u8]) -> Option<Superblock> {
+    btrfs_superblock(buf.get(BTRFS_OFFSET..)?)
+}
+
+fn btrfs_superblock(sb: &[u8]) -> Option<Superblock> {
+    // https://btrfs.readthedocs.io/en/latest/dev/On-disk-format.html
+    // the magic, and the superblock's own offset
+    if sb.get(0x40..0x48)? != b"_BHRfS_M" || u64_le(sb, 0x30)? != BTRFS_OFFSET as u64 {
+        return None;
+    }
+    let sector_size = u32_le(sb, 0x90)?;
+    if !is_block_size(sector_size, 512, 65536) {
+        return None;
+    }
+
+    Some(Superblock {
+        kind: Kind::Btrfs,
+        version: None,
+        block_size: Some(sector_size),
+    })
+}
+
+fn squashfs(buf: &[u8]) -> Option<Superblock> {
+    // https://dr-emann.github.io/squashfs/
+    // Version 4 is always little endian, earlier versions are in the byte
+    // order of the host that created them.
+    let (block_size, major, minor) = match buf.get(..4)? {
+        b"hsqs" => (u32_le(buf, 12)?, u16_le(buf, 28)?, u16_le(buf, 30)?),
+        b"sqsh" => (u32_be(buf, 12)?, u16_be(buf, 28)?, u16_be(buf, 30)?),
+        _ => return None,
+    };
+    if !(1..=4).contains(&major) {
+        return None;
+    }
+
+    Some(Superblock {
+        kind: Kind::SquashFs,
+        version: Some((u32::from(major), u32::from(minor))),
+        // version 4 block size, the earlier ones have a 16-bit block size here
+        block_size: (major == 4).then_some(block_size),
+    })
+}
+
+fn erofs(buf: &[u8]) -> Option<Superblock> {
+    // https://docs.kernel.org/filesystems/erofs.html
+    if u32_le(buf, SUPERBLOCK_OFFSET)? != 0xE0F5_E1E2 {
+        return None;
+    }
+    let block_size_bits = *buf.get(SUPERBLOCK_OFFSET + 0x0C)?;
+    if !(9..=16).contains(&block_size_bits) {
+        return None;
+    }
+
+    Some(Superblock {
+        kind: Kind::Erofs,
+        version: None,
+        block_size: Some(1 << block_size_bits),
+    })
+}
+
+fn f2fs(buf: &[u8]) -> Option<Superblock> {
+    // https://docs.kernel.org/filesystems/f2fs.html
+    let sb = buf.get(SUPERBLOCK_OFFSET..)?;
+    if u32_le(sb, 0)? != 0xF2F5_2010 {
+        return None;
+    }
+    let log_block_size = u32_le(sb, 0x10)?;
+    if !(9..=16).contains(&log_block_size) {
+        return None;
+    }
+
+    Some(Superblock {
+        kind: Kind::F2fs,
+        version: Some((u32::from(u16_le(sb, 4)?), u32::from(u16_le(sb, 6)?))),
+        block_size: Some(1 << log_block_size),
+    })
+}
+
+/// Returns whether the buffer starts with the jump instruction of a boot
+/// sector and ends the sector with the boot signature.
+fn is_boot_sector(buf: &[u8]) -> bool {
+    buf.len() >= 512
+        && ((buf[0] == 0xEB && buf[2] == 0x90) || buf[0] == 0xE9)
+        && buf[510..512] == [0x55, 0xAA]
+}
+
+fn exfat(buf: &[u8]) -> Option<Superblock> {
+    // https://learn.microsoft.com/en-us/windows/win32/fileio/exfat-specification
+    if !is_boot_sector(buf) || buf[3..11] != *b"EXFAT   " {
+        return None;
+    }
+    let (sector_shift, cluster_shift) = (u32::from(buf[0x6C]), u32::from(buf[0x6D]));
+    if !(9..=12).contains(&sector_shift) || sector_shift + cluster_shift > 25 {
+        return None;
+    }
+
+    Some(Superblock {
+        kind: Kind::ExFat,
+        version: Some((u32::from(buf[0x69]), u32::from(buf[0x68]))),
+        block_size: Some(1 << (sector_shift + cluster_shift)),
+    })
+}
+
+fn ntfs(buf: &[u8]) -> Option<Superblock> {
+    // https://flatcap.github.io/linux-ntfs/ntfs/files/boot.html
+    if !is_boot_sector(buf) || buf[3..11] != *b"NTFS    " {
+        return None;
+    }
+    let sector_size = u32::from(u16_le(buf, 0x0B)?);
+    // clusters larger than 64 KiB are stored as a negative power of two
+    let sectors_per_cluster = match buf[0x0D] {
+        n @ 0..=0x80 => u32::from(n),
+        n => 1u32.checked_shl(u32::from(n.wrapping_neg()))?,
+    };
+    if !is_block_size(sector_size, 256, 4096) || !sectors_per_cluster.is_power_of_two() {
+        return None;
+    }
+
+    Some(Superblock {
+        kind: Kind::Ntfs,
+        version: None,
+        block_size: sector_size.checked_mul(sectors_per_cluster),
+    })
+}
+
+fn fat(buf: &[u8]) -> Option<Superblock> {
+    // Microsoft Extensible Firmware Initiative FAT32 File System Specification
+    if !is_boot_sector(buf) {
+        return None;
+    }
+    let sector_size = u32::from(u16_le(buf, 0x0B)?);
+    let sectors_per_cluster = u32::from(buf[0x0D]);
+    let reserved_sectors = u32::from(u16_le(buf, 0x0E)?);
+    let fats = u32::from(buf[0x10]);
+    let root_entries = u32::from(u16_le(buf, 0x11)?);
+    let media = buf[0x15];
+    if !is_block_size(sector_size, 512, 4096)
+        || !sectors_per_cluster.is_power_of_two()
+        || reserved_sectors == 0
+        || !(1..=2).contains(&fats)
+        || (media != 0xF0 && media < 0xF8)
+    {
+        return None;
+    }
+
+    // The type is determined by the number of clusters alone.
+    let fat_sectors = match u16_le(buf, 0x16)? {
+        0 => u32_le(buf, 0x24)?,
+        n => u32::from(n),
+    };
+    let total_sectors = match u16_le(buf, 0x13)? {
+        0 => u32_le(buf, 0x20)?,
+        n => u32::from(n),
+    };
+    let root_sectors = (root_entries * 32).div_ceil(sector_size);
+    let metadata_sectors = fat_sectors
+        .checked_mul(fats)?
+        .checked_add(reserved_sectors + root_sectors)?;
+    let data_sectors = total_sectors
+        .checked_sub(metadata_sectors)
+        .filter(|&sectors| sectors > 0)?;
+    let kind = match data_sectors / sectors_per_cluster {
+        0..=4084 => Kind::Fat12,
+        4085..=65524 => Kind::Fat16,
+        _ => Kind::Fat32,
+    };
+
+    Some(Superblock {
+        kind,
+        version: None,
+        block_size: Some(sector_size * sectors_per_cluster),
+    })
+}
+
+fn hfs_plus(buf: &[u8]) -> Option<Superblock> {
+    // https://developer.apple.com/library/archive/technotes/tn/tn1150.html
+    let header = buf.get(SUPERBLOCK_OFFSET..)?;
+    let version = match (header.get(..2)?, u16_be(header, 2)?) {
+        (b"H+", 4) => 4,
+        (b"HX", 5) => 5,
+        _ => return None,
+    };
+    let block_size = u32_be(header, 40)?;
+    if !is_block_size(block_size, 512, 1 << 30) {
+        return None;
+    }
+
+    Some(Superblock {
+        kind: Kind::HfsPlus,
+        version: Some((version, 0)),
+        block_size: Some(block_size),
+    })
+}
+
+fn apfs(buf: &[u8]) -> Option<Superblock> {
+    // https://developer.apple.com/support/downloads/Apple-File-System-Reference.pdf
+    // the container superblock follows the 32 byte object header
+    if buf.get(32..36)? != b"NXSB" {
+        return None;
+    }
+    let block_size = u32_le(buf, 36)?;
+    if !is_block_size(block_size, 4096, 65536) {
+        return None;
+    }
+
+    Some(Superblock {
+        kind: Kind::Apfs,
+        version: None,
+        block_size: Some(block_size),
+    })
+}
+
+fn zfs(buf: &[u8]) -> Option<Superblock> {
+    zfs_nvlist(buf.get(ZFS_NVLIST_OFFSET..)?)
+}
+
+fn zfs_nvlist(nvlist: &[u8]) -> Option<Superblock> {
+    // XDR encoding in either byte order, list version 0 with unique names,
+    // followed by the first pair: "version", a single 64-bit integer
+    // https://github.com/openzfs/zfs/blob/master/module/nvpair/nvpair.c
+    let header = nvlist.get(..12)?;
+    if header[0] != 1 || header[1] > 1 || header[2..] != [0, 0, 0, 0, 0, 0, 0, 0, 0, 1] {
+        return None;
+    }
+    if nvlist.get(20..32)? != b"\0\0\0\x07version\0"
+        || nvlist.get(32..40)? != [0, 0, 0, 8, 0, 0, 0, 1]
+    {
+        return None;
+    }
+    let version = u64_be(nvlist, 40)?;
+    if version == 0 || version > 5000 {
+        return None;
+    }
+
+    Some(Superblock {
+        kind: Kind::Zfs,
+        version: Some((version.try_into().ok()?, 0)),
+        block_size: None,
+    })
+}
+
+fn luks(buf: &[u8]) -> Option<Superblock> {
+    // https://gitlab.com/cryptsetup/cryptsetup/-/wikis/Specification
+    if buf.get(..6)? != b"LUKS\xBA\xBE" {
+        return None;
+    }
+    let version = u16_be(buf, 6)?;
+    if !(1..=2).contains(&version) {
+        return None;
+    }
+
+    Some(Superblock {
+        kind: Kind::Luks,
+        version: Some((u32::from(version), 0)),
+        block_size: None,
+    })
+}
+
+/// Returns whether the block size is a power of two within the range.
+fn is_block_size(size: u32, min: u32, max: u32) -> bool {
+    size.is_power_of_two() && (min..=max).contains(&size)
+}
+
+/// Reads the superblocks that are further into the file than the prefix, if
+/// the file has one: the ZFS label configuration and the Btrfs superblock.
+///
+/// Returns the bytes from the end of the prefix to the end of the superblock.
+#[cfg(feature = "std")]
+pub(crate) fn read_superblock<R>(
+    r: &mut R,
+    prefix_len: u64,
+    len: u64,
+) -> std::io::Result<Option<Vec<u8>>>
+where
+    R: std::io::Read + std::io::Seek,
+{
+    use std::io::SeekFrom;
+
+    type Parser = fn(&[u8]) -> Option<Superblock>;
+    let superblocks: [(usize, usize, Parser); 2] = [
+        (ZFS_NVLIST_OFFSET, ZFS_NVLIST_LEN, zfs_nvlist),
+        (BTRFS_OFFSET, BTRFS_LEN, btrfs_superblock),
+    ];
+
+    for (offset, sb_len, parse) in superblocks {
+        let (start, end) = (offset as u64, (offset + sb_len) as u64);
+        if prefix_len >= end || len < end {
+            continue;
+        }
+
+        let mut sb = vec![0; sb_len];
+        r.seek(SeekFrom::Start(start))?;
+        r.read_exact(&mut sb)?;
+        if parse(&sb).is_none() {
+            continue;
+        }
+
+        // the prefix may already have the start of the superblock
+        let gap_start = prefix_len.min(start);
+        let mut buf = vec![0; usize::try_from(end - gap_start).unwrap_or(0)];
+        r.seek(SeekFrom::Start(gap_start))?;
+        r.read_exact(&mut buf)?;
+        let skip = usize::try_from(prefix_len - gap_start).unwrap_or(0);
+        return Ok(Some(buf.split_off(skip)));
+    }
+
+    Ok(None)
+}
@@ -1,4 +1,10 @@
-use core::convert::TryInto;
+use super::image_info;
+use super::isobmff::{self, Kind};
+use super::riff;
+use super::xml::{self, Dialect};
+use super::zip::{self, Container};
+
+pub use super::image_info::{ColorType, ImageInfo};
 
 /// Returns whether a buffer is JPEG image data.
 #[must_use]
@@ -9,20 +15,20 @@ pub fn is_jpeg(buf: &[u8]) -> bool {
 /// Returns whether a buffer is jpg2 image data.
 #[must_use]
 pub fn is_jpeg2000(buf: &[u8]) -> bool {
-    buf.len() > 12
-        && buf[0] == 0x0
-        && buf[1] == 0x0
-        && buf[2] == 0x0
-        && buf[3] == 0xC
-        && buf[4] == 0x6A
-        && buf[5] == 0x50
-        && buf[6] == 0x20
-        && buf[7] == 0x20
-        && buf[8] == 0xD
-        && buf[9] == 0xA
-        && buf[10] == 0x87
-        && buf[11] == 0xA
-        && buf[12] == 0x0
+    isobmff::is_jp2_signature(buf)
+        && !matches!(isobmff::kind(buf), Some(Kind::Jpx | Kind::Jpm | Kind::Mj2))
+}
+
+/// Returns whether a buffer is JPEG 2000 Part 2 (JPX) image data.
+#[must_use]
+pub fn is_jpx(buf: &[u8]) -> bool {
+    isobmff::kind(buf) == Some(Kind::Jpx)
+}
+
+/// Returns whether a buffer is JPEG 2000 Part 6 compound (JPM) image data.
+#[must_use]
+pub fn is_jpm(buf: &[u8]) -> bool {
+    isobmff::kind(buf) == Some(Kind::Jpm)
 }
 
 /// Returns whether a buffer is PNG image data.
@@ -31,6 +37,19 @@ pub fn is_png(buf: &[u8]) -> bool {
     buf.len() > 3 && buf[0] == 0x89 && buf[1] == 0x50 && buf[2] == 0x4E && buf[3] == 0x47
 }
 
+/// Returns whether a buffer is animated PNG (APNG) image data.
+#[must_use]
+pub fn is_apng(buf: &[u8]) -> bool {
+    is_png(buf) && image_info::is_apng(buf)
+}
+
+/// Returns whether a buffer is animated GIF image data: it has more than one
+/// image, or a looping extension, within the buffer.
+#[must_use]
+pub fn is_animated_gif(buf: &[u8]) -> bool {
+    is_gif(buf) && image_info::is_animated_gif(buf)
+}
+
 /// Returns whether a buffer is GIF image data.
 #[must_use]
 pub fn is_gif(buf: &[u8]) -> bool {
@@ -40,7 +59,13 @@ pub fn is_gif(buf: &[u8]) -> bool {
 /// Returns whether a buffer is WEBP image data.
 #[must_use]
 pub fn is_webp(buf: &[u8]) -> bool {
-    buf.len() > 11 && buf[8] == 0x57 && buf[9] == 0x45 && buf[10] == 0x42 && buf[11] == 0x50
+    riff::kind(buf) == Some(riff::Kind::Webp)
+}
+
+/// Returns whether a buffer is animated WEBP image data.
+#[must_use]
+pub fn is_animated_webp(buf: &[u8]) -> bool {
+    is_webp(buf) && image_info::is_animated_webp(buf)
 }
 
 /// Returns whether a buffer is Canon CR2 image data.
@@ -111,74 +136,46 @@ pub fn is_jxl(buf: &[u8]) -> bool {
 /// Returns whether a buffer is HEIF image data.
 #[must_use]
 pub fn is_heif(buf: &[u8]) -> bool {
-    if buf.is_empty() {
-        return false;
-    }
-
-    if !is_isobmff(buf) {
-        return false;
-    }
+    isobmff::kind(buf) == Some(Kind::Heif)
+}
 
-    if let Some((major, _minor, compatible)) = get_ftyp(buf) {
-        if major == b"heic" || major == b"heix" {
-            return true;
-        }
-
-        if major == b"mif1" || major == b"msf1" {
-            for b in compatible {
-                if b == b"heic" {
-                    return true;
-                }
-            }
-        }
-    }
+/// Returns whether a buffer is HEIF image sequence data.
+#[must_use]
+pub fn is_heif_sequence(buf: &[u8]) -> bool {
+    isobmff::kind(buf) == Some(Kind::HeifSequence)
+}
 
-    false
+/// Returns whether a buffer is HEIC (HEVC coded) image sequence data.
+#[must_use]
+pub fn is_heic_sequence(buf: &[u8]) -> bool {
+    isobmff::kind(buf) == Some(Kind::HeicSequence)
 }
 
 /// Returns whether a buffer is AVIF image data.
 #[must_use]
 pub fn is_avif(buf: &[u8]) -> bool {
-    if buf.is_empty() {
-        return false;
-    }
-
-    if !is_isobmff(buf) {
-        return false;
-    }
-
-    if let Some((major, _minor, compatible)) = get_ftyp(buf) {
-        if major == b"avif" || major == b"avis" {
-            return true;
-        }
-
-        for b in compatible {
-            if b == b"avif" || b == b"avis" {
-                return true;
-            }
-        }
-    }
-
-    false
+    isobmff::kind(buf) == Some(Kind::Avif)
 }
 
-// IsISOBMFF checks whether the given buffer represents ISO Base Media File Format data
-fn is_isobmff(buf: &[u8]) -> bool {
-    if buf.len() < 16 {
-        return false;
-    }
-
-    if &buf[4..8] != b"ftyp" {
-        return false;
-    }
+/// Returns whether a buffer is AVIF image sequence data.
+#[must_use]
+pub fn is_avif_sequence(buf: &[u8]) -> bool {
+    isobmff::kind(buf) == Some(Kind::AvifSequence)
+}
 
-    let ftyp_length = u32::from_be_bytes(buf[0..4].try_into().unwrap()) as usize;
-    buf.len() >= ftyp_length
+/// Returns whether a buffer is Canon CR3 image data.
+#[must_use]
+pub fn is_cr3(buf: &[u8]) -> bool {
+    isobmff::kind(buf) == Some(Kind::Cr3)
 }
 
 /// Returns whether a buffer is `ORA` image data.
 #[must_use]
 pub fn is_ora(buf: &[u8]) -> bool {
+    if let Some(container) = zip::inspect(buf) {
+        return container == Some(Container::ORA);
+    }
+
     buf.len() > 57
         && buf[0] == 0x50
         && buf[1] == 0x4B
@@ -263,19 +260,41 @@ pub fn is_dwg(buf: &[u8]) -> bool {
     )
 }
 
-// GetFtyp returns the major brand, minor version and compatible brands of the ISO-BMFF data
-fn get_ftyp(buf: &[u8]) -> Option<(&[u8], &[u8], impl Iterator<Item = &[u8]>)> {
-    if buf.len() < 16 {
-        return None;
-    }
+/// Returns whether a buffer is Windows animated cursor (ANI) data.
+#[must_use]
+pub fn is_ani(buf: &[u8]) -> bool {
+    riff::kind(buf) == Some(riff::Kind::Ani)
+}
 
-    let ftyp_length = u32::from_be_bytes(buf[0..4].try_into().unwrap()) as usize;
+/// Returns whether a buffer is CorelDRAW (CDR) image data.
+#[must_use]
+pub fn is_cdr(buf: &[u8]) -> bool {
+    riff::kind(buf) == Some(riff::Kind::Cdr)
+}
 
-    let major = &buf[8..12];
-    let minor = &buf[12..16];
-    let compatible = buf[16..]
-        .chunks_exact(4)
-        .take((ftyp_length / 4).saturating_sub(16 / 4));
+/// Returns whether a buffer is SVG image data.
+#[must_use]
+pub fn is_svg(buf: &[u8]) -> bool {
+    xml::dialect(buf) == Some(Dialect::Svg)
+}
 
-    Some((major, minor, compatible))
+/// Returns the dimensions and pixel format of an image, from its header.
+///
+/// PNG, JPEG, GIF, WebP, BMP, ICO, PSD, TIFF, JPEG XL, HEIF and AVIF are
+/// supported. No image data is decoded.
+///
+/// # Examples
+///
+/// ```rust
+/// use infer::image::ColorType;
+///
+/// let buf = include_bytes!("../../testdata/sample.png");
+/// let info = infer::image::info(buf).expect("PNG header is valid");
+///
+/// assert_eq!((info.width(), info.height()), (256, 170));
+/// assert_eq!(info.color_type(), Some(ColorType::Rgb));
+/// ```
+#[must_use]
+pub fn info(buf: &[u8]) -> Option<ImageInfo> {
+    image_info::info(buf)
 }
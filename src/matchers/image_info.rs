@@ -0,0 +1,770 @@
+//! Image header parsing.
+//!
+//! Dimensions and pixel formats are read from the headers of the supported
+//! formats, without decoding any image data.
+
+use core::convert::TryInto;
+
+use super::{u16_be, u16_le, u32_be, u32_le, u64_be};
+
+/// Maximum number of chunks, markers, boxes or directories that are walked.
+const MAX_ENTRIES: usize = 1024;
+
+/// Dimensions and pixel format of an image.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ImageInfo {
+    width: u32,
+    height: u32,
+    bit_depth: Option<u8>,
+    color_type: Option<ColorType>,
+    frames: Option<u32>,
+}
+
+impl ImageInfo {
+    /// Returns the width of the image in pixels.
+    #[must_use]
+    pub const fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the height of the image in pixels.
+    #[must_use]
+    pub const fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the number of bits per sample, or per palette index for indexed
+    /// images, if the header tells.
+    #[must_use]
+    pub const fn bit_depth(&self) -> Option<u8> {
+        self.bit_depth
+    }
+
+    /// Returns the color model of the pixels, if the header tells.
+    #[must_use]
+    pub const fn color_type(&self) -> Option<ColorType> {
+        self.color_type
+    }
+
+    /// Returns the number of frames of an animation, or of images in an icon
+    /// or a multi-page TIFF.
+    ///
+    /// Counting GIF, animated WebP and TIFF frames requires the whole file to
+    /// be within the buffer. Otherwise, and for formats which do not store the
+    /// count in their header, `None` is returned.
+    #[must_use]
+    pub const fn frames(&self) -> Option<u32> {
+        self.frames
+    }
+}
+
+/// Color model of the pixels of an image.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ColorType {
+    /// Grayscale.
+    Gray,
+    /// Grayscale with alpha.
+    GrayAlpha,
+    /// RGB, including images stored as YCbCr.
+    Rgb,
+    /// RGB with alpha.
+    Rgba,
+    /// Palette indices.
+    Indexed,
+    /// CMYK.
+    Cmyk,
+    /// CIE L*a*b*.
+    Lab,
+}
+
+/// Returns the dimensions and pixel format of an image.
+pub(crate) fn info(buf: &[u8]) -> Option<ImageInfo> {
+    match buf {
+        [0x89, b'P', b'N', b'G', ..] => png(buf),
+        [0xFF, 0xD8, 0xFF, ..] => jpeg(buf),
+        [b'G', b'I', b'F', b'8', ..] => gif(buf),
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => webp(buf),
+        [_, _, _, _, b'f', b't', b'y', b'p', ..] => heif(buf),
+        [b'B', b'M', ..] => bmp(buf),
+        [0x00, 0x00, 0x01 | 0x02, 0x00, ..] => ico(buf),
+        [b'8', b'B', b'P', b'S', ..] => psd(buf),
+        [b'I', b'I', 0x2A, 0x00, ..] => tiff(buf, Endian::Little),
+        [b'M', b'M', 0x00, 0x2A, ..] => tiff(buf, Endian::Big),
+        [0xFF, 0x0A, ..] => jxl_codestream(&buf[2..]),
+        [0x00, 0x00, 0x00, 0x0C, b'J', b'X', b'L', b' ', ..] => jxl_container(buf),
+        _ => None,
+    }
+}
+
+fn png(buf: &[u8]) -> Option<ImageInfo> {
+    if buf.get(12..16)? != b"IHDR" {
+        return None;
+    }
+    let color_type = match *buf.get(25)? {
+        0 => ColorType::Gray,
+        2 => ColorType::Rgb,
+        3 => ColorType::Indexed,
+        4 => ColorType::GrayAlpha,
+        6 => ColorType::Rgba,
+        _ => return None,
+    };
+
+    Some(ImageInfo {
+        width: u32_be(buf, 16)?,
+        height: u32_be(buf, 20)?,
+        bit_depth: Some(*buf.get(24)?),
+        color_type: Some(color_type),
+        frames: png_frames(buf),
+    })
+}
+
+/// Returns the number of frames from the `acTL` chunk of an APNG, or 1 for
+/// still images.
+fn png_frames(buf: &[u8]) -> Option<u32> {
+    match png_animation_control(buf)? {
+        Some(actl) => u32_be(buf, actl),
+        None => Some(1),
+    }
+}
+
+/// Returns whether a PNG has an `acTL` chunk before its image data, making it
+/// an APNG.
+pub(crate) fn is_apng(buf: &[u8]) -> bool {
+    matches!(png_animation_control(buf), Some(Some(_)))
+}
+
+/// Returns the position of the data of the `acTL` chunk, `Some(None)` if the
+/// image data starts without one, or `None` if the chunks are cut off before.
+fn png_animation_control(buf: &[u8]) -> Option<Option<usize>> {
+    let mut pos = 8;
+    for _ in 0..MAX_ENTRIES {
+        let len = usize::try_from(u32_be(buf, pos)?).ok()?;
+        match buf.get(pos + 4..pos + 8)? {
+            b"acTL" => return Some(Some(pos + 8)),
+            b"IDAT" | b"IEND" => return Some(None),
+            _ => {}
+        }
+        // length, type, data and CRC
+        pos = pos.checked_add(len)?.checked_add(12)?;
+    }
+    None
+}
+
+fn jpeg(buf: &[u8]) -> Option<ImageInfo> {
+    let mut pos = 2;
+    for _ in 0..MAX_ENTRIES {
+        // markers may be preceded by fill bytes
+        while buf.get(pos) == Some(&0xFF) && buf.get(pos + 1) == Some(&0xFF) {
+            pos += 1;
+        }
+        if *buf.get(pos)? != 0xFF {
+            return None;
+        }
+        let marker = *buf.get(pos + 1)?;
+        match marker {
+            // standalone markers
+            0x01 | 0xD0..=0xD7 => {
+                pos += 2;
+                continue;
+            }
+            // start of scan or end of image before any frame header
+            0xDA | 0xD9 => return None,
+            // start of frame, except DHT, JPG and DAC which share the range
+            0xC0..=0xCF if !matches!(marker, 0xC4 | 0xC8 | 0xCC) => {
+                let color_type = match *buf.get(pos + 9)? {
+                    1 => ColorType::Gray,
+                    3 => ColorType::Rgb,
+                    4 => ColorType::Cmyk,
+                    _ => return None,
+                };
+                return Some(ImageInfo {
+                    width: u16_be(buf, pos + 7)?.into(),
+                    height: u16_be(buf, pos + 5)?.into(),
+                    bit_depth: Some(*buf.get(pos + 4)?),
+                    color_type: Some(color_type),
+                    frames: Some(1),
+                });
+            }
+            _ => pos += 2 + usize::from(u16_be(buf, pos + 2)?),
+        }
+    }
+    None
+}
+
+fn gif(buf: &[u8]) -> Option<ImageInfo> {
+    if !matches!(buf.get(..6)?, b"GIF87a" | b"GIF89a") {
+        return None;
+    }
+    let flags = *buf.get(10)?;
+    let global_palette = flags & 0x80 != 0;
+
+    Some(ImageInfo {
+        width: u16_le(buf, 6)?.into(),
+        height: u16_le(buf, 8)?.into(),
+        bit_depth: global_palette.then_some((flags & 0x07) + 1),
+        color_type: Some(ColorType::Indexed),
+        frames: gif_frames(buf),
+    })
+}
+
+/// Returns the number of image descriptors of a GIF, if the trailer is within
+/// the buffer.
+fn gif_frames(buf: &[u8]) -> Option<u32> {
+    let mut frames = 0;
+    for (introducer, _) in gif_blocks(buf) {
+        match introducer {
+            0x2C => frames += 1,
+            0x3B => return Some(frames),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Returns whether a GIF has more than one image or an application extension
+/// setting up looping, within the buffer.
+pub(crate) fn is_animated_gif(buf: &[u8]) -> bool {
+    let mut images = 0;
+    gif_blocks(buf).any(|(introducer, pos)| match introducer {
+        0x2C => {
+            images += 1;
+            images > 1
+        }
+        // application extension of 11 bytes
+        0x21 => {
+            buf.get(pos + 1..pos + 3) == Some(&[0xFF, 0x0B])
+                && matches!(
+                    buf.get(pos + 3..pos + 14),
+                    Some(b"NETSCAPE2.0" | b"ANIMEXTS1.0")
+                )
+        }
+        _ => false,
+    })
+}
+
+/// Returns an iterator over the introducers and positions of the blocks of a
+/// GIF, up to the trailer. Iteration stops at a block cut off at the end of
+/// the buffer.
+fn gif_blocks(buf: &[u8]) -> impl Iterator<Item = (u8, usize)> + '_ {
+    let mut next = buf.get(10).map(|&flags| 13 + palette_len(flags));
+    core::iter::from_fn(move || {
+        let pos = next?;
+        let introducer = *buf.get(pos)?;
+        next = match introducer {
+            // image descriptor, local palette, LZW code size and image data
+            0x2C => buf
+                .get(pos + 9)
+                .and_then(|&flags| skip_sub_blocks(buf, pos + 10 + palette_len(flags) + 1)),
+            // extension label and data
+            0x21 => skip_sub_blocks(buf, pos + 2),
+            0x3B => None,
+            _ => return None,
+        };
+        Some((introducer, pos))
+    })
+}
+
+/// Returns the length of the color table following a GIF screen or image
+/// descriptor with the given flags.
+fn palette_len(flags: u8) -> usize {
+    if flags & 0x80 == 0 {
+        0
+    } else {
+        3 << ((flags & 0x07) + 1)
+    }
+}
+
+/// Returns the position after the terminating empty sub-block.
+fn skip_sub_blocks(buf: &[u8], mut pos: usize) -> Option<usize> {
+    loop {
+        match *buf.get(pos)? {
+            0 => return Some(pos + 1),
+            len => pos += 1 + usize::from(len),
+        }
+    }
+}
+
+fn webp(buf: &[u8]) -> Option<ImageInfo> {
+    let chunk = buf.get(12..16)?;
+    let data = 20;
+    match chunk {
+        b"VP8 " => {
+            // frame tag, then the start code of a key frame
+            if buf.get(data + 3..data + 6)? != [0x9D, 0x01, 0x2A] {
+                return None;
+            }
+            Some(ImageInfo {
+                width: (u16_le(buf, data + 6)? & 0x3FFF).into(),
+                height: (u16_le(buf, data + 8)? & 0x3FFF).into(),
+                bit_depth: Some(8),
+                color_type: Some(ColorType::Rgb),
+                frames: Some(1),
+            })
+        }
+        b"VP8L" => {
+            if *buf.get(data)? != 0x2F {
+                return None;
+            }
+            let bits = u32_le(buf, data + 1)?;
+            let alpha = bits & (1 << 28) != 0;
+            Some(ImageInfo {
+                width: (bits & 0x3FFF) + 1,
+                height: ((bits >> 14) & 0x3FFF) + 1,
+                bit_depth: Some(8),
+                color_type: Some(if alpha {
+                    ColorType::Rgba
+                } else {
+                    ColorType::Rgb
+                }),
+                frames: Some(1),
+            })
+        }
+        b"VP8X" => {
+            let flags = *buf.get(data)?;
+            let alpha = flags & 0x10 != 0;
+            let animated = flags & 0x02 != 0;
+            Some(ImageInfo {
+                width: u24_le(buf, data + 4)? + 1,
+                height: u24_le(buf, data + 7)? + 1,
+                bit_depth: Some(8),
+                color_type: Some(if alpha {
+                    ColorType::Rgba
+                } else {
+                    ColorType::Rgb
+                }),
+                frames: if animated { webp_frames(buf) } else { Some(1) },
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Returns the number of `ANMF` chunks of an animated WebP, if the whole file
+/// is within the buffer.
+fn webp_frames(buf: &[u8]) -> Option<u32> {
+    let end = usize::try_from(u32_le(buf, 4)?).ok()?.checked_add(8)?;
+    let frames = webp_chunks(buf.get(..end)?)
+        .filter(|&chunk| chunk == b"ANMF")
+        .count();
+    frames.try_into().ok()
+}
+
+/// Returns whether a WebP has the animation flag or animation chunks within
+/// the buffer.
+pub(crate) fn is_animated_webp(buf: &[u8]) -> bool {
+    buf.get(12..16) == Some(b"VP8X")
+        && (buf.get(20).is_some_and(|flags| flags & 0x02 != 0)
+            || webp_chunks(buf).any(|chunk| chunk == b"ANIM" || chunk == b"ANMF"))
+}
+
+/// Returns an iterator over the identifiers of the chunks of a WebP.
+fn webp_chunks(buf: &[u8]) -> impl Iterator<Item = &[u8]> {
+    let mut pos = 12;
+    core::iter::from_fn(move || {
+        let id = buf.get(pos..pos + 4)?;
+        let len = usize::try_from(u32_le(buf, pos + 4)?).ok()?;
+        // chunks are padded to an even length
+        pos = pos.checked_add(8)?.checked_add(len)?.checked_add(len & 1)?;
+        Some(id)
+    })
+}
+
+fn bmp(buf: &[u8]) -> Option<ImageInfo> {
+    let header_len = u32_le(buf, 14)?;
+    let (width, height, bits) = if header_len == 12 {
+        // OS/2 BITMAPCOREHEADER
+        (
+            u16_le(buf, 18)?.into(),
+            u16_le(buf, 20)?.into(),
+            u16_le(buf, 24)?,
+        )
+    } else if header_len >= 40 {
+        // negative heights are used by top-down bitmaps
+        (
+            u32_le(buf, 18)?,
+            i32::from_le_bytes(u32_le(buf, 22)?.to_le_bytes()).unsigned_abs(),
+            u16_le(buf, 28)?,
+        )
+    } else {
+        return None;
+    };
+
+    // BITMAPV3INFOHEADER and later have an alpha mask
+    let alpha = header_len >= 56 && u32_le(buf, 66).is_some_and(|mask| mask != 0);
+    let (bit_depth, color_type) = pixel_format(bits, alpha);
+    Some(ImageInfo {
+        width,
+        height,
+        bit_depth,
+        color_type,
+        frames: Some(1),
+    })
+}
+
+/// Returns the bit depth and color type of BMP and ICO pixels.
+fn pixel_format(bits_per_pixel: u16, alpha: bool) -> (Option<u8>, Option<ColorType>) {
+    match bits_per_pixel {
+        1 | 2 | 4 | 8 => (u8::try_from(bits_per_pixel).ok(), Some(ColorType::Indexed)),
+        16 => (None, Some(ColorType::Rgb)),
+        24 => (Some(8), Some(ColorType::Rgb)),
+        32 if alpha => (Some(8), Some(ColorType::Rgba)),
+        32 => (Some(8), Some(ColorType::Rgb)),
+        _ => (None, None),
+    }
+}
+
+fn ico(buf: &[u8]) -> Option<ImageInfo> {
+    let count = u16_le(buf, 4)?;
+    if count == 0 {
+        return None;
+    }
+
+    // the largest image of the directory
+    let mut largest: Option<(u32, u32, u16)> = None;
+    for i in 0..usize::from(count) {
+        let entry = 6 + i * 16;
+        let Some(bytes) = buf.get(entry..entry + 16) else {
+            break;
+        };
+        // a size of 0 stands for 256 pixels
+        let width = if bytes[0] == 0 { 256 } else { bytes[0].into() };
+        let height = if bytes[1] == 0 { 256 } else { bytes[1].into() };
+        let bits = u16::from_le_bytes([bytes[6], bytes[7]]);
+        if largest.map_or(true, |(w, h, b)| (width * height, bits) > (w * h, b)) {
+            largest = Some((width, height, bits));
+        }
+    }
+
+    let (width, height, bits) = largest?;
+    let (bit_depth, color_type) = pixel_format(bits, true);
+    Some(ImageInfo {
+        width,
+        height,
+        bit_depth,
+        color_type,
+        frames: Some(count.into()),
+    })
+}
+
+fn psd(buf: &[u8]) -> Option<ImageInfo> {
+    let channels = u16_be(buf, 12)?;
+    let color_type = match u16_be(buf, 24)? {
+        0 | 1 if channels > 1 => Some(ColorType::GrayAlpha),
+        0 | 1 => Some(ColorType::Gray),
+        2 => Some(ColorType::Indexed),
+        3 if channels > 3 => Some(ColorType::Rgba),
+        3 => Some(ColorType::Rgb),
+        4 => Some(ColorType::Cmyk),
+        9 => Some(ColorType::Lab),
+        // multichannel and duotone
+        _ => None,
+    };
+
+    Some(ImageInfo {
+        width: u32_be(buf, 18)?,
+        height: u32_be(buf, 14)?,
+        bit_depth: u16_be(buf, 22)?.try_into().ok(),
+        color_type,
+        frames: Some(1),
+    })
+}
+
+#[derive(Copy, Clone)]
+enum Endian {
+    Little,
+    Big,
+}
+
+struct Tiff<'a> {
+    buf: &'a [u8],
+    endian: Endian,
+}
+
+impl Tiff<'_> {
+    fn u16(&self, offset: usize) -> Option<u16> {
+        match self.endian {
+            Endian::Little => u16_le(self.buf, offset),
+            Endian::Big => u16_be(self.buf, offset),
+        }
+    }
+
+    fn u32(&self, offset: usize) -> Option<u32> {
+        match self.endian {
+            Endian::Little => u32_le(self.buf, offset),
+            Endian::Big => u32_be(self.buf, offset),
+        }
+    }
+
+    fn offset(&self, offset: usize) -> Option<usize> {
+        usize::try_from(self.u32(offset)?).ok()
+    }
+
+    /// Reads the SHORT or LONG value of a directory entry.
+    fn value(&self, entry: usize) -> Option<u32> {
+        match self.u16(entry + 2)? {
+            3 => self.u16(entry + 8).map(u32::from),
+            4 => self.u32(entry + 8),
+            _ => None,
+        }
+    }
+
+    /// Returns the number of directories, one per page, if the whole chain is
+    /// within the buffer.
+    fn pages(&self, mut ifd: usize) -> Option<u32> {
+        for pages in 1..=MAX_ENTRIES as u32 {
+            let entries = usize::from(self.u16(ifd)?);
+            match self.offset(ifd + 2 + entries * 12)? {
+                0 => return Some(pages),
+                next => ifd = next,
+            }
+        }
+        None
+    }
+}
+
+fn tiff(buf: &[u8], endian: Endian) -> Option<ImageInfo> {
+    let tiff = Tiff { buf, endian };
+    let ifd = tiff.offset(4)?;
+    let count = usize::from(tiff.u16(ifd)?);
+
+    let (mut width, mut height, mut bits, mut photometric) = (None, None, None, None);
+    let (mut samples, mut extra) = (1, 0);
+    for i in 0..count.min(MAX_ENTRIES) {
+        let entry = ifd + 2 + i * 12;
+        match tiff.u16(entry)? {
+            256 => width = tiff.value(entry),
+            257 => height = tiff.value(entry),
+            258 => {
+                // one value per sample, stored elsewhere if there are more than two
+                bits = match tiff.u32(entry + 4)? {
+                    1 | 2 => tiff.u16(entry + 8),
+                    _ => tiff.offset(entry + 8).and_then(|offset| tiff.u16(offset)),
+                };
+            }
+            262 => photometric = tiff.value(entry),
+            277 => samples = tiff.value(entry).unwrap_or(1),
+            338 => extra = tiff.u32(entry + 4)?,
+            _ => {}
+        }
+    }
+
+    let alpha = extra > 0;
+    let color_type = photometric.and_then(|photometric| match photometric {
+        0 | 1 if alpha || samples > 1 => Some(ColorType::GrayAlpha),
+        0 | 1 => Some(ColorType::Gray),
+        2 | 6 if alpha || samples > 3 => Some(ColorType::Rgba),
+        2 | 6 => Some(ColorType::Rgb),
+        3 => Some(ColorType::Indexed),
+        5 => Some(ColorType::Cmyk),
+        8 => Some(ColorType::Lab),
+        _ => None,
+    });
+
+    Some(ImageInfo {
+        width: width?,
+        height: height?,
+        bit_depth: bits.and_then(|bits| bits.try_into().ok()),
+        color_type,
+        frames: tiff.pages(ifd),
+    })
+}
+
+/// Returns the information from the size header of a JPEG XL codestream,
+/// after its signature.
+fn jxl_codestream(buf: &[u8]) -> Option<ImageInfo> {
+    let mut bits = Bits { buf, pos: 0 };
+
+    let small = bits.read(1)? == 1;
+    let height = if small {
+        (bits.read(5)? + 1) * 8
+    } else {
+        jxl_dimension(&mut bits)?
+    };
+    let width = match bits.read(3)? {
+        0 if small => (bits.read(5)? + 1) * 8,
+        0 => jxl_dimension(&mut bits)?,
+        ratio => {
+            let (num, den) = [(1, 1), (12, 10), (4, 3), (3, 2), (16, 9), (5, 4), (2, 1)]
+                [usize::try_from(ratio).ok()? - 1];
+            u32::try_from(u64::from(height) * num / den).ok()?
+        }
+    };
+
+    // default image metadata: 8-bit sRGB without extra channels
+    let default = bits.read(1)? == 1;
+    Some(ImageInfo {
+        width,
+        height,
+        bit_depth: default.then_some(8),
+        color_type: default.then_some(ColorType::Rgb),
+        frames: None,
+    })
+}
+
+fn jxl_dimension(bits: &mut Bits<'_>) -> Option<u32> {
+    let len = [9, 13, 18, 30][usize::try_from(bits.read(2)?).ok()?];
+    Some(bits.read(len)? + 1)
+}
+
+/// Returns the information from the codestream of a JPEG XL container.
+fn jxl_container(buf: &[u8]) -> Option<ImageInfo> {
+    let mut pos = 0;
+    for _ in 0..MAX_ENTRIES {
+        let (header, len) = box_header(buf, pos)?;
+        match buf.get(pos + 4..pos + 8)? {
+            b"jxlc" => return jxl_codestream(buf.get(pos + header + 2..)?),
+            // partial codestreams start with an index
+            b"jxlp" => return jxl_codestream(buf.get(pos + header + 6..)?),
+            _ => pos = pos.checked_add(len)?,
+        }
+    }
+    None
+}
+
+/// Little endian bit reader, as used by JPEG XL.
+struct Bits<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl Bits<'_> {
+    fn read(&mut self, len: usize) -> Option<u32> {
+        let mut value = 0;
+        for i in 0..len {
+            let byte = *self.buf.get(self.pos / 8)?;
+            value |= u32::from((byte >> (self.pos % 8)) & 1) << i;
+            self.pos += 1;
+        }
+        Some(value)
+    }
+}
+
+/// Returns the information from the `ispe` and `pixi` properties of the primary
+/// item of a HEIF or AVIF image.
+fn heif(buf: &[u8]) -> Option<ImageInfo> {
+    let meta = find_box(buf, b"meta")?;
+    // full box version and flags
+    let meta = meta.get(4..)?;
+
+    let primary = find_box(meta, b"pitm").and_then(|pitm| match pitm.first()? {
+        0 => u16_be(pitm, 4).map(u32::from),
+        _ => u32_be(pitm, 4),
+    });
+    let iprp = find_box(meta, b"iprp")?;
+    let properties = find_box(iprp, b"ipco")?;
+    let associations = find_box(iprp, b"ipma");
+
+    let mut size = None;
+    let mut pixi = None;
+    let mut index = 0;
+    let mut pos = 0;
+    while pos < properties.len() && index < MAX_ENTRIES {
+        let (header, len) = box_header(properties, pos)?;
+        let end = pos.checked_add(len)?;
+        let body = properties.get(pos + header..end)?;
+        index += 1;
+
+        let associated = match (primary, associations) {
+            (Some(item), Some(ipma)) => is_associated(ipma, item, index),
+            _ => true,
+        };
+        match &properties[pos + 4..pos + 8] {
+            b"ispe" if associated => {
+                let dimensions = (u32_be(body, 4)?, u32_be(body, 8)?);
+                // without associations, the largest image is used
+                if size.map_or(true, |(w, h): (u32, u32)| {
+                    u64::from(dimensions.0) * u64::from(dimensions.1) > u64::from(w) * u64::from(h)
+                }) {
+                    size = Some(dimensions);
+                }
+            }
+            b"pixi" if associated => pixi = Some(body),
+            _ => {}
+        }
+        pos = end;
+    }
+
+    let (width, height) = size?;
+    let channels = pixi.and_then(|pixi| pixi.get(4));
+    Some(ImageInfo {
+        width,
+        height,
+        bit_depth: pixi.and_then(|pixi| pixi.get(5)).copied(),
+        color_type: match channels {
+            Some(1) => Some(ColorType::Gray),
+            Some(3) => Some(ColorType::Rgb),
+            _ => None,
+        },
+        frames: None,
+    })
+}
+
+/// Returns whether the 1-based property index is associated with an item in
+/// an `ipma` box.
+fn is_associated(ipma: &[u8], item: u32, index: usize) -> bool {
+    let version = ipma.first().copied();
+    let large_index = ipma.get(3).is_some_and(|flags| flags & 1 != 0);
+    let Some(count) = u32_be(ipma, 4) else {
+        return false;
+    };
+
+    let mut pos = 8;
+    for _ in 0..count.min(MAX_ENTRIES as u32) {
+        let (id, id_len) = if version == Some(0) {
+            (u16_be(ipma, pos).map(u32::from), 2)
+        } else {
+            (u32_be(ipma, pos), 4)
+        };
+        let Some(&associations) = ipma.get(pos + id_len) else {
+            return false;
+        };
+        pos += id_len + 1;
+
+        let entry_len = if large_index { 2 } else { 1 };
+        if id == Some(item) {
+            return (0..usize::from(associations)).any(|i| {
+                let entry = pos + i * entry_len;
+                // the high bit marks essential properties
+                let property = if large_index {
+                    u16_be(ipma, entry).map(|v| usize::from(v & 0x7FFF))
+                } else {
+                    ipma.get(entry).map(|&v| usize::from(v & 0x7F))
+                };
+                property == Some(index)
+            });
+        }
+        pos += usize::from(associations) * entry_len;
+    }
+    false
+}
+
+/// Returns the body of the first box of a type at the top level of a buffer.
+fn find_box<'a>(buf: &'a [u8], box_type: &[u8; 4]) -> Option<&'a [u8]> {
+    let mut pos = 0;
+    for _ in 0..MAX_ENTRIES {
+        let (header, len) = box_header(buf, pos)?;
+        if buf.get(pos + 4..pos + 8)? == box_type {
+            // the box may be cut off at the end of the buffer
+            let end = pos.checked_add(len)?;
+            return buf.get(pos + header..buf.len().min(end));
+        }
+        pos = pos.checked_add(len)?;
+    }
+    None
+}
+
+/// Returns the length of the header and of the whole box at a position.
+fn box_header(buf: &[u8], pos: usize) -> Option<(usize, usize)> {
+    let (header, len) = match u32_be(buf, pos)? {
+        // the box extends to the end of the file
+        0 => (8, buf.len() - pos),
+        1 => (16, usize::try_from(u64_be(buf, pos + 8)?).ok()?),
+        len => (8, usize::try_from(len).ok()?),
+    };
+    (len >= header).then_some((header, len))
+}
+
+fn u24_le(buf: &[u8], offset: usize) -> Option<u32> {
+    let bytes = buf.get(offset..offset.checked_add(3)?)?;
+    Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], 0]))
+}
@@ -0,0 +1,171 @@
+//! ISO Base Media File Format (ISO/IEC 14496-12) brand identification.
+//!
+//! MP4, QuickTime, 3GPP, HEIF, AVIF, JPEG 2000 and related formats all start with
+//! a `ftyp` box (or a `styp` box for media segments) listing a major brand and
+//! compatible brands. The matchers of these formats share the parsing and the
+//! brand table below.
+
+use core::convert::TryInto;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum Kind {
+    Mp4,
+    M4v,
+    M4a,
+    M4b,
+    F4v,
+    F4a,
+    ThreeGp,
+    ThreeG2,
+    Mov,
+    Segment,
+    Heif,
+    HeifSequence,
+    HeicSequence,
+    Avif,
+    AvifSequence,
+    Cr3,
+    Jp2,
+    Jpx,
+    Jpm,
+    Mj2,
+}
+
+const BRANDS: &[(&[u8; 4], Kind)] = &[
+    // MPEG-4
+    (b"avc1", Kind::Mp4),
+    (b"dash", Kind::Mp4),
+    (b"iso2", Kind::Mp4),
+    (b"iso3", Kind::Mp4),
+    (b"iso4", Kind::Mp4),
+    (b"iso5", Kind::Mp4),
+    (b"iso6", Kind::Mp4),
+    (b"isom", Kind::Mp4),
+    (b"mmp4", Kind::Mp4),
+    (b"mp41", Kind::Mp4),
+    (b"mp42", Kind::Mp4),
+    (b"mp4v", Kind::Mp4),
+    (b"mp71", Kind::Mp4),
+    (b"cmfc", Kind::Mp4),
+    (b"cmf2", Kind::Mp4),
+    (b"MSNV", Kind::Mp4),
+    (b"NDAS", Kind::Mp4),
+    (b"NDSC", Kind::Mp4),
+    (b"NSDC", Kind::Mp4),
+    (b"NDSH", Kind::Mp4),
+    (b"NDSM", Kind::Mp4),
+    (b"NDSP", Kind::Mp4),
+    (b"NDSS", Kind::Mp4),
+    (b"NDXC", Kind::Mp4),
+    (b"NDXH", Kind::Mp4),
+    (b"NDXM", Kind::Mp4),
+    (b"NDXP", Kind::Mp4),
+    (b"NDXS", Kind::Mp4),
+    // Apple
+    (b"M4V ", Kind::M4v),
+    (b"M4VH", Kind::M4v),
+    (b"M4VP", Kind::M4v),
+    (b"M4A ", Kind::M4a),
+    (b"M4B ", Kind::M4b),
+    (b"qt  ", Kind::Mov),
+    // Adobe Flash
+    (b"F4V ", Kind::F4v),
+    (b"F4P ", Kind::F4v),
+    (b"F4A ", Kind::F4a),
+    (b"F4B ", Kind::F4a),
+    // 3GPP and 3GPP2
+    (b"3gp4", Kind::ThreeGp),
+    (b"3gp5", Kind::ThreeGp),
+    (b"3gp6", Kind::ThreeGp),
+    (b"3gp7", Kind::ThreeGp),
+    (b"3gs7", Kind::ThreeGp),
+    (b"3ge6", Kind::ThreeGp),
+    (b"3ge7", Kind::ThreeGp),
+    (b"3gg6", Kind::ThreeGp),
+    (b"3gr6", Kind::ThreeGp),
+    (b"3g2a", Kind::ThreeG2),
+    (b"3g2b", Kind::ThreeG2),
+    (b"3g2c", Kind::ThreeG2),
+    (b"kddi", Kind::ThreeG2),
+    // HEIF
+    (b"heic", Kind::Heif),
+    (b"heix", Kind::Heif),
+    (b"heim", Kind::Heif),
+    (b"heis", Kind::Heif),
+    (b"hevc", Kind::HeicSequence),
+    (b"hevx", Kind::HeicSequence),
+    (b"hevm", Kind::HeicSequence),
+    (b"hevs", Kind::HeicSequence),
+    (b"avif", Kind::Avif),
+    (b"avis", Kind::AvifSequence),
+    // Canon raw
+    (b"crx ", Kind::Cr3),
+    // JPEG 2000
+    (b"jp2 ", Kind::Jp2),
+    (b"jpx ", Kind::Jpx),
+    (b"jpm ", Kind::Jpm),
+    (b"mjp2", Kind::Mj2),
+    (b"mj2s", Kind::Mj2),
+];
+
+/// Returns the kind of ISO-BMFF data identified by its brands.
+pub(crate) fn kind(buf: &[u8]) -> Option<Kind> {
+    let (box_type, major, mut compatible) = ftyp(buf)?;
+    if box_type == b"styp" {
+        return Some(Kind::Segment);
+    }
+
+    match brand(major) {
+        Some(Kind::Mp4) | None => {}
+        Some(kind) => return Some(kind),
+    }
+
+    // Generic major brands are refined by the compatible brands.
+    let image = matches!(major, b"mif1" | b"mif2" | b"miaf" | b"msf1");
+    let sequence = major == b"msf1";
+    let refined = compatible.find_map(|b| match brand(b)? {
+        Kind::Avif if sequence => Some(Kind::AvifSequence),
+        Kind::Heif if sequence => Some(Kind::HeicSequence),
+        kind @ (Kind::Avif | Kind::AvifSequence) => Some(kind),
+        kind @ (Kind::Heif | Kind::HeicSequence) if image => Some(kind),
+        _ => None,
+    });
+
+    refined.or(match major {
+        b"mif1" | b"mif2" | b"miaf" => Some(Kind::Heif),
+        b"msf1" => Some(Kind::HeifSequence),
+        _ => brand(major),
+    })
+}
+
+/// Returns whether a buffer starts with a JPEG 2000 signature box.
+pub(crate) fn is_jp2_signature(buf: &[u8]) -> bool {
+    buf.len() > 12 && buf[..12] == *b"\x00\x00\x00\x0CjP  \r\n\x87\n"
+}
+
+fn brand(brand: &[u8]) -> Option<Kind> {
+    BRANDS
+        .iter()
+        .find(|(b, _)| b.as_slice() == brand)
+        .map(|(_, kind)| *kind)
+}
+
+/// Returns the box type, the major brand and the compatible brands of the
+/// `ftyp` or `styp` box at the start of the buffer.
+///
+/// The box may be preceded by a JPEG 2000 signature box.
+fn ftyp(mut buf: &[u8]) -> Option<(&[u8], &[u8], impl Iterator<Item = &[u8]>)> {
+    if is_jp2_signature(buf) {
+        buf = &buf[12..];
+    }
+    if buf.len() < 16 || !matches!(&buf[4..8], b"ftyp" | b"styp") {
+        return None;
+    }
+
+    let box_len = u32::from_be_bytes(buf[0..4].try_into().unwrap()) as usize;
+    let compatible = buf[16..]
+        .chunks_exact(4)
+        .take(box_len.saturating_sub(16) / 4);
+
+    Some((&buf[4..8], &buf[8..12], compatible))
+}
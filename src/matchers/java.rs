@@ -0,0 +1,74 @@
+//! Java class file header parsing.
+//!
+//! # Examples
+//!
+//! ```rust
+//! let buf = include_bytes!("../../testdata/sample.class");
+//! let class = infer::java::info(buf).expect("class file header is valid");
+//!
+//! assert_eq!(class.major_version(), 61);
+//! assert_eq!(class.release(), 17);
+//! ```
+
+/// Minor version of class files depending on preview features of their release.
+const PREVIEW_MINOR_VERSION: u16 = 0xFFFF;
+
+/// Version information from the header of a class file.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ClassFile {
+    major_version: u16,
+    minor_version: u16,
+}
+
+impl ClassFile {
+    /// Returns the major version of the class file format, e.g. `65`.
+    #[must_use]
+    pub const fn major_version(&self) -> u16 {
+        self.major_version
+    }
+
+    /// Returns the minor version of the class file format.
+    #[must_use]
+    pub const fn minor_version(&self) -> u16 {
+        self.minor_version
+    }
+
+    /// Returns the Java SE release which introduced the class file version,
+    /// e.g. `21` for version 65.
+    ///
+    /// Releases before Java 5 are numbered like `ClassFileFormatVersion` in the
+    /// JDK: `1` for 1.1 (and 1.0.2), up to `4` for 1.4.
+    #[must_use]
+    pub const fn release(&self) -> u16 {
+        self.major_version - 44
+    }
+
+    /// Returns whether the class file depends on preview features of its
+    /// release, so it only runs on exactly that release.
+    #[must_use]
+    pub const fn is_preview(&self) -> bool {
+        self.major_version >= 56 && self.minor_version == PREVIEW_MINOR_VERSION
+    }
+}
+
+/// Returns the version information from the header of a class file.
+#[must_use]
+pub fn info(buf: &[u8]) -> Option<ClassFile> {
+    if buf.len() < 8 || buf[..4] != [0xCA, 0xFE, 0xBA, 0xBE] {
+        return None;
+    }
+
+    // Mach-O universal binaries also start with 0xCAFEBABE, followed by the
+    // number of slices where the version is. Class files start at version 45.0,
+    // and universal binaries have fewer slices.
+    // https://docs.oracle.com/javase/specs/jvms/se21/html/jvms-4.html
+    let major_version = u16::from_be_bytes([buf[6], buf[7]]);
+    if major_version < 45 {
+        return None;
+    }
+
+    Some(ClassFile {
+        major_version,
+        minor_version: u16::from_be_bytes([buf[4], buf[5]]),
+    })
+}
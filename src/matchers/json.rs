@@ -0,0 +1,403 @@
+//! Allocation-free JSON prefix validator.
+//!
+//! Buffers are usually a prefix of a file, so a document that is valid up to
+//! the end of the buffer is accepted even if it is cut off. Formats built on
+//! JSON, such as GeoJSON or Jupyter notebooks, are identified by the members of
+//! the root object. Only the first 8 KiB are inspected, and a buffer that is
+//! longer is treated as cut off there.
+
+use super::match_bytes;
+
+const MAX_DEPTH: usize = 64;
+
+/// Maximum number of bytes inspected, the length of the prefix read from files.
+const MAX_LEN: usize = 8192;
+
+const GEOJSON_TYPES: &[&[u8]] = &[
+    b"Feature",
+    b"FeatureCollection",
+    b"GeometryCollection",
+    b"LineString",
+    b"MultiLineString",
+    b"MultiPoint",
+    b"MultiPolygon",
+    b"Point",
+    b"Polygon",
+];
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum Kind {
+    GeoJson,
+    TopoJson,
+    Notebook,
+    Gltf,
+    JsonSchema,
+    WebManifest,
+    SourceMap,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Error {
+    Invalid,
+    /// The buffer ends before the value is complete.
+    Truncated,
+}
+
+struct Parser<'a> {
+    buf: &'a [u8],
+    pos: usize,
+    /// Number of complete values parsed, not counting object keys.
+    values: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(buf: &'a [u8]) -> Parser<'a> {
+        Parser {
+            buf,
+            pos: 0,
+            values: 0,
+        }
+    }
+
+    /// Skips whitespace and returns the next byte.
+    fn peek(&mut self) -> Result<u8, Error> {
+        while let Some(&b) = self.buf.get(self.pos) {
+            if !matches!(b, b' ' | b'\t' | b'\n' | b'\r') {
+                return Ok(b);
+            }
+            self.pos += 1;
+        }
+        Err(Error::Truncated)
+    }
+
+    fn expect(&mut self, expected: u8) -> Result<(), Error> {
+        if self.peek()? != expected {
+            return Err(Error::Invalid);
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    /// Parses a value and returns it as a slice of the buffer.
+    fn value(&mut self, depth: usize) -> Result<&'a [u8], Error> {
+        let first = self.peek()?;
+        let start = self.pos;
+        match first {
+            b'{' => self.container(depth, b'}')?,
+            b'[' => self.container(depth, b']')?,
+            b'"' => {
+                self.string()?;
+            }
+            b't' => self.literal(b"true")?,
+            b'f' => self.literal(b"false")?,
+            b'n' => self.literal(b"null")?,
+            b'-' | b'0'..=b'9' => self.number()?,
+            _ => return Err(Error::Invalid),
+        }
+        self.values += 1;
+        Ok(&self.buf[start..self.pos])
+    }
+
+    fn container(&mut self, depth: usize, close: u8) -> Result<(), Error> {
+        if depth == MAX_DEPTH {
+            return Err(Error::Invalid);
+        }
+        self.pos += 1;
+        if self.peek()? == close {
+            self.pos += 1;
+            return Ok(());
+        }
+
+        loop {
+            if close == b'}' {
+                self.string()?;
+                self.expect(b':')?;
+            }
+            self.value(depth + 1)?;
+            match self.peek()? {
+                b',' => self.pos += 1,
+                b if b == close => {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                _ => return Err(Error::Invalid),
+            }
+        }
+    }
+
+    /// Parses a string and returns its content with escape sequences left as is.
+    fn string(&mut self) -> Result<&'a [u8], Error> {
+        if self.peek()? != b'"' {
+            return Err(Error::Invalid);
+        }
+        let start = self.pos + 1;
+        let mut i = start;
+
+        loop {
+            match *self.buf.get(i).ok_or(Error::Truncated)? {
+                b'"' => break,
+                b'\\' => match *self.buf.get(i + 1).ok_or(Error::Truncated)? {
+                    b'"' | b'\\' | b'/' | b'b' | b'f' | b'n' | b'r' | b't' => i += 2,
+                    b'u' => {
+                        let hex = &self.buf[i + 2..self.buf.len().min(i + 6)];
+                        if !hex.iter().all(u8::is_ascii_hexdigit) {
+                            return Err(Error::Invalid);
+                        }
+                        if hex.len() < 4 {
+                            return Err(Error::Truncated);
+                        }
+                        i += 6;
+                    }
+                    _ => return Err(Error::Invalid),
+                },
+                0..=0x1F => return Err(Error::Invalid),
+                _ => i += 1,
+            }
+        }
+
+        let content = &self.buf[start..i];
+        core::str::from_utf8(content).map_err(|_| Error::Invalid)?;
+        self.pos = i + 1;
+        Ok(content)
+    }
+
+    fn literal(&mut self, literal: &[u8]) -> Result<(), Error> {
+        let rest = &self.buf[self.pos..];
+        let len = rest.len().min(literal.len());
+        if rest[..len] != literal[..len] {
+            return Err(Error::Invalid);
+        }
+        if len < literal.len() {
+            return Err(Error::Truncated);
+        }
+        self.pos += len;
+        Ok(())
+    }
+
+    fn number(&mut self) -> Result<(), Error> {
+        let buf = &self.buf[self.pos..];
+        let mut i = usize::from(buf[0] == b'-');
+
+        match buf.get(i) {
+            Some(b'0') => i += 1,
+            Some(b'1'..=b'9') => i += digits(&buf[i..]),
+            Some(_) => return Err(Error::Invalid),
+            None => return Err(Error::Truncated),
+        }
+        if buf.get(i) == Some(&b'.') {
+            i += 1;
+            i += required_digits(&buf[i..])?;
+        }
+        if matches!(buf.get(i), Some(b'e' | b'E')) {
+            i += 1;
+            if matches!(buf.get(i), Some(b'+' | b'-')) {
+                i += 1;
+            }
+            i += required_digits(&buf[i..])?;
+        }
+
+        // more digits may follow the end of the buffer
+        if i == buf.len() {
+            return Err(Error::Truncated);
+        }
+        self.pos += i;
+        Ok(())
+    }
+}
+
+fn digits(buf: &[u8]) -> usize {
+    buf.iter().take_while(|b| b.is_ascii_digit()).count()
+}
+
+fn required_digits(buf: &[u8]) -> Result<usize, Error> {
+    match digits(buf) {
+        0 if buf.is_empty() => Err(Error::Truncated),
+        0 => Err(Error::Invalid),
+        n => Ok(n),
+    }
+}
+
+/// Returns the inspected prefix of a buffer, without a UTF-8 byte order mark.
+fn prefix(buf: &[u8]) -> &[u8] {
+    let buf = buf.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(buf);
+    &buf[..buf.len().min(MAX_LEN)]
+}
+
+/// Returns whether a buffer holds a JSON object or array, optionally cut off
+/// at the end of the buffer.
+pub(crate) fn is_document(buf: &[u8]) -> bool {
+    let mut parser = Parser::new(prefix(buf));
+    if !matches!(parser.peek(), Ok(b'{' | b'[')) {
+        return false;
+    }
+
+    match parser.value(0) {
+        // only whitespace may follow
+        Ok(_) => parser.peek() == Err(Error::Truncated),
+        // a cut off document has to contain at least one complete value
+        Err(Error::Truncated) => parser.values > 0,
+        Err(Error::Invalid) => false,
+    }
+}
+
+/// Returns whether a buffer holds newline-delimited JSON objects or arrays,
+/// with at least two complete lines.
+pub(crate) fn is_lines(buf: &[u8]) -> bool {
+    let mut lines = prefix(buf).split(|&b| b == b'\n').peekable();
+    let mut complete = 0;
+
+    while let Some(line) = lines.next() {
+        let mut parser = Parser::new(line);
+        match parser.peek() {
+            Ok(b'{' | b'[') => {}
+            Err(_) => continue,
+            Ok(_) => return false,
+        }
+        match parser.value(0) {
+            Ok(_) if parser.peek() == Err(Error::Truncated) => complete += 1,
+            // only the last line may be cut off
+            Err(Error::Truncated) if lines.peek().is_none() => {}
+            _ => return false,
+        }
+    }
+    complete >= 2
+}
+
+/// Returns the kind of JSON document identified by the members of its root object.
+pub(crate) fn kind(buf: &[u8]) -> Option<Kind> {
+    if !is_document(buf) {
+        return None;
+    }
+
+    let mut geo_type = false;
+    let mut topology = false;
+    let mut notebook = false;
+    let mut gltf = false;
+    let mut schema = false;
+    let mut source_map_version = false;
+    let mut mappings = false;
+    let mut app_name = false;
+    let mut app_member = false;
+
+    for (i, (key, value)) in members(prefix(buf)).enumerate() {
+        match key {
+            b"type" => {
+                let value = string(value);
+                topology |= value == Some(b"Topology");
+                geo_type |= value.is_some_and(|v| GEOJSON_TYPES.contains(&v));
+            }
+            b"$schema" => {
+                schema |= string(value).is_some_and(|v| match_bytes(v, b"json-schema.org"));
+            }
+            b"nbformat" => notebook |= !value.is_empty() && digits(value) == value.len(),
+            // notebooks are written with sorted keys, so `nbformat` may be cut off
+            b"cells" if i == 0 => {
+                notebook |= first_element(value)
+                    .is_some_and(|cell| members(cell).any(|(key, _)| key == b"cell_type"));
+            }
+            b"asset" => {
+                gltf |=
+                    members(value).any(|(key, value)| key == b"version" && string(value).is_some());
+            }
+            b"version" => source_map_version |= value == b"3",
+            b"mappings" | b"sections" => mappings = true,
+            b"name" | b"short_name" => app_name |= string(value).is_some(),
+            b"start_url" | b"display" | b"icons" => app_member = true,
+            _ => {}
+        }
+    }
+
+    if schema {
+        Some(Kind::JsonSchema)
+    } else if notebook {
+        Some(Kind::Notebook)
+    } else if gltf {
+        Some(Kind::Gltf)
+    } else if source_map_version && mappings {
+        Some(Kind::SourceMap)
+    } else if topology {
+        Some(Kind::TopoJson)
+    } else if geo_type {
+        Some(Kind::GeoJson)
+    } else if app_name && app_member {
+        Some(Kind::WebManifest)
+    } else {
+        None
+    }
+}
+
+/// Iterator over the members of an object, which may be cut off.
+struct Members<'a> {
+    parser: Parser<'a>,
+    done: bool,
+}
+
+impl<'a> Iterator for Members<'a> {
+    type Item = (&'a [u8], &'a [u8]);
+
+    fn next(&mut self) -> Option<(&'a [u8], &'a [u8])> {
+        if self.done {
+            return None;
+        }
+        let member = self.member();
+        if member.is_none() {
+            self.done = true;
+        }
+        member
+    }
+}
+
+impl<'a> Members<'a> {
+    fn member(&mut self) -> Option<(&'a [u8], &'a [u8])> {
+        let key = self.parser.string().ok()?;
+        self.parser.expect(b':').ok()?;
+
+        let start = self.parser.pos;
+        let value = match self.parser.value(1) {
+            Ok(value) => value,
+            Err(Error::Truncated) => {
+                self.done = true;
+                trim_start(&self.parser.buf[start..])
+            }
+            Err(Error::Invalid) => return None,
+        };
+
+        match self.parser.peek() {
+            Ok(b',') => self.parser.pos += 1,
+            _ => self.done = true,
+        }
+        Some((key, value))
+    }
+}
+
+/// Returns the members of the object at the start of the buffer.
+fn members(buf: &[u8]) -> Members<'_> {
+    let mut parser = Parser::new(buf);
+    let done = parser.expect(b'{').is_err() || parser.peek() == Ok(b'}');
+    Members { parser, done }
+}
+
+/// Returns the first element of the array at the start of the buffer.
+fn first_element(buf: &[u8]) -> Option<&[u8]> {
+    let mut parser = Parser::new(buf);
+    parser.expect(b'[').ok()?;
+    let start = parser.pos;
+    match parser.value(1) {
+        Ok(value) => Some(value),
+        Err(Error::Truncated) => Some(trim_start(&buf[start..])),
+        Err(Error::Invalid) => None,
+    }
+}
+
+/// Returns the content of a complete string value.
+fn string(value: &[u8]) -> Option<&[u8]> {
+    Parser::new(value).string().ok()
+}
+
+fn trim_start(buf: &[u8]) -> &[u8] {
+    let start = buf
+        .iter()
+        .position(|b| !matches!(b, b' ' | b'\t' | b'\n' | b'\r'))
+        .unwrap_or(buf.len());
+    &buf[start..]
+}
@@ -0,0 +1,350 @@
+//! Mach-O header parsing.
+//!
+//! Thin Mach-O files start with a single header. Universal ("fat") binaries
+//! start with a table of slices, each of them a thin Mach-O file for one CPU.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use infer::macho::{self, Cpu, FileType, Width};
+//!
+//! let buf = include_bytes!("../../testdata/sample_mach_x64");
+//! let macho = macho::info(buf).expect("Mach-O header is valid");
+//!
+//! assert_eq!(macho.cpu(), Cpu::X86_64);
+//! assert_eq!(macho.width(), Width::Bits64);
+//! assert_eq!(macho.file_type(), FileType::Executable);
+//!
+//! let buf = include_bytes!("../../testdata/sample_mach_fat");
+//! let cpus: Vec<Cpu> = macho::slices(buf).unwrap().map(|slice| slice.cpu()).collect();
+//! assert_eq!(cpus, [Cpu::X86, Cpu::PowerPc]);
+//! ```
+
+use super::{u32_be, u64_be};
+
+const MH_MAGIC: u32 = 0xFEED_FACE;
+const MH_MAGIC_64: u32 = 0xFEED_FACF;
+const FAT_MAGIC: u32 = 0xCAFE_BABE;
+const FAT_MAGIC_64: u32 = 0xCAFE_BABF;
+
+const CPU_SUBTYPE_MASK: u32 = 0xFF00_0000;
+
+/// Java class files share the magic of universal binaries, but their
+/// version, where the number of slices is, starts at 45.
+const MAX_SLICES: u32 = 45;
+
+/// Information from the header of a thin Mach-O file.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct MachO {
+    cpu: Cpu,
+    cpu_subtype: u32,
+    width: Width,
+    file_type: FileType,
+    flags: u32,
+}
+
+impl MachO {
+    /// Returns the CPU the file targets.
+    #[must_use]
+    pub const fn cpu(&self) -> Cpu {
+        self.cpu
+    }
+
+    /// Returns the CPU subtype, without the capability bits.
+    #[must_use]
+    pub const fn cpu_subtype(&self) -> u32 {
+        self.cpu_subtype
+    }
+
+    /// Returns whether the file is 32 or 64-bit.
+    #[must_use]
+    pub const fn width(&self) -> Width {
+        self.width
+    }
+
+    /// Returns the kind of Mach-O file.
+    #[must_use]
+    pub const fn file_type(&self) -> FileType {
+        self.file_type
+    }
+
+    /// Returns the `flags` field of the header.
+    #[must_use]
+    pub const fn flags(&self) -> u32 {
+        self.flags
+    }
+}
+
+/// Word size of a Mach-O file.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Width {
+    /// 32-bit, `MH_MAGIC`.
+    Bits32,
+    /// 64-bit, `MH_MAGIC_64`.
+    Bits64,
+}
+
+/// CPU type, from the `cputype` field.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Cpu {
+    /// Intel x86.
+    X86,
+    /// x86-64.
+    X86_64,
+    /// ARM, 32-bit.
+    Arm,
+    /// ARM64, including arm64e.
+    Arm64,
+    /// ARM64 with 32-bit pointers, used by watchOS.
+    Arm64_32,
+    /// PowerPC.
+    PowerPc,
+    /// 64-bit PowerPC.
+    PowerPc64,
+    /// Any other value.
+    Other(u32),
+}
+
+impl Cpu {
+    fn from_u32(value: u32) -> Cpu {
+        match value {
+            7 => Cpu::X86,
+            0x0100_0007 => Cpu::X86_64,
+            12 => Cpu::Arm,
+            0x0100_000C => Cpu::Arm64,
+            0x0200_000C => Cpu::Arm64_32,
+            18 => Cpu::PowerPc,
+            0x0100_0012 => Cpu::PowerPc64,
+            other => Cpu::Other(other),
+        }
+    }
+
+    /// Returns a short name of the architecture, e.g. `x86_64` or `aarch64`.
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Cpu::X86 => "x86",
+            Cpu::X86_64 => "x86_64",
+            Cpu::Arm => "arm",
+            Cpu::Arm64 => "aarch64",
+            Cpu::Arm64_32 => "arm64_32",
+            Cpu::PowerPc => "powerpc",
+            Cpu::PowerPc64 => "powerpc64",
+            Cpu::Other(_) => "unknown",
+        }
+    }
+}
+
+/// Kind of Mach-O file, from the `filetype` field.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum FileType {
+    /// Relocatable object file (`MH_OBJECT`), e.g. `.o`.
+    Object,
+    /// Executable (`MH_EXECUTE`).
+    Executable,
+    /// Fixed VM shared library (`MH_FVMLIB`).
+    FixedVmLibrary,
+    /// Core dump (`MH_CORE`).
+    Core,
+    /// Preloaded executable (`MH_PRELOAD`).
+    Preload,
+    /// Dynamic library (`MH_DYLIB`), e.g. `.dylib`.
+    Dylib,
+    /// Dynamic linker (`MH_DYLINKER`).
+    Dylinker,
+    /// Loadable bundle (`MH_BUNDLE`), e.g. `.bundle`.
+    Bundle,
+    /// Stub of a dynamic library, without code (`MH_DYLIB_STUB`).
+    DylibStub,
+    /// Debug symbols (`MH_DSYM`), the DWARF file of a `.dSYM` bundle.
+    Dsym,
+    /// Kernel extension (`MH_KEXT_BUNDLE`).
+    KextBundle,
+    /// Set of Mach-O files (`MH_FILESET`), e.g. a kernel collection.
+    Fileset,
+    /// Any other value.
+    Other(u32),
+}
+
+impl FileType {
+    fn from_u32(value: u32) -> FileType {
+        match value {
+            1 => FileType::Object,
+            2 => FileType::Executable,
+            3 => FileType::FixedVmLibrary,
+            4 => FileType::Core,
+            5 => FileType::Preload,
+            6 => FileType::Dylib,
+            7 => FileType::Dylinker,
+            8 => FileType::Bundle,
+            9 => FileType::DylibStub,
+            10 => FileType::Dsym,
+            11 => FileType::KextBundle,
+            12 => FileType::Fileset,
+            other => FileType::Other(other),
+        }
+    }
+}
+
+/// A slice of a universal binary.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Slice {
+    cpu: Cpu,
+    cpu_subtype: u32,
+    offset: u64,
+    size: u64,
+    info: Option<MachO>,
+}
+
+impl Slice {
+    /// Returns the CPU of the slice, as listed in the table of slices.
+    #[must_use]
+    pub const fn cpu(&self) -> Cpu {
+        self.cpu
+    }
+
+    /// Returns the CPU subtype of the slice, without the capability bits.
+    #[must_use]
+    pub const fn cpu_subtype(&self) -> u32 {
+        self.cpu_subtype
+    }
+
+    /// Returns the offset of the slice from the start of the file.
+    #[must_use]
+    pub const fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Returns the size of the slice in bytes.
+    #[must_use]
+    pub const fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Returns the information from the header of the slice, if it is within
+    /// the buffer.
+    #[must_use]
+    pub const fn info(&self) -> Option<MachO> {
+        self.info
+    }
+}
+
+/// Iterator over the slices of a universal binary, see [`slices`].
+#[derive(Debug, Clone)]
+pub struct Slices<'a> {
+    buf: &'a [u8],
+    is_64: bool,
+    index: u32,
+    count: u32,
+}
+
+impl Iterator for Slices<'_> {
+    type Item = Slice;
+
+    fn next(&mut self) -> Option<Slice> {
+        if self.index >= self.count {
+            return None;
+        }
+        let entry_len = if self.is_64 { 32 } else { 20 };
+        let entry = 8 + self.index as usize * entry_len;
+        self.index += 1;
+
+        let (offset, size) = if self.is_64 {
+            (u64_be(self.buf, entry + 8), u64_be(self.buf, entry + 16))
+        } else {
+            (
+                u32_be(self.buf, entry + 8).map(u64::from),
+                u32_be(self.buf, entry + 12).map(u64::from),
+            )
+        };
+        let (Some(cputype), Some(cpu_subtype), Some(offset), Some(size)) = (
+            u32_be(self.buf, entry),
+            u32_be(self.buf, entry + 4),
+            offset,
+            size,
+        ) else {
+            // the rest of the table is cut off
+            self.index = self.count;
+            return None;
+        };
+
+        let info = usize::try_from(offset)
+            .ok()
+            .and_then(|offset| self.buf.get(offset..))
+            .and_then(thin_info);
+
+        Some(Slice {
+            cpu: Cpu::from_u32(cputype),
+            cpu_subtype: cpu_subtype & !CPU_SUBTYPE_MASK,
+            offset,
+            size,
+            info,
+        })
+    }
+}
+
+/// Returns the information from the header of a Mach-O file.
+///
+/// For universal binaries, this is the header of the first slice within the
+/// buffer. Slices are usually page aligned, so the buffer may have to be
+/// larger than the one used by [`crate::get_from_path`] to reach them.
+#[must_use]
+pub fn info(buf: &[u8]) -> Option<MachO> {
+    match slices(buf) {
+        Some(mut slices) => slices.find_map(|slice| slice.info),
+        None => thin_info(buf),
+    }
+}
+
+/// Returns an iterator over the slices of a universal binary, or `None` if the
+/// buffer is not a universal binary.
+///
+/// Iteration stops early if the table of slices is cut off.
+#[must_use]
+pub fn slices(buf: &[u8]) -> Option<Slices<'_>> {
+    let is_64 = match u32_be(buf, 0)? {
+        FAT_MAGIC => false,
+        FAT_MAGIC_64 => true,
+        _ => return None,
+    };
+    let count = u32_be(buf, 4)?;
+    if count >= MAX_SLICES {
+        return None;
+    }
+
+    Some(Slices {
+        buf,
+        is_64,
+        index: 0,
+        count,
+    })
+}
+
+/// Returns the information from the header of a thin Mach-O file.
+fn thin_info(buf: &[u8]) -> Option<MachO> {
+    let magic = u32_be(buf, 0)?;
+    let (width, big_endian) = match magic {
+        MH_MAGIC => (Width::Bits32, true),
+        MH_MAGIC_64 => (Width::Bits64, true),
+        _ if magic.swap_bytes() == MH_MAGIC => (Width::Bits32, false),
+        _ if magic.swap_bytes() == MH_MAGIC_64 => (Width::Bits64, false),
+        _ => return None,
+    };
+    let field = |offset| {
+        u32_be(buf, offset).map(|value| {
+            if big_endian {
+                value
+            } else {
+                value.swap_bytes()
+            }
+        })
+    };
+
+    Some(MachO {
+        cpu: Cpu::from_u32(field(4)?),
+        cpu_subtype: field(8)? & !CPU_SUBTYPE_MASK,
+        width,
+        file_type: FileType::from_u32(field(12)?),
+        flags: field(24)?,
+    })
+}
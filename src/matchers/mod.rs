@@ -1,13 +1,28 @@
 pub mod app;
 pub mod archive;
 pub mod audio;
+mod audio_info;
 pub mod book;
+mod compound;
+mod csv;
+pub(crate) mod disk;
 pub mod doc;
+pub mod elf;
+pub mod filesystem;
 pub mod font;
 pub mod image;
+mod image_info;
+mod isobmff;
+pub mod java;
+mod json;
+pub mod macho;
 pub mod odf;
+pub mod pe;
+mod riff;
 pub mod text;
 pub mod video;
+pub mod xml;
+pub mod zip;
 
 pub(crate) fn compare_bytes(slice: &[u8], sub_slice: &[u8], start_offset: usize) -> bool {
     let sl = sub_slice.len();
@@ -32,3 +47,39 @@ pub(crate) fn match_bytes(slice: &[u8], sub_slice: &[u8]) -> bool {
         .windows(sub_slice.len())
         .any(|window| window == sub_slice)
 }
+
+pub(crate) fn u16_le(buf: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(
+        buf.get(offset..offset.checked_add(2)?)?.try_into().ok()?,
+    ))
+}
+
+pub(crate) fn u16_be(buf: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(
+        buf.get(offset..offset.checked_add(2)?)?.try_into().ok()?,
+    ))
+}
+
+pub(crate) fn u32_le(buf: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(
+        buf.get(offset..offset.checked_add(4)?)?.try_into().ok()?,
+    ))
+}
+
+pub(crate) fn u32_be(buf: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(
+        buf.get(offset..offset.checked_add(4)?)?.try_into().ok()?,
+    ))
+}
+
+pub(crate) fn u64_le(buf: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(
+        buf.get(offset..offset.checked_add(8)?)?.try_into().ok()?,
+    ))
+}
+
+pub(crate) fn u64_be(buf: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_be_bytes(
+        buf.get(offset..offset.checked_add(8)?)?.try_into().ok()?,
+    ))
+}
@@ -1,4 +1,6 @@
 use super::compare_bytes;
+use super::xml::{self, Dialect};
+use super::zip::{self, Container};
 
 #[derive(Debug, Eq, PartialEq)]
 enum DocType {
@@ -25,6 +27,42 @@ pub fn is_odp(buf: &[u8]) -> bool {
     odf(buf) == Some(DocType::Presentation)
 }
 
+/// Returns whether a buffer is Flat XML `OpenDocument` Text
+#[must_use]
+pub fn is_fodt(buf: &[u8]) -> bool {
+    flat_odf(buf) == Some(b"text")
+}
+
+/// Returns whether a buffer is Flat XML `OpenDocument` Spreadsheet
+#[must_use]
+pub fn is_fods(buf: &[u8]) -> bool {
+    flat_odf(buf) == Some(b"spreadsheet")
+}
+
+/// Returns whether a buffer is Flat XML `OpenDocument` Presentation
+#[must_use]
+pub fn is_fodp(buf: &[u8]) -> bool {
+    flat_odf(buf) == Some(b"presentation")
+}
+
+/// Returns whether a buffer is Flat XML `OpenDocument` Graphics
+#[must_use]
+pub fn is_fodg(buf: &[u8]) -> bool {
+    flat_odf(buf) == Some(b"graphics")
+}
+
+/// Returns the document kind of a flat `OpenDocument` file, e.g. `text`,
+/// from the `office:mimetype` attribute of its root element.
+fn flat_odf(buf: &[u8]) -> Option<&[u8]> {
+    if xml::dialect(buf) != Some(Dialect::FlatOdf) {
+        return None;
+    }
+    xml::root(buf)?
+        .attributes()
+        .find_map(|(name, value)| name.ends_with(b":mimetype").then_some(value))?
+        .strip_prefix(b"application/vnd.oasis.opendocument.")
+}
+
 fn odf(buf: &[u8]) -> Option<DocType> {
     let signature = [b'P', b'K', 0x03, 0x04];
 
@@ -33,6 +71,15 @@ fn odf(buf: &[u8]) -> Option<DocType> {
         return None;
     }
 
+    if let Some(container) = zip::inspect(buf) {
+        return match container {
+            Some(Container::ODT) => Some(DocType::Text),
+            Some(Container::ODS) => Some(DocType::Spreadsheet),
+            Some(Container::ODP) => Some(DocType::Presentation),
+            _ => None,
+        };
+    }
+
     // Check mimetype
     if !compare_bytes(buf, b"mimetype", 0x1E) {
         return None;
@@ -0,0 +1,241 @@
+//! Portable Executable (PE) header parsing.
+//!
+//! EXE, DLL, driver and EFI images all start with the `MZ` magic of the DOS
+//! header. They are told apart by the PE/COFF header that `e_lfanew` points to.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use infer::pe::{self, Format, Machine, Subsystem};
+//!
+//! let buf = include_bytes!("../../testdata/sample.exe");
+//! let pe = pe::info(buf).expect("PE header is valid");
+//!
+//! assert_eq!(pe.machine(), Machine::Amd64);
+//! assert_eq!(pe.format(), Format::Pe32Plus);
+//! assert!(!pe.is_dll());
+//! ```
+
+use super::{u16_le, u32_le};
+
+const IMAGE_FILE_DLL: u16 = 0x2000;
+
+/// Information from the PE/COFF header of an image.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Pe {
+    machine: Machine,
+    format: Format,
+    subsystem: Subsystem,
+    characteristics: u16,
+}
+
+impl Pe {
+    /// Returns the architecture of the image.
+    #[must_use]
+    pub const fn machine(&self) -> Machine {
+        self.machine
+    }
+
+    /// Returns whether the image is PE32 or PE32+.
+    #[must_use]
+    pub const fn format(&self) -> Format {
+        self.format
+    }
+
+    /// Returns the subsystem required to run the image.
+    #[must_use]
+    pub const fn subsystem(&self) -> Subsystem {
+        self.subsystem
+    }
+
+    /// Returns the `Characteristics` field of the COFF header.
+    #[must_use]
+    pub const fn characteristics(&self) -> u16 {
+        self.characteristics
+    }
+
+    /// Returns whether the image is a dynamic-link library.
+    #[must_use]
+    pub const fn is_dll(&self) -> bool {
+        self.characteristics & IMAGE_FILE_DLL != 0
+    }
+}
+
+/// Format of the optional header.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Format {
+    /// PE32, for 32-bit images.
+    Pe32,
+    /// PE32+, for 64-bit images.
+    Pe32Plus,
+}
+
+/// Architecture of an image, from the `Machine` field.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Machine {
+    /// Any machine.
+    Unknown,
+    /// Intel 386.
+    I386,
+    /// x64.
+    Amd64,
+    /// ARM, little endian.
+    Arm,
+    /// ARM Thumb-2, little endian.
+    ArmNt,
+    /// ARM64, little endian.
+    Arm64,
+    /// ARM64EC, ARM64 code interoperable with x64.
+    Arm64Ec,
+    /// Intel Itanium.
+    Ia64,
+    /// 32-bit RISC-V.
+    RiscV32,
+    /// 64-bit RISC-V.
+    RiscV64,
+    /// 64-bit LoongArch.
+    LoongArch64,
+    /// EFI byte code.
+    Ebc,
+    /// Any other value.
+    Other(u16),
+}
+
+impl Machine {
+    fn from_u16(value: u16) -> Machine {
+        match value {
+            0x0000 => Machine::Unknown,
+            0x014C => Machine::I386,
+            0x8664 => Machine::Amd64,
+            0x01C0 => Machine::Arm,
+            0x01C4 => Machine::ArmNt,
+            0xAA64 => Machine::Arm64,
+            0xA641 => Machine::Arm64Ec,
+            0x0200 => Machine::Ia64,
+            0x5032 => Machine::RiscV32,
+            0x5064 => Machine::RiscV64,
+            0x6264 => Machine::LoongArch64,
+            0x0EBC => Machine::Ebc,
+            other => Machine::Other(other),
+        }
+    }
+
+    /// Returns a short name of the architecture, e.g. `x86_64` or `aarch64`.
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Machine::I386 => "x86",
+            Machine::Amd64 => "x86_64",
+            Machine::Arm | Machine::ArmNt => "arm",
+            Machine::Arm64 => "aarch64",
+            Machine::Arm64Ec => "arm64ec",
+            Machine::Ia64 => "ia64",
+            Machine::RiscV32 => "riscv32",
+            Machine::RiscV64 => "riscv64",
+            Machine::LoongArch64 => "loongarch64",
+            Machine::Ebc => "ebc",
+            Machine::Unknown | Machine::Other(_) => "unknown",
+        }
+    }
+}
+
+/// Subsystem required to run an image, from the optional header.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Subsystem {
+    /// Unknown subsystem.
+    Unknown,
+    /// Device drivers and native Windows processes.
+    Native,
+    /// Windows graphical user interface.
+    WindowsGui,
+    /// Windows console.
+    WindowsConsole,
+    /// OS/2 console.
+    Os2Console,
+    /// POSIX console.
+    PosixConsole,
+    /// Native Win9x driver.
+    NativeWindows,
+    /// Windows CE.
+    WindowsCeGui,
+    /// EFI application.
+    EfiApplication,
+    /// EFI driver with boot services.
+    EfiBootServiceDriver,
+    /// EFI driver with runtime services.
+    EfiRuntimeDriver,
+    /// EFI ROM image.
+    EfiRom,
+    /// Xbox.
+    Xbox,
+    /// Windows boot application.
+    WindowsBootApplication,
+    /// Any other value.
+    Other(u16),
+}
+
+impl Subsystem {
+    fn from_u16(value: u16) -> Subsystem {
+        match value {
+            0 => Subsystem::Unknown,
+            1 => Subsystem::Native,
+            2 => Subsystem::WindowsGui,
+            3 => Subsystem::WindowsConsole,
+            5 => Subsystem::Os2Console,
+            7 => Subsystem::PosixConsole,
+            8 => Subsystem::NativeWindows,
+            9 => Subsystem::WindowsCeGui,
+            10 => Subsystem::EfiApplication,
+            11 => Subsystem::EfiBootServiceDriver,
+            12 => Subsystem::EfiRuntimeDriver,
+            13 => Subsystem::EfiRom,
+            14 => Subsystem::Xbox,
+            16 => Subsystem::WindowsBootApplication,
+            other => Subsystem::Other(other),
+        }
+    }
+
+    /// Returns whether the subsystem is one of the EFI ones.
+    #[must_use]
+    pub const fn is_efi(&self) -> bool {
+        matches!(
+            self,
+            Subsystem::EfiApplication
+                | Subsystem::EfiBootServiceDriver
+                | Subsystem::EfiRuntimeDriver
+                | Subsystem::EfiRom
+        )
+    }
+}
+
+/// Returns the information from the PE/COFF header of an image.
+///
+/// Returns `None` for DOS executables without a PE header, and if the header
+/// is not within the buffer.
+#[must_use]
+pub fn info(buf: &[u8]) -> Option<Pe> {
+    if buf.len() < 64 || buf[..2] != *b"MZ" {
+        return None;
+    }
+
+    let pe = usize::try_from(u32_le(buf, 0x3C)?).ok()?;
+    if buf.get(pe..pe.checked_add(4)?)? != b"PE\0\0" {
+        return None;
+    }
+    let coff = pe + 4;
+    let optional = coff + 20;
+
+    let format = match u16_le(buf, optional)? {
+        0x10B => Format::Pe32,
+        0x20B => Format::Pe32Plus,
+        _ => return None,
+    };
+
+    Some(Pe {
+        machine: Machine::from_u16(u16_le(buf, coff)?),
+        format,
+        // at the same offset in both formats
+        subsystem: Subsystem::from_u16(u16_le(buf, optional + 68)?),
+        characteristics: u16_le(buf, coff + 18)?,
+    })
+}
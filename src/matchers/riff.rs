@@ -0,0 +1,56 @@
+//! Resource Interchange File Format (RIFF) form type dispatch.
+//!
+//! RIFF files start with a `RIFF` chunk whose first four data bytes name the form
+//! type, e.g. `RIFF....WAVE`. The 64-bit `RF64` and `BW64` variants of WAVE
+//! replace the chunk identifier instead.
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum Kind {
+    Wav,
+    Rf64,
+    Bw64,
+    Avi,
+    Webp,
+    Ani,
+    Cdr,
+    Sf2,
+    Dls,
+    Rmi,
+    Qcp,
+}
+
+const FORMS: &[(&[u8; 4], Kind)] = &[
+    (b"WAVE", Kind::Wav),
+    (b"AVI ", Kind::Avi),
+    (b"WEBP", Kind::Webp),
+    (b"ACON", Kind::Ani),
+    (b"sfbk", Kind::Sf2),
+    (b"DLS ", Kind::Dls),
+    (b"RMID", Kind::Rmi),
+    (b"QLCM", Kind::Qcp),
+];
+
+/// Returns the kind of RIFF data identified by its form type.
+pub(crate) fn kind(buf: &[u8]) -> Option<Kind> {
+    if buf.len() < 12 {
+        return None;
+    }
+
+    let form = &buf[8..12];
+    match &buf[..4] {
+        b"RIFF" | b"RIFX" => {}
+        b"RF64" => return (form == b"WAVE").then_some(Kind::Rf64),
+        b"BW64" => return (form == b"WAVE").then_some(Kind::Bw64),
+        _ => return None,
+    }
+
+    // CorelDRAW form types carry the version in the last byte, e.g. `CDR9` or `CDRA`.
+    if &form[..3] == b"CDR" || &form[..3] == b"cdr" {
+        return Some(Kind::Cdr);
+    }
+
+    FORMS
+        .iter()
+        .find(|(f, _)| f.as_slice() == form)
+        .map(|(_, kind)| *kind)
+}
@@ -1,3 +1,9 @@
+use super::csv;
+use super::json::{self, Kind};
+use super::xml::{self, Dialect};
+
+pub use super::csv::CsvDialect;
+
 /// Returns whether a buffer is html data.
 ///
 /// Conforms to [whatwg](https://mimesniff.spec.whatwg.org/)
@@ -50,6 +56,134 @@ pub fn is_xml(buf: &[u8]) -> bool {
     starts_with_ignore_ascii_case(buf, val)
 }
 
+/// Returns whether a buffer is an RSS feed.
+#[must_use]
+pub fn is_rss(buf: &[u8]) -> bool {
+    xml::dialect(buf) == Some(Dialect::Rss)
+}
+
+/// Returns whether a buffer is an Atom feed.
+#[must_use]
+pub fn is_atom(buf: &[u8]) -> bool {
+    xml::dialect(buf) == Some(Dialect::Atom)
+}
+
+/// Returns whether a buffer is a Keyhole Markup Language (KML) document.
+#[must_use]
+pub fn is_kml(buf: &[u8]) -> bool {
+    xml::dialect(buf) == Some(Dialect::Kml)
+}
+
+/// Returns whether a buffer is a GPS Exchange Format (GPX) document.
+#[must_use]
+pub fn is_gpx(buf: &[u8]) -> bool {
+    xml::dialect(buf) == Some(Dialect::Gpx)
+}
+
+/// Returns whether a buffer is an XHTML document.
+#[must_use]
+pub fn is_xhtml(buf: &[u8]) -> bool {
+    xml::dialect(buf) == Some(Dialect::Xhtml)
+}
+
+/// Returns whether a buffer is an XSLT stylesheet.
+#[must_use]
+pub fn is_xslt(buf: &[u8]) -> bool {
+    xml::dialect(buf) == Some(Dialect::Xslt)
+}
+
+/// Returns whether a buffer is an XML property list.
+#[must_use]
+pub fn is_plist(buf: &[u8]) -> bool {
+    xml::dialect(buf) == Some(Dialect::Plist)
+}
+
+/// Returns whether a buffer is a MusicXML score.
+#[must_use]
+pub fn is_musicxml(buf: &[u8]) -> bool {
+    xml::dialect(buf) == Some(Dialect::MusicXml)
+}
+
+/// Returns whether a buffer is an XLIFF localization document.
+#[must_use]
+pub fn is_xliff(buf: &[u8]) -> bool {
+    xml::dialect(buf) == Some(Dialect::Xliff)
+}
+
+/// Returns whether a buffer is a DocBook document.
+#[must_use]
+pub fn is_docbook(buf: &[u8]) -> bool {
+    xml::dialect(buf) == Some(Dialect::DocBook)
+}
+
+/// Returns whether a buffer is an Android XML resource, e.g. a layout.
+#[must_use]
+pub fn is_android_xml(buf: &[u8]) -> bool {
+    xml::dialect(buf) == Some(Dialect::Android)
+}
+
+/// Returns whether a buffer is a Maven project object model (POM).
+#[must_use]
+pub fn is_pom(buf: &[u8]) -> bool {
+    xml::dialect(buf) == Some(Dialect::Pom)
+}
+
+/// Returns whether a buffer is a JSON object or array.
+///
+/// A document cut off at the end of the buffer is accepted.
+#[must_use]
+pub fn is_json(buf: &[u8]) -> bool {
+    json::is_document(buf)
+}
+
+/// Returns whether a buffer is newline-delimited JSON (NDJSON or JSON Lines).
+#[must_use]
+pub fn is_ndjson(buf: &[u8]) -> bool {
+    json::is_lines(buf)
+}
+
+/// Returns whether a buffer is a GeoJSON document.
+#[must_use]
+pub fn is_geojson(buf: &[u8]) -> bool {
+    json::kind(buf) == Some(Kind::GeoJson)
+}
+
+/// Returns whether a buffer is a TopoJSON topology.
+#[must_use]
+pub fn is_topojson(buf: &[u8]) -> bool {
+    json::kind(buf) == Some(Kind::TopoJson)
+}
+
+/// Returns whether a buffer is a Jupyter notebook.
+#[must_use]
+pub fn is_ipynb(buf: &[u8]) -> bool {
+    json::kind(buf) == Some(Kind::Notebook)
+}
+
+/// Returns whether a buffer is a glTF JSON model.
+#[must_use]
+pub fn is_gltf(buf: &[u8]) -> bool {
+    json::kind(buf) == Some(Kind::Gltf)
+}
+
+/// Returns whether a buffer is a JSON Schema.
+#[must_use]
+pub fn is_json_schema(buf: &[u8]) -> bool {
+    json::kind(buf) == Some(Kind::JsonSchema)
+}
+
+/// Returns whether a buffer is a Web App Manifest.
+#[must_use]
+pub fn is_webmanifest(buf: &[u8]) -> bool {
+    json::kind(buf) == Some(Kind::WebManifest)
+}
+
+/// Returns whether a buffer is a source map.
+#[must_use]
+pub fn is_source_map(buf: &[u8]) -> bool {
+    json::kind(buf) == Some(Kind::SourceMap)
+}
+
 /// Strip whitespaces at the beginning of the buffer.
 ///
 /// Follows <https://mimesniff.spec.whatwg.org>
@@ -65,7 +199,8 @@ fn trim_start_whitespaces(mut buf: &[u8]) -> &[u8] {
 }
 
 /// Strip BOM at the beginning of the buffer.
-fn trim_start_byte_order_marks(mut buf: &[u8]) -> &[u8] {
+#[must_use]
+pub fn trim_start_byte_order_marks(mut buf: &[u8]) -> &[u8] {
     while buf.len() >= 3 {
         match (buf[0], buf[1], buf[2]) {
             (0xEF, 0xBB, 0xBF) => buf = &buf[3..],                // UTF-8
@@ -80,10 +215,393 @@ fn starts_with_ignore_ascii_case(buf: &[u8], needle: &[u8]) -> bool {
     buf.len() >= needle.len() && buf[..needle.len()].eq_ignore_ascii_case(needle)
 }
 
+const PYTHON: &[&[u8]] = &[b"python", b"pypy"];
+const NODE: &[&[u8]] = &[b"node", b"nodejs"];
+const PERL: &[&[u8]] = &[b"perl"];
+const RUBY: &[&[u8]] = &[b"ruby", b"jruby"];
+const PHP: &[&[u8]] = &[b"php"];
+const LUA: &[&[u8]] = &[b"lua", b"luajit"];
+const AWK: &[&[u8]] = &[b"awk", b"gawk", b"mawk", b"nawk"];
+
+/// Maximum number of bytes inspected by [`charset`], the length of the prefix
+/// read from files.
+const CHARSET_LEN: usize = 8192;
+
 /// Returns whether a buffer is a shell script.
+///
+/// Any script with a shebang line is a shell script, unless its interpreter
+/// is one of the other languages detected, e.g. Python. A bare `#!` is not.
 #[must_use]
 pub fn is_shellscript(buf: &[u8]) -> bool {
-    buf.len() > 2 && &buf[..2] == b"#!"
+    buf.len() > 2
+        && buf.starts_with(b"#!")
+        && ![PYTHON, NODE, PERL, RUBY, PHP, LUA, AWK]
+            .iter()
+            .any(|interpreters| is_script(buf, interpreters))
+}
+
+/// Returns whether a buffer is a Python script.
+#[must_use]
+pub fn is_python(buf: &[u8]) -> bool {
+    is_script(buf, PYTHON)
+}
+
+/// Returns whether a buffer is a JavaScript script run by Node.js.
+#[must_use]
+pub fn is_javascript(buf: &[u8]) -> bool {
+    is_script(buf, NODE)
+}
+
+/// Returns whether a buffer is a Perl script.
+#[must_use]
+pub fn is_perl(buf: &[u8]) -> bool {
+    is_script(buf, PERL)
+}
+
+/// Returns whether a buffer is a Ruby script.
+#[must_use]
+pub fn is_ruby(buf: &[u8]) -> bool {
+    is_script(buf, RUBY)
+}
+
+/// Returns whether a buffer is a PHP script.
+#[must_use]
+pub fn is_php(buf: &[u8]) -> bool {
+    is_script(buf, PHP)
+}
+
+/// Returns whether a buffer is a Lua script.
+#[must_use]
+pub fn is_lua(buf: &[u8]) -> bool {
+    is_script(buf, LUA)
+}
+
+/// Returns whether a buffer is an AWK script.
+#[must_use]
+pub fn is_awk(buf: &[u8]) -> bool {
+    is_script(buf, AWK)
+}
+
+fn is_script(buf: &[u8], interpreters: &[&[u8]]) -> bool {
+    interpreter(buf).is_some_and(|name| interpreters.contains(&name))
+}
+
+/// Returns the name of the interpreter of a script from its shebang line.
+///
+/// The directory and a trailing version number are removed from the name. If
+/// the interpreter is run with `env`, its options, including the command line
+/// split by `-S`, and variable assignments are skipped.
+///
+/// # Examples
+///
+/// ```rust
+/// use infer::text::interpreter;
+///
+/// assert_eq!(interpreter(b"#!/usr/bin/python3.12\n"), Some(&b"python"[..]));
+/// assert_eq!(interpreter(b"#!/usr/bin/env -S node --experimental-vm-modules\n"), Some(&b"node"[..]));
+/// assert_eq!(interpreter(b"#!/bin/sh\n"), Some(&b"sh"[..]));
+/// assert_eq!(interpreter(b"echo hello\n"), None);
+/// ```
+#[must_use]
+pub fn interpreter(buf: &[u8]) -> Option<&[u8]> {
+    let line = buf.strip_prefix(b"#!")?;
+    let line = &line[..line
+        .iter()
+        .position(|&b| b == b'\n' || b == b'\r')
+        .unwrap_or(line.len())];
+    let mut words = line
+        .split(|&b| b == b' ' || b == b'\t')
+        .filter(|word| !word.is_empty());
+
+    let mut name = basename(words.next()?);
+    if name == b"env" {
+        loop {
+            let word = words.next()?;
+            match word {
+                // `-S` splits the rest of the line, which the words already are
+                b"-S" | b"--split-string" | b"-i" | b"--ignore-environment" | b"--" => {}
+                b"-u" | b"--unset" | b"-C" | b"--chdir" => {
+                    words.next()?;
+                }
+                _ if word.starts_with(b"-S") => {
+                    name = basename(&word[2..]);
+                    break;
+                }
+                _ if word.starts_with(b"-") || word.contains(&b'=') => {}
+                _ => {
+                    name = basename(word);
+                    break;
+                }
+            }
+        }
+    }
+
+    // `python3.12` is run as `python`
+    let len = name
+        .iter()
+        .rposition(|&b| !b.is_ascii_digit() && b != b'.')
+        .map_or(0, |i| i + 1);
+    let name = &name[..len];
+    (!name.is_empty()).then_some(name)
+}
+
+fn basename(path: &[u8]) -> &[u8] {
+    path.rsplit(|&b| b == b'/').next().unwrap_or(path)
+}
+
+/// Character encoding of text data.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Charset {
+    /// 7-bit ASCII.
+    Ascii,
+    /// UTF-8.
+    Utf8,
+    /// UTF-16, big endian.
+    Utf16Be,
+    /// UTF-16, little endian.
+    Utf16Le,
+    /// UTF-32, big endian.
+    Utf32Be,
+    /// UTF-32, little endian.
+    Utf32Le,
+    /// ISO-8859-1 (Latin-1).
+    Iso8859_1,
+    /// Windows-1252.
+    Windows1252,
+}
+
+impl Charset {
+    /// Returns the IANA name of the charset, as used in the `charset` parameter of MIME types.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use infer::text::Charset;
+    ///
+    /// assert_eq!(Charset::Utf16Le.name(), "utf-16le");
+    /// ```
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Charset::Ascii => "us-ascii",
+            Charset::Utf8 => "utf-8",
+            Charset::Utf16Be => "utf-16be",
+            Charset::Utf16Le => "utf-16le",
+            Charset::Utf32Be => "utf-32be",
+            Charset::Utf32Le => "utf-32le",
+            Charset::Iso8859_1 => "iso-8859-1",
+            Charset::Windows1252 => "windows-1252",
+        }
+    }
+}
+
+/// Returns the charset of a buffer if it is text data.
+///
+/// The charset is identified by its byte order mark, by the pattern of NUL
+/// bytes of UTF-16 and UTF-32 text without one, by validating UTF-8 and
+/// finally by single-byte heuristics. A multi-byte sequence cut off at the
+/// end of the buffer is accepted, since the buffer is usually a file prefix.
+/// Only the first 8 KiB are inspected, and a byte order mark has to be
+/// followed by text.
+///
+/// # Examples
+///
+/// ```rust
+/// use infer::text::{self, Charset};
+///
+/// assert_eq!(text::charset(b"plain text\n"), Some(Charset::Ascii));
+/// assert_eq!(text::charset("caf\u{e9}\n".as_bytes()), Some(Charset::Utf8));
+/// assert_eq!(text::charset(b"\xFF\xFEh\x00i\x00"), Some(Charset::Utf16Le));
+/// assert_eq!(text::charset(&[0x00, 0x01, 0x02, 0x03]), None);
+/// ```
+#[must_use]
+pub fn charset(buf: &[u8]) -> Option<Charset> {
+    let buf = &buf[..buf.len().min(CHARSET_LEN)];
+    if buf.is_empty() {
+        return None;
+    }
+
+    // UTF-32 LE must be checked before UTF-16 LE, whose BOM is a prefix of it.
+    let (charset, text) = if let Some(text) = buf.strip_prefix(&[0xFF, 0xFE, 0x00, 0x00]) {
+        (Charset::Utf32Le, text)
+    } else if let Some(text) = buf.strip_prefix(&[0x00, 0x00, 0xFE, 0xFF]) {
+        (Charset::Utf32Be, text)
+    } else if let Some(text) = buf.strip_prefix(&[0xEF, 0xBB, 0xBF]) {
+        return (!text.is_empty() && is_utf8_text(text)).then_some(Charset::Utf8);
+    } else if let Some(text) = buf.strip_prefix(&[0xFE, 0xFF]) {
+        (Charset::Utf16Be, text)
+    } else if let Some(text) = buf.strip_prefix(&[0xFF, 0xFE]) {
+        (Charset::Utf16Le, text)
+    } else if let Some(charset) = wide_charset(buf) {
+        (charset, buf)
+    } else {
+        return narrow_charset(buf);
+    };
+
+    // a byte order mark alone is not text
+    (!text.is_empty() && is_wide_text(text, charset)).then_some(charset)
+}
+
+/// Returns whether a buffer is plain text data.
+///
+/// See [`charset`] for the supported encodings.
+#[must_use]
+pub fn is_plain_text(buf: &[u8]) -> bool {
+    charset(buf).is_some()
+}
+
+/// Returns whether a buffer is comma, semicolon or pipe separated values.
+///
+/// See [`csv_dialect`] for the detection rules.
+#[must_use]
+pub fn is_csv(buf: &[u8]) -> bool {
+    csv::dialect(buf).is_some_and(|dialect| dialect.delimiter() != b'\t')
+}
+
+/// Returns whether a buffer is tab separated values.
+///
+/// See [`csv_dialect`] for the detection rules.
+#[must_use]
+pub fn is_tsv(buf: &[u8]) -> bool {
+    csv::dialect(buf).is_some_and(|dialect| dialect.delimiter() == b'\t')
+}
+
+/// Returns the dialect of delimiter-separated text, such as CSV or TSV.
+///
+/// The first records are parsed with `,`, `\t`, `;` and `|` as delimiter, and
+/// `"` as quote character, or `'` if the buffer contains no `"`. A delimiter is
+/// accepted if it splits at least two records into the same number of fields,
+/// at least two. The first record is reported as a header if its fields are
+/// distinct names, and a column below it is numeric or of a fixed width that
+/// differs from the name. Unquoted fields other than numbers must not start
+/// with a space after the delimiter, so prose with a comma on each line is not
+/// CSV.
+///
+/// # Examples
+///
+/// ```rust
+/// let dialect = infer::text::csv_dialect(b"name;size\nfoo;12\n\"bar;baz\";7\n")
+///     .expect("delimited text");
+///
+/// assert_eq!(dialect.delimiter(), b';');
+/// assert_eq!(dialect.quote(), Some(b'"'));
+/// assert!(dialect.has_header());
+/// ```
+#[must_use]
+pub fn csv_dialect(buf: &[u8]) -> Option<CsvDialect> {
+    csv::dialect(buf)
+}
+
+/// Returns whether a character is a control character that does not occur in text.
+///
+/// Follows the binary data bytes of <https://mimesniff.spec.whatwg.org>.
+fn is_binary_control(c: u32) -> bool {
+    matches!(c, 0x00..=0x08 | 0x0B | 0x0E..=0x1A | 0x1C..=0x1F)
+}
+
+/// Guesses UTF-16 or UTF-32 text without a byte order mark from the position of NUL bytes.
+fn wide_charset(buf: &[u8]) -> Option<Charset> {
+    let units = |width: usize| buf.chunks_exact(width).take(256);
+
+    // ASCII characters dominate most text: one non-NUL byte per code unit.
+    let is = |width: usize, pos: usize| {
+        let total = units(width).count();
+        total > 0
+            && units(width)
+                .filter(|u| u[pos] != 0 && u.iter().enumerate().all(|(i, b)| i == pos || *b == 0))
+                .count()
+                * 10
+                >= total * 9
+    };
+
+    if is(4, 0) {
+        Some(Charset::Utf32Le)
+    } else if is(4, 3) {
+        Some(Charset::Utf32Be)
+    } else if is(2, 0) {
+        Some(Charset::Utf16Le)
+    } else if is(2, 1) {
+        Some(Charset::Utf16Be)
+    } else {
+        None
+    }
+}
+
+fn is_wide_text(buf: &[u8], charset: Charset) -> bool {
+    let unit = |u: &[u8]| -> u32 {
+        match charset {
+            Charset::Utf16Le => u32::from(u16::from_le_bytes([u[0], u[1]])),
+            Charset::Utf16Be => u32::from(u16::from_be_bytes([u[0], u[1]])),
+            Charset::Utf32Le => u32::from_le_bytes([u[0], u[1], u[2], u[3]]),
+            _ => u32::from_be_bytes([u[0], u[1], u[2], u[3]]),
+        }
+    };
+
+    if matches!(charset, Charset::Utf32Le | Charset::Utf32Be) {
+        return buf
+            .chunks_exact(4)
+            .map(unit)
+            .all(|c| char::from_u32(c).is_some() && !is_binary_control(c));
+    }
+
+    let mut units = buf.chunks_exact(2).map(unit).peekable();
+    while let Some(c) = units.next() {
+        match c {
+            0xD800..=0xDBFF => match units.next() {
+                Some(0xDC00..=0xDFFF) => {}
+                // a high surrogate cut off at the end of the buffer
+                None => return true,
+                Some(_) => return false,
+            },
+            0xDC00..=0xDFFF => return false,
+            c if is_binary_control(c) => return false,
+            _ => {}
+        }
+    }
+    true
+}
+
+fn is_utf8_text(buf: &[u8]) -> bool {
+    let valid = match core::str::from_utf8(buf) {
+        Ok(text) => text,
+        // an incomplete sequence at the end of the buffer is not an error
+        Err(err) if err.error_len().is_none() => {
+            core::str::from_utf8(&buf[..err.valid_up_to()]).unwrap_or_default()
+        }
+        Err(_) => return false,
+    };
+    !valid.bytes().any(|b| is_binary_control(u32::from(b)))
+}
+
+/// Identifies ASCII, UTF-8 and single-byte encoded text.
+fn narrow_charset(buf: &[u8]) -> Option<Charset> {
+    if buf.iter().any(|&b| is_binary_control(u32::from(b))) {
+        return None;
+    }
+    if buf.is_ascii() {
+        return Some(Charset::Ascii);
+    }
+    if is_utf8_text(buf) {
+        return Some(Charset::Utf8);
+    }
+
+    // Text in Latin-script languages is mostly ASCII.
+    let high = buf.iter().filter(|&&b| b >= 0x80).count();
+    if high * 10 > buf.len() * 3 {
+        return None;
+    }
+
+    // C1 controls are rarely used, their positions hold printable characters in Windows-1252,
+    // except for the few that are undefined.
+    let mut charset = Charset::Iso8859_1;
+    for &b in buf {
+        match b {
+            0x81 | 0x8D | 0x8F | 0x90 | 0x9D => return None,
+            0x80..=0x9F => charset = Charset::Windows1252,
+            _ => {}
+        }
+    }
+    Some(charset)
 }
 
 #[cfg(test)]
@@ -113,5 +631,8 @@ mod tests {
     #[test]
     fn shellscript() {
         assert!(!is_shellscript(b"#!"));
+        assert!(is_shellscript(b"#!/bin/sh\n"));
+        assert!(is_shellscript(b"#! /usr/bin/env bash\r\n"));
+        assert!(!is_shellscript(b"#!/usr/bin/env python3\n"));
     }
 }
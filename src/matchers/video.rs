@@ -1,14 +1,10 @@
+use super::isobmff::{self, Kind};
+use super::riff;
+
 /// Returns whether a buffer is M4V video data.
 #[must_use]
 pub fn is_m4v(buf: &[u8]) -> bool {
-    buf.len() > 10
-        && buf[4] == 0x66
-        && buf[5] == 0x74
-        && buf[6] == 0x79
-        && buf[7] == 0x70
-        && buf[8] == 0x4D
-        && buf[9] == 0x34
-        && buf[10] == 0x56
+    isobmff::kind(buf) == Some(Kind::M4v)
 }
 
 /// Returns whether a buffer is MKV video data.
@@ -36,25 +32,17 @@ pub fn is_webm(buf: &[u8]) -> bool {
 /// Returns whether a buffer is Quicktime MOV video data.
 #[must_use]
 pub fn is_mov(buf: &[u8]) -> bool {
-    buf.len() > 15
-        && (((buf[4] == b'f' && buf[5] == b't' && buf[6] == b'y' && buf[7] == b'p')
-            && (buf[8] == b'q' && buf[9] == b't' && buf[10] == b' ' && buf[11] == b' '))
-            || (buf[4] == 0x6d && buf[5] == 0x6f && buf[6] == 0x6f && buf[7] == 0x76)
-            || (buf[4] == 0x6d && buf[5] == 0x64 && buf[6] == 0x61 && buf[7] == 0x74)
-            || (buf[12] == 0x6d && buf[13] == 0x64 && buf[14] == 0x61 && buf[15] == 0x74))
+    isobmff::kind(buf) == Some(Kind::Mov)
+        || (buf.len() > 15
+            && ((buf[4] == 0x6d && buf[5] == 0x6f && buf[6] == 0x6f && buf[7] == 0x76)
+                || (buf[4] == 0x6d && buf[5] == 0x64 && buf[6] == 0x61 && buf[7] == 0x74)
+                || (buf[12] == 0x6d && buf[13] == 0x64 && buf[14] == 0x61 && buf[15] == 0x74)))
 }
 
 /// Returns whether a buffer is AVI video data.
 #[must_use]
 pub fn is_avi(buf: &[u8]) -> bool {
-    buf.len() > 10
-        && buf[0] == 0x52
-        && buf[1] == 0x49
-        && buf[2] == 0x46
-        && buf[3] == 0x46
-        && buf[8] == 0x41
-        && buf[9] == 0x56
-        && buf[10] == 0x49
+    riff::kind(buf) == Some(riff::Kind::Avi)
 }
 
 /// Returns whether a buffer is WMV video data.
@@ -93,34 +81,35 @@ pub fn is_flv(buf: &[u8]) -> bool {
 /// Returns whether a buffer is MP4 video data.
 #[must_use]
 pub fn is_mp4(buf: &[u8]) -> bool {
-    buf.len() > 11
-        && (buf[4] == b'f' && buf[5] == b't' && buf[6] == b'y' && buf[7] == b'p')
-        && ((buf[8] == b'a' && buf[9] == b'v' && buf[10] == b'c' && buf[11] == b'1')
-            || (buf[8] == b'd' && buf[9] == b'a' && buf[10] == b's' && buf[11] == b'h')
-            || (buf[8] == b'i' && buf[9] == b's' && buf[10] == b'o' && buf[11] == b'2')
-            || (buf[8] == b'i' && buf[9] == b's' && buf[10] == b'o' && buf[11] == b'3')
-            || (buf[8] == b'i' && buf[9] == b's' && buf[10] == b'o' && buf[11] == b'4')
-            || (buf[8] == b'i' && buf[9] == b's' && buf[10] == b'o' && buf[11] == b'5')
-            || (buf[8] == b'i' && buf[9] == b's' && buf[10] == b'o' && buf[11] == b'6')
-            || (buf[8] == b'i' && buf[9] == b's' && buf[10] == b'o' && buf[11] == b'm')
-            || (buf[8] == b'm' && buf[9] == b'm' && buf[10] == b'p' && buf[11] == b'4')
-            || (buf[8] == b'm' && buf[9] == b'p' && buf[10] == b'4' && buf[11] == b'1')
-            || (buf[8] == b'm' && buf[9] == b'p' && buf[10] == b'4' && buf[11] == b'2')
-            || (buf[8] == b'm' && buf[9] == b'p' && buf[10] == b'4' && buf[11] == b'v')
-            || (buf[8] == b'm' && buf[9] == b'p' && buf[10] == b'7' && buf[11] == b'1')
-            || (buf[8] == b'M' && buf[9] == b'S' && buf[10] == b'N' && buf[11] == b'V')
-            || (buf[8] == b'N' && buf[9] == b'D' && buf[10] == b'A' && buf[11] == b'S')
-            || (buf[8] == b'N' && buf[9] == b'D' && buf[10] == b'S' && buf[11] == b'C')
-            || (buf[8] == b'N' && buf[9] == b'S' && buf[10] == b'D' && buf[11] == b'C')
-            || (buf[8] == b'N' && buf[9] == b'D' && buf[10] == b'S' && buf[11] == b'H')
-            || (buf[8] == b'N' && buf[9] == b'D' && buf[10] == b'S' && buf[11] == b'M')
-            || (buf[8] == b'N' && buf[9] == b'D' && buf[10] == b'S' && buf[11] == b'P')
-            || (buf[8] == b'N' && buf[9] == b'D' && buf[10] == b'S' && buf[11] == b'S')
-            || (buf[8] == b'N' && buf[9] == b'D' && buf[10] == b'X' && buf[11] == b'C')
-            || (buf[8] == b'N' && buf[9] == b'D' && buf[10] == b'X' && buf[11] == b'H')
-            || (buf[8] == b'N' && buf[9] == b'D' && buf[10] == b'X' && buf[11] == b'M')
-            || (buf[8] == b'N' && buf[9] == b'D' && buf[10] == b'X' && buf[11] == b'P')
-            || (buf[8] == b'N' && buf[9] == b'D' && buf[10] == b'X' && buf[11] == b'S')
-            || (buf[8] == b'F' && buf[9] == b'4' && buf[10] == b'V' && buf[11] == b' ')
-            || (buf[8] == b'F' && buf[9] == b'4' && buf[10] == b'P' && buf[11] == b' '))
+    isobmff::kind(buf) == Some(Kind::Mp4)
+}
+
+/// Returns whether a buffer is 3GPP video data.
+#[must_use]
+pub fn is_3gp(buf: &[u8]) -> bool {
+    isobmff::kind(buf) == Some(Kind::ThreeGp)
+}
+
+/// Returns whether a buffer is 3GPP2 video data.
+#[must_use]
+pub fn is_3g2(buf: &[u8]) -> bool {
+    isobmff::kind(buf) == Some(Kind::ThreeG2)
+}
+
+/// Returns whether a buffer is Adobe Flash F4V video data.
+#[must_use]
+pub fn is_f4v(buf: &[u8]) -> bool {
+    isobmff::kind(buf) == Some(Kind::F4v)
+}
+
+/// Returns whether a buffer is an ISO-BMFF media segment (CMAF/DASH).
+#[must_use]
+pub fn is_m4s(buf: &[u8]) -> bool {
+    isobmff::kind(buf) == Some(Kind::Segment)
+}
+
+/// Returns whether a buffer is Motion JPEG 2000 video data.
+#[must_use]
+pub fn is_mj2(buf: &[u8]) -> bool {
+    isobmff::kind(buf) == Some(Kind::Mj2)
 }
@@ -0,0 +1,322 @@
+//! XML prolog and root element scanner.
+//!
+//! XML dialects such as SVG, Atom or Maven POMs are identified by the name and
+//! namespace of their root element rather than by a magic number. The scanner
+//! skips the XML declaration, processing instructions, comments and the document
+//! type declaration, and returns the start tag of the root element without
+//! allocating. Documents without an XML declaration are scanned as well.
+
+use super::text::trim_start_byte_order_marks;
+
+/// The root element of an XML document.
+#[derive(Debug, Copy, Clone)]
+pub struct Root<'a> {
+    name: &'a [u8],
+    attributes: &'a [u8],
+    doctype: Option<&'a [u8]>,
+    declaration: bool,
+}
+
+impl<'a> Root<'a> {
+    /// Returns the qualified name of the root element, e.g. `office:document`.
+    #[must_use]
+    pub const fn name(&self) -> &'a [u8] {
+        self.name
+    }
+
+    /// Returns the name of the root element without its namespace prefix.
+    #[must_use]
+    pub fn local_name(&self) -> &'a [u8] {
+        match self.name.iter().position(|&b| b == b':') {
+            Some(i) => &self.name[i + 1..],
+            None => self.name,
+        }
+    }
+
+    /// Returns the namespace prefix of the root element, if any.
+    #[must_use]
+    pub fn prefix(&self) -> Option<&'a [u8]> {
+        let i = self.name.iter().position(|&b| b == b':')?;
+        Some(&self.name[..i])
+    }
+
+    /// Returns the namespace of the root element, as declared on the root element itself.
+    #[must_use]
+    pub fn namespace(&self) -> Option<&'a [u8]> {
+        match self.prefix() {
+            Some(prefix) => self.attributes().find_map(|(name, value)| {
+                (name.strip_prefix(b"xmlns:") == Some(prefix)).then_some(value)
+            }),
+            None => self.attribute(b"xmlns"),
+        }
+    }
+
+    /// Returns whether the root element declares the given namespace, with any prefix.
+    #[must_use]
+    pub fn declares(&self, namespace: &[u8]) -> bool {
+        self.attributes().any(|(name, value)| {
+            (name == b"xmlns" || name.starts_with(b"xmlns:")) && value == namespace
+        })
+    }
+
+    /// Returns the value of an attribute of the root element.
+    ///
+    /// Entity and character references in the value are not expanded.
+    #[must_use]
+    pub fn attribute(&self, name: &[u8]) -> Option<&'a [u8]> {
+        self.attributes()
+            .find_map(|(n, value)| (n == name).then_some(value))
+    }
+
+    /// Returns an iterator over the attribute names and values of the root element.
+    #[must_use]
+    pub fn attributes(&self) -> Attributes<'a> {
+        Attributes {
+            buf: self.attributes,
+        }
+    }
+
+    /// Returns the content of the document type declaration, e.g.
+    /// `plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "..."`.
+    #[must_use]
+    pub const fn doctype(&self) -> Option<&'a [u8]> {
+        self.doctype
+    }
+
+    /// Returns whether the document starts with an XML declaration.
+    #[must_use]
+    pub const fn has_declaration(&self) -> bool {
+        self.declaration
+    }
+}
+
+/// Iterator over the attributes of a start tag.
+#[derive(Debug, Clone)]
+pub struct Attributes<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> Iterator for Attributes<'a> {
+    type Item = (&'a [u8], &'a [u8]);
+
+    fn next(&mut self) -> Option<(&'a [u8], &'a [u8])> {
+        let buf = trim_start(self.buf);
+        let name_len = buf.iter().position(|&b| !is_name_byte(b))?;
+        let (name, rest) = buf.split_at(name_len);
+        let rest = trim_start(rest).strip_prefix(b"=")?;
+        let rest = trim_start(rest);
+
+        let quote = *rest.first().filter(|&&b| b == b'"' || b == b'\'')?;
+        let rest = &rest[1..];
+        let Some(end) = rest.iter().position(|&b| b == quote) else {
+            // the value is cut off at the end of the buffer
+            self.buf = &[];
+            return None;
+        };
+
+        self.buf = &rest[end + 1..];
+        if name.is_empty() {
+            return None;
+        }
+        Some((name, &rest[..end]))
+    }
+}
+
+/// Returns the root element of an XML document.
+///
+/// Returns `None` if the buffer does not start with markup or if the root
+/// element start tag is not found.
+///
+/// # Examples
+///
+/// ```rust
+/// let buf = br#"<?xml version="1.0"?>
+/// <!-- a comment -->
+/// <feed xmlns="http://www.w3.org/2005/Atom"><title>Example</title></feed>"#;
+/// let root = infer::xml::root(buf).expect("root element found");
+///
+/// assert_eq!(root.name(), b"feed");
+/// assert_eq!(root.namespace(), Some(&b"http://www.w3.org/2005/Atom"[..]));
+/// ```
+#[must_use]
+pub fn root(buf: &[u8]) -> Option<Root<'_>> {
+    let mut buf = trim_start(trim_start_byte_order_marks(buf));
+    let declaration = buf.starts_with(b"<?xml") && buf.get(5).is_some_and(|&b| is_space(b));
+    let mut doctype = None;
+
+    loop {
+        if let Some(rest) = buf.strip_prefix(b"<?") {
+            buf = skip_past(rest, b"?>")?;
+        } else if let Some(rest) = buf.strip_prefix(b"<!--") {
+            buf = skip_past(rest, b"-->")?;
+        } else if let Some(rest) = strip_prefix_ignore_case(buf, b"<!DOCTYPE") {
+            let end = doctype_end(rest)?;
+            doctype = Some(trim_start(&rest[..end]));
+            buf = &rest[end + 1..];
+        } else if let Some(rest) = buf.strip_prefix(b"<") {
+            let name_len = rest.iter().position(|&b| !is_name_byte(b))?;
+            if name_len == 0 || !is_name_start_byte(rest[0]) {
+                return None;
+            }
+            let (name, rest) = rest.split_at(name_len);
+            if !matches!(rest[0], b'>' | b'/') && !is_space(rest[0]) {
+                return None;
+            }
+            let end = tag_end(rest);
+            return Some(Root {
+                name,
+                attributes: &rest[..end],
+                doctype,
+                declaration,
+            });
+        } else {
+            return None;
+        }
+        buf = trim_start(buf);
+    }
+}
+
+/// Returns the remainder of the buffer after the first occurrence of `needle`.
+fn skip_past<'a>(buf: &'a [u8], needle: &[u8]) -> Option<&'a [u8]> {
+    let i = buf.windows(needle.len()).position(|w| w == needle)?;
+    Some(&buf[i + needle.len()..])
+}
+
+/// Returns the position of the `>` closing a document type declaration,
+/// skipping an internal subset and quoted literals.
+fn doctype_end(buf: &[u8]) -> Option<usize> {
+    let mut quote = None;
+    let mut subset = false;
+    for (i, &b) in buf.iter().enumerate() {
+        match (quote, b) {
+            (Some(q), _) if b == q => quote = None,
+            (Some(_), _) => {}
+            (None, b'"' | b'\'') => quote = Some(b),
+            (None, b'[') => subset = true,
+            (None, b']') => subset = false,
+            (None, b'>') if !subset => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Returns the position of the `>` or `/>` closing a start tag, or the
+/// length of the buffer if the tag is cut off.
+fn tag_end(buf: &[u8]) -> usize {
+    let mut quote = None;
+    for (i, &b) in buf.iter().enumerate() {
+        match (quote, b) {
+            (Some(q), _) if b == q => quote = None,
+            (Some(_), _) => {}
+            (None, b'"' | b'\'') => quote = Some(b),
+            (None, b'>') => return i,
+            (None, b'/') if buf.get(i + 1) == Some(&b'>') => return i,
+            _ => {}
+        }
+    }
+    buf.len()
+}
+
+fn strip_prefix_ignore_case<'a>(buf: &'a [u8], prefix: &[u8]) -> Option<&'a [u8]> {
+    (buf.len() >= prefix.len() && buf[..prefix.len()].eq_ignore_ascii_case(prefix))
+        .then(|| &buf[prefix.len()..])
+}
+
+fn trim_start(buf: &[u8]) -> &[u8] {
+    let start = buf.iter().position(|&b| !is_space(b)).unwrap_or(buf.len());
+    &buf[start..]
+}
+
+fn trim_end(buf: &[u8]) -> &[u8] {
+    let end = buf.iter().rposition(|&b| !is_space(b)).map_or(0, |i| i + 1);
+    &buf[..end]
+}
+
+fn is_space(b: u8) -> bool {
+    matches!(b, b' ' | b'\t' | b'\r' | b'\n')
+}
+
+/// Returns whether a byte may start an element name. Non-ASCII bytes
+/// are accepted as part of UTF-8 encoded names.
+fn is_name_start_byte(b: u8) -> bool {
+    b.is_ascii_alphabetic() || b == b'_' || b == b':' || b >= 0x80
+}
+
+fn is_name_byte(b: u8) -> bool {
+    is_name_start_byte(b) || b.is_ascii_digit() || b == b'-' || b == b'.'
+}
+
+/// XML vocabulary identified by the root element.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum Dialect {
+    Svg,
+    Rss,
+    Atom,
+    Kml,
+    Gpx,
+    Xhtml,
+    Xslt,
+    Plist,
+    MusicXml,
+    Xliff,
+    DocBook,
+    FlatOdf,
+    Android,
+    Pom,
+}
+
+const SVG: &[u8] = b"http://www.w3.org/2000/svg";
+const RSS1: &[u8] = b"http://purl.org/rss/1.0/";
+const ATOM: &[u8] = b"http://www.w3.org/2005/Atom";
+const XHTML: &[u8] = b"http://www.w3.org/1999/xhtml";
+const XSLT: &[u8] = b"http://www.w3.org/1999/XSL/Transform";
+const DOCBOOK: &[u8] = b"http://docbook.org/ns/docbook";
+const ODF_OFFICE: &[u8] = b"urn:oasis:names:tc:opendocument:xmlns:office:1.0";
+const ANDROID: &[u8] = b"http://schemas.android.com/apk/res/android";
+const POM: &[u8] = b"http://maven.apache.org/POM/4.0.0";
+
+/// Returns whether a document type declaration is the one of HTML5, `<!DOCTYPE html>`,
+/// or its legacy compatible form.
+fn is_html5_doctype(doctype: Option<&[u8]>) -> bool {
+    doctype.is_some_and(|doctype| {
+        let doctype = trim_end(doctype);
+        doctype.eq_ignore_ascii_case(b"html")
+            || doctype.eq_ignore_ascii_case(b"html SYSTEM \"about:legacy-compat\"")
+    })
+}
+
+/// Returns the XML dialect of a buffer identified by its root element.
+pub(crate) fn dialect(buf: &[u8]) -> Option<Dialect> {
+    let root = root(buf)?;
+    let namespace = root.namespace();
+
+    let dialect = match (root.local_name(), namespace) {
+        (_, Some(SVG)) | (b"svg", None) => Dialect::Svg,
+        (b"rss", _) => Dialect::Rss,
+        (b"RDF", _) if root.declares(RSS1) => Dialect::Rss,
+        (b"feed", Some(ATOM)) => Dialect::Atom,
+        (b"kml", _) => Dialect::Kml,
+        (b"gpx", _) => Dialect::Gpx,
+        // HTML5 pages often declare the namespace too, and are still HTML
+        (b"html", Some(XHTML)) if root.has_declaration() || !is_html5_doctype(root.doctype()) => {
+            Dialect::Xhtml
+        }
+        (b"stylesheet" | b"transform", Some(XSLT)) => Dialect::Xslt,
+        (b"plist", _) => Dialect::Plist,
+        (b"score-partwise" | b"score-timewise", _) => Dialect::MusicXml,
+        (b"xliff", _) => Dialect::Xliff,
+        (_, Some(DOCBOOK)) => Dialect::DocBook,
+        (b"document", Some(ODF_OFFICE)) => Dialect::FlatOdf,
+        (b"project", Some(POM)) => Dialect::Pom,
+        _ if root.declares(ANDROID) => Dialect::Android,
+        _ if root
+            .doctype()
+            .is_some_and(|doctype| super::match_bytes(doctype, b"DocBook")) =>
+        {
+            Dialect::DocBook
+        }
+        _ => return None,
+    };
+    Some(dialect)
+}
@@ -0,0 +1,522 @@
+//! ZIP container inspection based on the central directory.
+//!
+//! Many formats are ZIP archives with a well-known set of entries. Looking at the
+//! local file headers at the start of the archive only works when the entries
+//! are stored in the expected order, so this module locates the end of central
+//! directory record near the end of the buffer and classifies the archive
+//! by its full entry list.
+//!
+//! The buffer can either be the whole archive or a prefix of it followed by the
+//! central directory and the end of central directory record, as read by
+//! [`get_from_path`](crate::get_from_path).
+
+use core::convert::TryInto;
+
+use super::compare_bytes;
+
+const LOCAL_FILE_HEADER: &[u8] = b"PK\x03\x04";
+const CENTRAL_FILE_HEADER: &[u8] = b"PK\x01\x02";
+const END_OF_CENTRAL_DIRECTORY: &[u8] = b"PK\x05\x06";
+const ZIP64_END_OF_CENTRAL_DIRECTORY: &[u8] = b"PK\x06\x06";
+const ZIP64_END_OF_CENTRAL_DIRECTORY_LOCATOR: &[u8] = b"PK\x06\x07";
+
+const END_OF_CENTRAL_DIRECTORY_LEN: usize = 22;
+const ZIP64_END_OF_CENTRAL_DIRECTORY_LEN: usize = 56;
+const ZIP64_END_OF_CENTRAL_DIRECTORY_LOCATOR_LEN: usize = 20;
+const CENTRAL_FILE_HEADER_LEN: usize = 46;
+const LOCAL_FILE_HEADER_LEN: usize = 30;
+const MAX_COMMENT_LEN: usize = 0xFFFF;
+
+/// Maximum number of central directory bytes read by `get_from_path`.
+#[cfg(feature = "std")]
+const MAX_CENTRAL_DIRECTORY_LEN: u64 = 4 * 1024 * 1024;
+
+/// Kind of a ZIP based container.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Container {
+    /// Microsoft Word Open XML document.
+    DOCX,
+    /// Microsoft Excel Open XML spreadsheet.
+    XLSX,
+    /// Microsoft `PowerPoint` Open XML presentation.
+    PPTX,
+    /// Other Office Open XML package.
+    OOXML,
+    /// `OpenDocument` text.
+    ODT,
+    /// `OpenDocument` spreadsheet.
+    ODS,
+    /// `OpenDocument` presentation.
+    ODP,
+    /// Other `OpenDocument` package.
+    ODF,
+    /// EPUB electronic publication.
+    EPUB,
+    /// `OpenRaster` image.
+    ORA,
+    /// Java archive.
+    JAR,
+    /// Android application package.
+    APK,
+    /// Android App Bundle.
+    AAB,
+    /// iOS application archive.
+    IPA,
+    /// Mozilla extension.
+    XPI,
+    /// Visual Studio extension.
+    VSIX,
+}
+
+/// A ZIP archive located by its end of central directory record.
+#[derive(Debug, Copy, Clone)]
+pub struct Archive<'a> {
+    buf: &'a [u8],
+    central_directory: &'a [u8],
+    entries: usize,
+    // Difference between the offsets recorded in the archive and the
+    // position in `buf`, non-zero for prefixed archives and tail buffers.
+    shift: i128,
+}
+
+impl<'a> Archive<'a> {
+    /// Locates the central directory of the archive in `buf`.
+    ///
+    /// Returns `None` if no end of central directory record is found
+    /// in the last 64 KiB of the buffer.
+    #[must_use]
+    pub fn new(buf: &'a [u8]) -> Option<Archive<'a>> {
+        let end = find_end_of_central_directory(buf)?;
+        let (cd_offset, cd_size, entries, cd_end) = read_end_of_central_directory(buf, end)?;
+
+        let cd_start = cd_end.checked_sub(usize::try_from(cd_size).ok()?)?;
+        let central_directory = &buf[cd_start..cd_end];
+        if entries > 0 && !compare_bytes(central_directory, CENTRAL_FILE_HEADER, 0) {
+            return None;
+        }
+
+        Some(Archive {
+            buf,
+            central_directory,
+            entries: usize::try_from(entries).ok()?,
+            shift: i128::from(cd_offset) - i128::try_from(cd_start).ok()?,
+        })
+    }
+
+    /// Returns an iterator over the central directory entries.
+    #[must_use]
+    pub fn entries(&self) -> Entries<'a> {
+        Entries {
+            buf: self.central_directory,
+            remaining: self.entries,
+        }
+    }
+
+    /// Returns the entry with the given name.
+    #[must_use]
+    pub fn find(&self, name: &[u8]) -> Option<Entry<'a>> {
+        self.entries().find(|e| e.name() == name)
+    }
+
+    /// Returns whether any entry name starts with `prefix`.
+    #[must_use]
+    pub fn contains_prefix(&self, prefix: &[u8]) -> bool {
+        self.entries().any(|e| e.name().starts_with(prefix))
+    }
+
+    /// Returns the data of a stored (uncompressed) entry, as far as it is contained in the buffer.
+    #[must_use]
+    pub fn stored_data(&self, entry: &Entry<'_>) -> Option<&'a [u8]> {
+        if entry.method != 0 {
+            return None;
+        }
+
+        // The local header is looked up at the recorded offset first, which is where it
+        // lives when `buf` starts with the archive, then at the shifted offset.
+        let offset = u64::from(entry.local_header_offset);
+        let shifted = usize::try_from(i128::from(offset) - self.shift).ok();
+        let candidates = [usize::try_from(offset).ok(), shifted];
+
+        candidates.iter().flatten().find_map(|&pos| {
+            let header = self.buf.get(pos..)?;
+            if !compare_bytes(header, LOCAL_FILE_HEADER, 0) || header.len() < LOCAL_FILE_HEADER_LEN
+            {
+                return None;
+            }
+            let name_len = usize::from(u16::from_le_bytes(header[26..28].try_into().unwrap()));
+            let extra_len = usize::from(u16::from_le_bytes(header[28..30].try_into().unwrap()));
+            if !compare_bytes(header, entry.name, LOCAL_FILE_HEADER_LEN) {
+                return None;
+            }
+            let data = header.get(LOCAL_FILE_HEADER_LEN + name_len + extra_len..)?;
+            let len = usize::try_from(entry.compressed_size).unwrap_or(usize::MAX);
+            Some(&data[..len.min(data.len())])
+        })
+    }
+
+    /// Classifies the archive by its entries.
+    #[must_use]
+    pub fn container(&self) -> Option<Container> {
+        if let Some(mimetype) = self.find(b"mimetype") {
+            if let Some(kind) = self
+                .stored_data(&mimetype)
+                .and_then(container_from_mimetype)
+            {
+                return Some(kind);
+            }
+        }
+
+        classify(self.entries().map(|e| e.name()))
+    }
+}
+
+/// An entry of the central directory.
+#[derive(Debug, Copy, Clone)]
+pub struct Entry<'a> {
+    name: &'a [u8],
+    method: u16,
+    compressed_size: u32,
+    uncompressed_size: u32,
+    local_header_offset: u32,
+}
+
+impl<'a> Entry<'a> {
+    /// Returns the raw entry name.
+    #[must_use]
+    pub const fn name(&self) -> &'a [u8] {
+        self.name
+    }
+
+    /// Returns the compression method.
+    #[must_use]
+    pub const fn method(&self) -> u16 {
+        self.method
+    }
+
+    /// Returns the compressed size, `0xFFFFFFFF` for ZIP64 entries.
+    #[must_use]
+    pub const fn compressed_size(&self) -> u32 {
+        self.compressed_size
+    }
+
+    /// Returns the uncompressed size, `0xFFFFFFFF` for ZIP64 entries.
+    #[must_use]
+    pub const fn uncompressed_size(&self) -> u32 {
+        self.uncompressed_size
+    }
+}
+
+/// Iterator over the entries of the central directory.
+#[derive(Debug, Clone)]
+pub struct Entries<'a> {
+    buf: &'a [u8],
+    remaining: usize,
+}
+
+impl<'a> Iterator for Entries<'a> {
+    type Item = Entry<'a>;
+
+    fn next(&mut self) -> Option<Entry<'a>> {
+        if self.remaining == 0
+            || self.buf.len() < CENTRAL_FILE_HEADER_LEN
+            || !compare_bytes(self.buf, CENTRAL_FILE_HEADER, 0)
+        {
+            self.remaining = 0;
+            return None;
+        }
+
+        let header = self.buf;
+        let u16_at =
+            |i: usize| usize::from(u16::from_le_bytes(header[i..i + 2].try_into().unwrap()));
+        let u32_at = |i: usize| u32::from_le_bytes(header[i..i + 4].try_into().unwrap());
+
+        let name_len = u16_at(28);
+        let len = CENTRAL_FILE_HEADER_LEN + name_len + u16_at(30) + u16_at(32);
+        if header.len() < len {
+            self.remaining = 0;
+            return None;
+        }
+
+        self.buf = &header[len..];
+        self.remaining -= 1;
+
+        Some(Entry {
+            name: &header[CENTRAL_FILE_HEADER_LEN..CENTRAL_FILE_HEADER_LEN + name_len],
+            method: u16::from_le_bytes(header[10..12].try_into().unwrap()),
+            compressed_size: u32_at(20),
+            uncompressed_size: u32_at(24),
+            local_header_offset: u32_at(42),
+        })
+    }
+}
+
+/// Classifies a ZIP archive by its central directory.
+///
+/// Returns `None` if the buffer does not start with a ZIP local file header,
+/// the central directory is not contained in the buffer, or the
+/// container kind is unknown.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::fs;
+/// use infer::zip::Container;
+///
+/// let buf = fs::read("testdata/sample.docx").unwrap();
+/// assert_eq!(infer::zip::container(&buf), Some(Container::DOCX));
+/// ```
+#[must_use]
+pub fn container(buf: &[u8]) -> Option<Container> {
+    if !compare_bytes(buf, LOCAL_FILE_HEADER, 0) {
+        return None;
+    }
+    Archive::new(buf)?.container()
+}
+
+/// Classifies a ZIP archive by its central directory, if it is contained in the buffer.
+///
+/// Matchers of ZIP based formats use the central directory when it is available
+/// and fall back to looking at the first local file headers otherwise.
+pub(crate) fn inspect(buf: &[u8]) -> Option<Option<Container>> {
+    if !compare_bytes(buf, LOCAL_FILE_HEADER, 0) {
+        return None;
+    }
+    Archive::new(buf).map(|archive| archive.container())
+}
+
+/// Classifies a ZIP archive by its central directory if it is contained in the buffer,
+/// or by the local file headers at the start of the buffer otherwise.
+pub(crate) fn package(buf: &[u8]) -> Option<Container> {
+    match inspect(buf) {
+        Some(container) => container,
+        None if compare_bytes(buf, LOCAL_FILE_HEADER, 0) => classify(LocalNames { buf }),
+        None => None,
+    }
+}
+
+/// Returns whether a buffer starts with a ZIP local file header.
+#[cfg(feature = "std")]
+pub(crate) fn is_local_file_header(buf: &[u8]) -> bool {
+    compare_bytes(buf, LOCAL_FILE_HEADER, 0)
+}
+
+fn container_from_mimetype(mimetype: &[u8]) -> Option<Container> {
+    const ODF: &[u8] = b"application/vnd.oasis.opendocument.";
+
+    match mimetype {
+        b"application/epub+zip" => Some(Container::EPUB),
+        b"image/openraster" => Some(Container::ORA),
+        _ => mimetype.strip_prefix(ODF).map(|kind| {
+            if kind.starts_with(b"text") {
+                Container::ODT
+            } else if kind.starts_with(b"spreadsheet") {
+                Container::ODS
+            } else if kind.starts_with(b"presentation") {
+                Container::ODP
+            } else {
+                Container::ODF
+            }
+        }),
+    }
+}
+
+fn classify<'a, I>(names: I) -> Option<Container>
+where
+    I: Iterator<Item = &'a [u8]> + Clone,
+{
+    let has = |name: &[u8]| names.clone().any(|n| n == name);
+    let has_prefix = |prefix: &[u8]| names.clone().any(|n| n.starts_with(prefix));
+
+    // Visual Studio extensions are Open Packaging Conventions packages like OOXML documents.
+    if has(b"extension.vsixmanifest") {
+        return Some(Container::VSIX);
+    }
+
+    if has(b"[Content_Types].xml") {
+        return Some(if has_prefix(b"word/") {
+            Container::DOCX
+        } else if has_prefix(b"xl/") {
+            Container::XLSX
+        } else if has_prefix(b"ppt/") {
+            Container::PPTX
+        } else {
+            Container::OOXML
+        });
+    }
+
+    if has(b"META-INF/container.xml") {
+        return Some(Container::EPUB);
+    }
+
+    if has(b"content.xml") && has(b"META-INF/manifest.xml") {
+        return Some(Container::ODF);
+    }
+
+    if has(b"stack.xml") && has(b"mergedimage.png") {
+        return Some(Container::ORA);
+    }
+
+    // APKs and bundles are also signed JARs, so they are checked first.
+    if has(b"AndroidManifest.xml") {
+        return Some(Container::APK);
+    }
+
+    if has(b"BundleConfig.pb") {
+        return Some(Container::AAB);
+    }
+
+    if names.clone().any(is_ios_app_entry) {
+        return Some(Container::IPA);
+    }
+
+    // Many archives have a `manifest.json`, WebExtensions are told apart by the
+    // signature of addons.mozilla.org.
+    let is_signed_by_mozilla = has(b"META-INF/mozilla.rsa") || has(b"META-INF/cose.sig");
+    if has(b"install.rdf") || (has(b"manifest.json") && is_signed_by_mozilla) {
+        return Some(Container::XPI);
+    }
+
+    if has(b"META-INF/MANIFEST.MF") {
+        return Some(Container::JAR);
+    }
+
+    None
+}
+
+/// Returns whether an entry name is inside an application bundle, i.e. `Payload/<name>.app/`.
+fn is_ios_app_entry(name: &[u8]) -> bool {
+    name.strip_prefix(b"Payload/".as_slice())
+        .and_then(|rest| rest.iter().position(|&b| b == b'/').map(|i| &rest[..i]))
+        .is_some_and(|bundle| bundle.len() > 4 && bundle.ends_with(b".app"))
+}
+
+/// Iterator over the names of the consecutive local file headers at the start of a buffer.
+#[derive(Clone)]
+struct LocalNames<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> Iterator for LocalNames<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<&'a [u8]> {
+        let header = self.buf;
+        if header.len() < LOCAL_FILE_HEADER_LEN || !compare_bytes(header, LOCAL_FILE_HEADER, 0) {
+            return None;
+        }
+
+        let flags = u16::from_le_bytes(header[6..8].try_into().unwrap());
+        let compressed_size = u32::from_le_bytes(header[18..22].try_into().unwrap());
+        let name_len = usize::from(u16::from_le_bytes(header[26..28].try_into().unwrap()));
+        let extra_len = usize::from(u16::from_le_bytes(header[28..30].try_into().unwrap()));
+        let name = header.get(LOCAL_FILE_HEADER_LEN..LOCAL_FILE_HEADER_LEN + name_len)?;
+
+        // The size of entries followed by a data descriptor is unknown,
+        // so the next header can not be located.
+        self.buf = if flags & 0x08 != 0 && compressed_size == 0 {
+            &[]
+        } else {
+            usize::try_from(compressed_size)
+                .ok()
+                .and_then(|size| header.get(LOCAL_FILE_HEADER_LEN + name_len + extra_len + size..))
+                .unwrap_or(&[])
+        };
+
+        Some(name)
+    }
+}
+
+fn find_end_of_central_directory(buf: &[u8]) -> Option<usize> {
+    let last = buf.len().checked_sub(END_OF_CENTRAL_DIRECTORY_LEN)?;
+    let first = last.saturating_sub(MAX_COMMENT_LEN);
+
+    (first..=last).rev().find(|&pos| {
+        compare_bytes(buf, END_OF_CENTRAL_DIRECTORY, pos) && {
+            let comment_len = u16::from_le_bytes(buf[pos + 20..pos + 22].try_into().unwrap());
+            pos + END_OF_CENTRAL_DIRECTORY_LEN + usize::from(comment_len) <= buf.len()
+        }
+    })
+}
+
+/// Returns the central directory offset, size, entry count and end position in `buf`.
+fn read_end_of_central_directory(buf: &[u8], pos: usize) -> Option<(u64, u64, u64, usize)> {
+    let eocd = &buf[pos..pos + END_OF_CENTRAL_DIRECTORY_LEN];
+    let entries = u16::from_le_bytes(eocd[10..12].try_into().unwrap());
+    let cd_size = u32::from_le_bytes(eocd[12..16].try_into().unwrap());
+    let cd_offset = u32::from_le_bytes(eocd[16..20].try_into().unwrap());
+
+    // ZIP64 archives store the actual values in the ZIP64 end of central directory
+    // record, which precedes its locator and the end of central directory record.
+    let zip64 = pos
+        .checked_sub(
+            ZIP64_END_OF_CENTRAL_DIRECTORY_LOCATOR_LEN + ZIP64_END_OF_CENTRAL_DIRECTORY_LEN,
+        )
+        .filter(|&record| {
+            compare_bytes(buf, ZIP64_END_OF_CENTRAL_DIRECTORY, record)
+                && compare_bytes(
+                    buf,
+                    ZIP64_END_OF_CENTRAL_DIRECTORY_LOCATOR,
+                    record + ZIP64_END_OF_CENTRAL_DIRECTORY_LEN,
+                )
+        });
+
+    let Some(record) = zip64 else {
+        if entries == 0xFFFF || cd_size == 0xFFFF_FFFF || cd_offset == 0xFFFF_FFFF {
+            return None;
+        }
+        return Some((
+            u64::from(cd_offset),
+            u64::from(cd_size),
+            u64::from(entries),
+            pos,
+        ));
+    };
+
+    let u64_at = |i: usize| u64::from_le_bytes(buf[record + i..record + i + 8].try_into().unwrap());
+
+    Some((u64_at(48), u64_at(40), u64_at(32), record))
+}
+
+/// Reads the central directory and the end of central directory record of a ZIP archive.
+///
+/// Returns `None` if the end of central directory record is not found or the central
+/// directory is unreasonably large.
+#[cfg(feature = "std")]
+pub(crate) fn read_central_directory<R>(r: &mut R, len: u64) -> std::io::Result<Option<Vec<u8>>>
+where
+    R: std::io::Read + std::io::Seek,
+{
+    use std::io::SeekFrom;
+
+    let tail_len = len.min((END_OF_CENTRAL_DIRECTORY_LEN + MAX_COMMENT_LEN) as u64);
+    let tail_start = len - tail_len;
+    let mut tail = vec![0; usize::try_from(tail_len).unwrap_or(0)];
+    r.seek(SeekFrom::Start(tail_start))?;
+    r.read_exact(&mut tail)?;
+
+    let Some(end) = find_end_of_central_directory(&tail) else {
+        return Ok(None);
+    };
+    let Some((_, cd_size, _, cd_end)) = read_end_of_central_directory(&tail, end) else {
+        return Ok(None);
+    };
+    if cd_size > MAX_CENTRAL_DIRECTORY_LEN {
+        return Ok(None);
+    }
+
+    let cd_end = tail_start + cd_end as u64;
+    let Some(cd_start) = cd_end.checked_sub(cd_size) else {
+        return Ok(None);
+    };
+    if cd_start >= tail_start {
+        let start = usize::try_from(cd_start - tail_start).unwrap_or(0);
+        return Ok(Some(tail.split_off(start)));
+    }
+
+    let mut buf = vec![0; usize::try_from(tail_start - cd_start).unwrap_or(0)];
+    r.seek(SeekFrom::Start(cd_start))?;
+    r.read_exact(&mut buf)?;
+    buf.extend_from_slice(&tail);
+    Ok(Some(buf))
+}
@@ -0,0 +1,15 @@
+//! Memory mapping of files, the only `unsafe` code of the crate.
+
+use std::fs::File;
+use std::io;
+
+/// Maps the whole file read-only.
+#[allow(unsafe_code)]
+pub(crate) fn map(file: &File) -> io::Result<memmap2::Mmap> {
+    // SAFETY: the mapped bytes are only valid as long as the file is not changed by another
+    // process. If it is truncated, reading the pages past its new end raises SIGBUS, and if
+    // it is modified, the `&[u8]` of the map is not immutable as Rust assumes. Neither can be
+    // prevented here, which is why this is behind the `mmap` feature, the only one relaxing
+    // `forbid(unsafe_code)`.
+    unsafe { memmap2::Mmap::map(file) }
+}
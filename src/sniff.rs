@@ -0,0 +1,528 @@
+//! MIME type sniffing as done by web browsers.
+//!
+//! Implements the [WHATWG MIME Sniffing Standard](https://mimesniff.spec.whatwg.org/):
+//! the computed MIME type of a resource depends on the `Content-Type` supplied
+//! with it, the `X-Content-Type-Options: nosniff` flag and the context it is
+//! loaded in. Only the first 1445 bytes of the resource are inspected. Unlike
+//! [`crate::get`], the result is one of the few MIME types browsers act upon.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use infer::sniff::{sniff, sniff_in_context, Context};
+//!
+//! let png = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 0x00];
+//! assert_eq!(sniff(&png, None, false), "image/png");
+//! assert_eq!(sniff(b"<html><body>", Some("application/unknown"), false), "text/html");
+//! assert_eq!(sniff(b"<html><body>", Some("text/plain"), false), "text/plain");
+//! assert_eq!(sniff(&png, Some("text/plain"), false), "application/octet-stream");
+//! assert_eq!(sniff_in_context(Context::Image, &png, Some("image/gif")), Some("image/png"));
+//! ```
+
+/// Maximum number of bytes of a resource that are inspected.
+pub const RESOURCE_HEADER_LEN: usize = 1445;
+
+/// Context a resource is loaded in, other than a browsing context.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Context {
+    /// An `<img>` element or a CSS image.
+    Image,
+    /// An `<audio>` or `<video>` element.
+    AudioVideo,
+    /// A web font loaded by `@font-face`.
+    Font,
+    /// A style sheet, e.g. `<link rel=stylesheet>`.
+    Style,
+    /// A script, e.g. `<script src>`.
+    Script,
+}
+
+/// Returns the computed MIME type of a resource loaded in a browsing context,
+/// e.g. a document or an `<iframe>`.
+///
+/// `supplied` is the `Content-Type` of the resource, if any, and `no_sniff` is
+/// set if it was served with `X-Content-Type-Options: nosniff`. If `supplied`
+/// is exactly one of the `text/plain` values sent by default by old Apache
+/// versions, the resource is only checked for binary data, unless it starts
+/// with a byte order mark.
+#[must_use]
+pub fn sniff<'a>(buf: &[u8], supplied: Option<&'a str>, no_sniff: bool) -> &'a str {
+    let header = resource_header(buf);
+    let Some(supplied) = supplied else {
+        return identify_unknown(header, !no_sniff);
+    };
+
+    let essence = essence(supplied);
+    if ["unknown/unknown", "application/unknown", "*/*"]
+        .iter()
+        .any(|unknown| essence.eq_ignore_ascii_case(unknown))
+    {
+        return identify_unknown(header, !no_sniff);
+    }
+    if no_sniff {
+        return supplied;
+    }
+    if is_apache_bug(supplied) {
+        let has_bom = match_table(header, BOM_PATTERNS).is_some();
+        return if !has_bom && is_binary(header) {
+            "application/octet-stream"
+        } else {
+            "text/plain"
+        };
+    }
+    if is_xml(essence) {
+        return supplied;
+    }
+    if essence.eq_ignore_ascii_case("text/html") {
+        return feed_type(header).unwrap_or(supplied);
+    }
+
+    let top_level = top_level_type(essence);
+    let matched = if top_level.eq_ignore_ascii_case("image") {
+        image_type(header)
+    } else if top_level.eq_ignore_ascii_case("audio")
+        || top_level.eq_ignore_ascii_case("video")
+        || essence.eq_ignore_ascii_case("application/ogg")
+    {
+        audio_video_type(header)
+    } else {
+        None
+    };
+    matched.unwrap_or(supplied)
+}
+
+/// Returns the computed MIME type of a resource loaded in a specific context,
+/// or `None` if it is undefined.
+///
+/// Style sheets and scripts are not sniffed: their supplied MIME type is
+/// returned as is, and browsers then refuse to apply or run the resource if
+/// it is not `text/css` or a JavaScript MIME type.
+#[must_use]
+pub fn sniff_in_context<'a>(
+    context: Context,
+    buf: &[u8],
+    supplied: Option<&'a str>,
+) -> Option<&'a str> {
+    let header = resource_header(buf);
+    if supplied.is_some_and(|supplied| is_xml(essence(supplied))) {
+        return supplied;
+    }
+
+    let matched = match context {
+        Context::Image => image_type(header),
+        Context::AudioVideo => audio_video_type(header),
+        Context::Font => font_type(header),
+        Context::Style | Context::Script => None,
+    };
+    matched.or(supplied)
+}
+
+/// Returns the MIME type of a resource whose type is unknown, following the
+/// "rules for identifying an unknown MIME type".
+///
+/// HTML, XML and PDF are only recognized if `sniff_scriptable` is set. A
+/// resource without binary data is reported as `text/plain` and any other as
+/// `application/octet-stream`.
+#[must_use]
+pub fn identify_unknown(buf: &[u8], sniff_scriptable: bool) -> &'static str {
+    let header = resource_header(buf);
+    if sniff_scriptable {
+        if let Some(mime) = scriptable_type(header) {
+            return mime;
+        }
+    }
+
+    let matched = match_table(header, UNKNOWN_PATTERNS)
+        .or_else(|| image_type(header))
+        .or_else(|| audio_video_type(header))
+        .or_else(|| archive_type(header));
+    match matched {
+        Some(mime) => mime,
+        None if is_binary(header) => "application/octet-stream",
+        None => "text/plain",
+    }
+}
+
+/// Returns the MIME type of an image, following "image type pattern matching".
+#[must_use]
+pub fn image_type(buf: &[u8]) -> Option<&'static str> {
+    match_table(resource_header(buf), IMAGE_PATTERNS)
+}
+
+/// Returns the MIME type of audio or video, following "audio or video type
+/// pattern matching".
+#[must_use]
+pub fn audio_video_type(buf: &[u8]) -> Option<&'static str> {
+    let header = resource_header(buf);
+    if let Some(mime) = match_table(header, AUDIO_VIDEO_PATTERNS) {
+        Some(mime)
+    } else if is_mp4(header) {
+        Some("video/mp4")
+    } else if is_webm(header) {
+        Some("video/webm")
+    } else if is_mp3_without_id3(header) {
+        Some("audio/mpeg")
+    } else {
+        None
+    }
+}
+
+/// Returns the MIME type of a font, following "font type pattern matching".
+#[must_use]
+pub fn font_type(buf: &[u8]) -> Option<&'static str> {
+    match_table(resource_header(buf), FONT_PATTERNS)
+}
+
+/// Returns the MIME type of an archive, following "archive type pattern matching".
+#[must_use]
+pub fn archive_type(buf: &[u8]) -> Option<&'static str> {
+    match_table(resource_header(buf), ARCHIVE_PATTERNS)
+}
+
+/// Returns whether a buffer contains a binary data byte, i.e. a control
+/// character other than a tab, a line feed, a form feed, a carriage return or
+/// an escape.
+#[must_use]
+pub fn is_binary(buf: &[u8]) -> bool {
+    resource_header(buf)
+        .iter()
+        .any(|&b| matches!(b, 0x00..=0x08 | 0x0B | 0x0E..=0x1A | 0x1C..=0x1F))
+}
+
+/// A byte pattern, compared after masking the input with `mask`.
+struct Pattern {
+    pattern: &'static [u8],
+    mask: &'static [u8],
+    mime: &'static str,
+}
+
+const fn exact(pattern: &'static [u8], mime: &'static str) -> Pattern {
+    Pattern {
+        pattern,
+        mask: &[0xFF; 64],
+        mime,
+    }
+}
+
+const WHITESPACE: &[u8] = b"\t\n\x0C\r ";
+
+/// Tags that start an HTML document, matched case-insensitively and followed
+/// by a space or `>`.
+const HTML_TAGS: &[&[u8]] = &[
+    b"<!DOCTYPE HTML",
+    b"<HTML",
+    b"<HEAD",
+    b"<SCRIPT",
+    b"<IFRAME",
+    b"<H1",
+    b"<DIV",
+    b"<FONT",
+    b"<TABLE",
+    b"<A",
+    b"<STYLE",
+    b"<TITLE",
+    b"<B",
+    b"<BODY",
+    b"<BR",
+    b"<P",
+    b"<!--",
+];
+
+/// UTF-16BE, UTF-16LE and UTF-8 byte order marks, which are text even if
+/// followed by null bytes.
+const BOM_PATTERNS: &[Pattern] = &[
+    exact(b"\xFE\xFF", "text/plain"),
+    exact(b"\xFF\xFE", "text/plain"),
+    exact(b"\xEF\xBB\xBF", "text/plain"),
+];
+
+const UNKNOWN_PATTERNS: &[Pattern] = &[
+    exact(b"%!PS-Adobe-", "application/postscript"),
+    Pattern {
+        pattern: b"\xFE\xFF\x00\x00",
+        mask: b"\xFF\xFF\x00\x00",
+        mime: "text/plain",
+    },
+    Pattern {
+        pattern: b"\xFF\xFE\x00\x00",
+        mask: b"\xFF\xFF\x00\x00",
+        mime: "text/plain",
+    },
+    Pattern {
+        pattern: b"\xEF\xBB\xBF\x00",
+        mask: b"\xFF\xFF\xFF\x00",
+        mime: "text/plain",
+    },
+];
+
+const IMAGE_PATTERNS: &[Pattern] = &[
+    exact(b"\x00\x00\x01\x00", "image/x-icon"),
+    exact(b"\x00\x00\x02\x00", "image/x-icon"),
+    exact(b"BM", "image/bmp"),
+    exact(b"GIF87a", "image/gif"),
+    exact(b"GIF89a", "image/gif"),
+    Pattern {
+        pattern: b"RIFF\x00\x00\x00\x00WEBPVP",
+        mask: b"\xFF\xFF\xFF\xFF\x00\x00\x00\x00\xFF\xFF\xFF\xFF\xFF\xFF",
+        mime: "image/webp",
+    },
+    exact(b"\x89PNG\r\n\x1A\n", "image/png"),
+    exact(b"\xFF\xD8\xFF", "image/jpeg"),
+];
+
+const AUDIO_VIDEO_PATTERNS: &[Pattern] = &[
+    exact(b".snd", "audio/basic"),
+    Pattern {
+        pattern: b"FORM\x00\x00\x00\x00AIFF",
+        mask: b"\xFF\xFF\xFF\xFF\x00\x00\x00\x00\xFF\xFF\xFF\xFF",
+        mime: "audio/aiff",
+    },
+    exact(b"ID3", "audio/mpeg"),
+    exact(b"OggS\x00", "application/ogg"),
+    exact(b"MThd\x00\x00\x00\x06", "audio/midi"),
+    Pattern {
+        pattern: b"RIFF\x00\x00\x00\x00AVI ",
+        mask: b"\xFF\xFF\xFF\xFF\x00\x00\x00\x00\xFF\xFF\xFF\xFF",
+        mime: "video/avi",
+    },
+    Pattern {
+        pattern: b"RIFF\x00\x00\x00\x00WAVE",
+        mask: b"\xFF\xFF\xFF\xFF\x00\x00\x00\x00\xFF\xFF\xFF\xFF",
+        mime: "audio/wave",
+    },
+];
+
+const FONT_PATTERNS: &[Pattern] = &[
+    Pattern {
+        pattern: &[
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, b'L', b'P',
+        ],
+        mask: &[
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0xFF, 0xFF,
+        ],
+        mime: "application/vnd.ms-fontobject",
+    },
+    exact(b"\x00\x01\x00\x00", "font/ttf"),
+    exact(b"OTTO", "font/otf"),
+    exact(b"ttcf", "font/collection"),
+    exact(b"wOFF", "font/woff"),
+    exact(b"wOF2", "font/woff2"),
+];
+
+const ARCHIVE_PATTERNS: &[Pattern] = &[
+    exact(b"\x1F\x8B\x08", "application/x-gzip"),
+    exact(b"PK\x03\x04", "application/zip"),
+    exact(b"Rar!\x1A\x07\x00", "application/x-rar-compressed"),
+];
+
+fn resource_header(buf: &[u8]) -> &[u8] {
+    &buf[..buf.len().min(RESOURCE_HEADER_LEN)]
+}
+
+fn match_table(header: &[u8], table: &[Pattern]) -> Option<&'static str> {
+    table
+        .iter()
+        .find(|p| {
+            header.len() >= p.pattern.len()
+                && header
+                    .iter()
+                    .zip(p.pattern.iter().zip(p.mask))
+                    .all(|(&b, (&pattern, &mask))| b & mask == pattern)
+        })
+        .map(|p| p.mime)
+}
+
+/// Returns the MIME type of HTML, XML or PDF, the types that may run scripts.
+fn scriptable_type(header: &[u8]) -> Option<&'static str> {
+    if header.starts_with(b"%PDF-") {
+        return Some("application/pdf");
+    }
+
+    let start = header
+        .iter()
+        .position(|b| !WHITESPACE.contains(b))
+        .unwrap_or(header.len());
+    let rest = &header[start..];
+    let is_tag = |tag: &[u8]| {
+        rest.len() > tag.len()
+            && rest[..tag.len()].eq_ignore_ascii_case(tag)
+            && matches!(rest[tag.len()], b' ' | b'>')
+    };
+
+    if HTML_TAGS.iter().copied().any(is_tag) {
+        Some("text/html")
+    } else if rest.starts_with(b"<?xml") {
+        Some("text/xml")
+    } else {
+        None
+    }
+}
+
+/// Returns the MIME type of an RSS or Atom feed served as HTML, following
+/// "sniffing a mislabeled feed".
+fn feed_type(header: &[u8]) -> Option<&'static str> {
+    let mut s = if header.starts_with(b"\xEF\xBB\xBF") {
+        3
+    } else {
+        0
+    };
+
+    loop {
+        // skip whitespace up to the next markup
+        loop {
+            match header.get(s)? {
+                b'<' => {
+                    s += 1;
+                    break;
+                }
+                b if WHITESPACE.contains(b) => s += 1,
+                _ => return None,
+            }
+        }
+
+        let rest = header.get(s..)?;
+        if let Some(comment) = rest.strip_prefix(b"!--") {
+            s += 3 + find(comment, b"-->")? + 3;
+        } else if let Some(declaration) = rest.strip_prefix(b"!") {
+            s += 1 + find(declaration, b">")? + 1;
+        } else if let Some(instruction) = rest.strip_prefix(b"?") {
+            s += 1 + find(instruction, b"?>")? + 2;
+        } else if rest.starts_with(b"rss") {
+            return Some("application/rss+xml");
+        } else if rest.starts_with(b"feed") {
+            return Some("application/atom+xml");
+        } else if let Some(rdf) = rest.strip_prefix(b"rdf:RDF") {
+            let rss = find(rdf, b"http://purl.org/rss/1.0/").is_some();
+            let syntax = find(rdf, b"http://www.w3.org/1999/02/22-rdf-syntax-ns#").is_some();
+            return (rss && syntax).then_some("application/rss+xml");
+        } else {
+            return None;
+        }
+    }
+}
+
+/// Returns whether a buffer starts with a `ftyp` box with an `mp4` brand.
+fn is_mp4(header: &[u8]) -> bool {
+    if header.len() < 12 {
+        return false;
+    }
+    let box_size = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+    if header.len() < box_size || box_size % 4 != 0 || &header[4..8] != b"ftyp" {
+        return false;
+    }
+    if &header[8..11] == b"mp4" {
+        return true;
+    }
+    (16..box_size)
+        .step_by(4)
+        .any(|i| header.get(i..i + 3) == Some(b"mp4"))
+}
+
+/// Returns whether a buffer is an EBML document with the `webm` doc type.
+fn is_webm(header: &[u8]) -> bool {
+    if !header.starts_with(&[0x1A, 0x45, 0xDF, 0xA3]) {
+        return false;
+    }
+
+    let mut iter = 4;
+    while iter < header.len() && iter < 38 {
+        if header[iter..].starts_with(&[0x42, 0x82]) {
+            iter += 2;
+            if iter >= header.len() {
+                break;
+            }
+            iter += vint_len(header[iter]);
+            if iter >= header.len() - 4 {
+                break;
+            }
+            // the doc type may be padded with NUL bytes
+            let padding = header[iter..].iter().take_while(|&&b| b == 0).count();
+            return header[iter + padding..].starts_with(b"webm");
+        }
+        iter += 1;
+    }
+    false
+}
+
+/// Returns the length of an EBML variable size integer from its first byte.
+fn vint_len(first: u8) -> usize {
+    (first.leading_zeros() as usize + 1).min(8)
+}
+
+/// Returns whether a buffer starts with two consecutive MPEG audio frames.
+fn is_mp3_without_id3(header: &[u8]) -> bool {
+    const MP3_RATES: [u32; 15] = [
+        0, 32000, 40000, 48000, 56000, 64000, 80000, 96000, 112000, 128000, 160000, 192000, 224000,
+        256000, 320000,
+    ];
+    const MP25_RATES: [u32; 15] = [
+        0, 8000, 16000, 24000, 32000, 40000, 48000, 56000, 64000, 80000, 96000, 112000, 128000,
+        144000, 160000,
+    ];
+    const SAMPLE_RATES: [u32; 3] = [44100, 48000, 32000];
+
+    if !is_mp3_header(header) {
+        return false;
+    }
+
+    let version = (header[1] & 0x18) >> 3;
+    let bitrate_index = usize::from((header[2] & 0xF0) >> 4);
+    let bitrate = if version & 0x01 != 0 {
+        MP3_RATES[bitrate_index]
+    } else {
+        MP25_RATES[bitrate_index]
+    };
+    let sample_rate = SAMPLE_RATES[usize::from((header[2] & 0x0C) >> 2)];
+    let padding = u32::from((header[2] & 0x02) >> 1);
+
+    let scale = if version == 1 { 72 } else { 144 };
+    let frame_len = (bitrate * scale / sample_rate + padding) as usize;
+    if frame_len < 4 || frame_len > header.len() {
+        return false;
+    }
+    is_mp3_header(&header[frame_len..])
+}
+
+fn is_mp3_header(buf: &[u8]) -> bool {
+    buf.len() >= 4
+        && buf[0] == 0xFF
+        && buf[1] & 0xE0 == 0xE0
+        // layer, bitrate and sample rate must not be reserved
+        && (buf[1] & 0x06) >> 1 != 0
+        && (buf[2] & 0xF0) >> 4 != 15
+        && (buf[2] & 0x0C) >> 2 != 3
+}
+
+/// Returns whether the `Content-Type` is one that old Apache versions sent
+/// for any file without a known extension.
+fn is_apache_bug(supplied: &str) -> bool {
+    matches!(
+        supplied,
+        "text/plain"
+            | "text/plain; charset=ISO-8859-1"
+            | "text/plain; charset=iso-8859-1"
+            | "text/plain; charset=UTF-8"
+    )
+}
+
+/// Returns the type and subtype of a MIME type, without parameters.
+pub(crate) fn essence(mime: &str) -> &str {
+    mime.split(';').next().unwrap_or(mime).trim()
+}
+
+fn top_level_type(essence: &str) -> &str {
+    essence.split('/').next().unwrap_or(essence)
+}
+
+pub(crate) fn is_xml(essence: &str) -> bool {
+    let subtype = essence.split('/').nth(1).unwrap_or_default();
+    (subtype.len() >= 4 && subtype[subtype.len() - 4..].eq_ignore_ascii_case("+xml"))
+        || essence.eq_ignore_ascii_case("text/xml")
+        || essence.eq_ignore_ascii_case("application/xml")
+}
+
+fn find(buf: &[u8], needle: &[u8]) -> Option<usize> {
+    buf.windows(needle.len()).position(|w| w == needle)
+}
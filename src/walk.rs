@@ -0,0 +1,349 @@
+//! Parallel directory tree scanning.
+//!
+//! Requires the `walk` feature.
+//!
+//! # Examples
+//!
+//! ```rust
+//! let mut entries = infer::walk::Walk::new("testdata")
+//!     .threads(2)
+//!     .include("*.jpg")
+//!     .expect("valid glob")
+//!     .run()
+//!     .expect("valid glob set");
+//!
+//! for (path, kind) in &mut entries {
+//!     let kind = kind.expect("file read successfully");
+//!     println!("{}: {:?}", path.display(), kind);
+//! }
+//!
+//! let jpeg = infer::get(&[0xFF, 0xD8, 0xFF, 0xAA]).unwrap();
+//! assert_eq!(entries.counters().count(&jpeg), 1);
+//! ```
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use walkdir::WalkDir;
+
+use crate::{Infer, Type};
+
+/// Error returned for invalid include or exclude globs.
+pub use globset::Error as GlobError;
+
+/// Number of queued paths and results allowed per worker thread.
+const QUEUE_DEPTH: usize = 64;
+
+/// Item produced by [`Entries`].
+pub type Entry = (PathBuf, io::Result<Option<Type>>);
+
+/// How symbolic links are handled while walking.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum Symlinks {
+    /// Symbolic links are ignored.
+    #[default]
+    Skip,
+    /// Symbolic links to files are classified, but links to directories are not descended into.
+    Files,
+    /// All symbolic links are followed.
+    Follow,
+}
+
+/// Builder for a parallel directory walk.
+///
+/// Files are classified with [`Infer::get_from_path`] on a pool of worker threads
+/// and the results are yielded by [`Entries`] in completion order.
+pub struct Walk {
+    root: PathBuf,
+    threads: usize,
+    symlinks: Symlinks,
+    include: GlobSetBuilder,
+    exclude: GlobSetBuilder,
+    infer: Infer,
+}
+
+impl Walk {
+    /// Creates a walk over the tree rooted at `root`.
+    pub fn new<P: AsRef<Path>>(root: P) -> Walk {
+        Walk {
+            root: root.as_ref().to_path_buf(),
+            threads: 0,
+            symlinks: Symlinks::default(),
+            include: GlobSetBuilder::new(),
+            exclude: GlobSetBuilder::new(),
+            infer: Infer::new(),
+        }
+    }
+
+    /// Sets the number of worker threads.
+    ///
+    /// Defaults to the available parallelism when set to `0`.
+    #[must_use]
+    pub fn threads(mut self, threads: usize) -> Walk {
+        self.threads = threads;
+        self
+    }
+
+    /// Sets how symbolic links are handled. Defaults to [`Symlinks::Skip`].
+    #[must_use]
+    pub fn symlinks(mut self, symlinks: Symlinks) -> Walk {
+        self.symlinks = symlinks;
+        self
+    }
+
+    /// Only classifies files whose path relative to the root matches `glob`.
+    ///
+    /// May be called multiple times, a file is included when it matches any of the globs.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `glob` is not a valid glob.
+    pub fn include(mut self, glob: &str) -> Result<Walk, GlobError> {
+        self.include.add(Glob::new(glob)?);
+        Ok(self)
+    }
+
+    /// Skips files and directories whose path relative to the root matches `glob`.
+    ///
+    /// May be called multiple times. Excluded directories are not descended into.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `glob` is not a valid glob.
+    pub fn exclude(mut self, glob: &str) -> Result<Walk, GlobError> {
+        self.exclude.add(Glob::new(glob)?);
+        Ok(self)
+    }
+
+    /// Uses the given `Infer`, e.g. one with custom matchers, to classify files.
+    #[must_use]
+    pub fn infer(mut self, infer: Infer) -> Walk {
+        self.infer = infer;
+        self
+    }
+
+    /// Starts walking the tree in the background.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the include or exclude globs can not be combined.
+    pub fn run(self) -> Result<Entries, GlobError> {
+        let include = self.include.build()?;
+        let exclude = self.exclude.build()?;
+        let threads = match self.threads {
+            0 => thread::available_parallelism().map_or(1, usize::from),
+            n => n,
+        };
+
+        let counters = Arc::new(Counters::default());
+        let infer = Arc::new(self.infer);
+        let (path_tx, path_rx) = mpsc::sync_channel::<PathBuf>(threads * QUEUE_DEPTH);
+        let (entry_tx, entry_rx) = mpsc::sync_channel::<Entry>(threads * QUEUE_DEPTH);
+        let path_rx = Arc::new(Mutex::new(path_rx));
+
+        for _ in 0..threads {
+            let path_rx = Arc::clone(&path_rx);
+            let entry_tx = entry_tx.clone();
+            let counters = Arc::clone(&counters);
+            let infer = Arc::clone(&infer);
+            thread::spawn(move || classify(&path_rx, &entry_tx, &counters, &infer));
+        }
+
+        let walker = Walker {
+            root: self.root,
+            symlinks: self.symlinks,
+            include,
+            exclude,
+        };
+        let walk_counters = Arc::clone(&counters);
+        thread::spawn(move || walker.walk(&path_tx, &entry_tx, &walk_counters));
+
+        Ok(Entries {
+            rx: entry_rx,
+            counters,
+        })
+    }
+}
+
+/// Iterator over the classified files of a [`Walk`].
+///
+/// Dropping it stops the walk.
+pub struct Entries {
+    rx: Receiver<Entry>,
+    counters: Arc<Counters>,
+}
+
+impl Entries {
+    /// Returns the counters of the walk, which are updated as files are classified.
+    #[must_use]
+    pub fn counters(&self) -> &Counters {
+        &self.counters
+    }
+}
+
+impl Iterator for Entries {
+    type Item = Entry;
+
+    fn next(&mut self) -> Option<Entry> {
+        self.rx.recv().ok()
+    }
+}
+
+/// Progress and per-type counters of a [`Walk`].
+#[derive(Default)]
+pub struct Counters {
+    files: AtomicU64,
+    errors: AtomicU64,
+    unknown: AtomicU64,
+    types: Mutex<Vec<(Type, u64)>>,
+}
+
+impl Counters {
+    /// Returns the number of files classified so far, including the ones that
+    /// could not be read. Errors of the directory walk, such as unreadable
+    /// directories, are only counted by [`errors`](Self::errors).
+    #[must_use]
+    pub fn files(&self) -> u64 {
+        self.files.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of files or directories that could not be read.
+    #[must_use]
+    pub fn errors(&self) -> u64 {
+        self.errors.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of files of unknown type.
+    #[must_use]
+    pub fn unknown(&self) -> u64 {
+        self.unknown.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of files of the given type.
+    #[must_use]
+    pub fn count(&self, kind: &Type) -> u64 {
+        self.types
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .iter()
+            .find(|(k, _)| k == kind)
+            .map_or(0, |(_, n)| *n)
+    }
+
+    /// Returns the number of files of every type seen so far.
+    #[must_use]
+    pub fn types(&self) -> Vec<(Type, u64)> {
+        self.types
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone()
+    }
+
+    fn record(&self, kind: &io::Result<Option<Type>>) {
+        self.files.fetch_add(1, Ordering::Relaxed);
+        match kind {
+            Ok(Some(kind)) => {
+                let mut types = self
+                    .types
+                    .lock()
+                    .unwrap_or_else(std::sync::PoisonError::into_inner);
+                match types.iter_mut().find(|(k, _)| k == kind) {
+                    Some((_, n)) => *n += 1,
+                    None => types.push((*kind, 1)),
+                }
+            }
+            Ok(None) => {
+                self.unknown.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(_) => {
+                self.errors.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+struct Walker {
+    root: PathBuf,
+    symlinks: Symlinks,
+    include: GlobSet,
+    exclude: GlobSet,
+}
+
+impl Walker {
+    fn walk(&self, paths: &SyncSender<PathBuf>, entries: &SyncSender<Entry>, counters: &Counters) {
+        let walk = WalkDir::new(&self.root)
+            .follow_links(self.symlinks == Symlinks::Follow)
+            .into_iter()
+            .filter_entry(|e| e.depth() == 0 || !self.exclude.is_match(self.relative(e.path())));
+
+        for entry in walk {
+            let sent = match entry {
+                Ok(entry) => {
+                    if !self.is_candidate(&entry) {
+                        continue;
+                    }
+                    paths.send(entry.into_path()).is_ok()
+                }
+                Err(err) => {
+                    let path = err.path().unwrap_or(&self.root).to_path_buf();
+                    let err = Err(io::Error::from(err));
+                    counters.errors.fetch_add(1, Ordering::Relaxed);
+                    entries.send((path, err)).is_ok()
+                }
+            };
+
+            if !sent {
+                // `Entries` has been dropped.
+                return;
+            }
+        }
+    }
+
+    fn is_candidate(&self, entry: &walkdir::DirEntry) -> bool {
+        let is_file = if entry.path_is_symlink() {
+            match self.symlinks {
+                Symlinks::Skip => false,
+                Symlinks::Files => fs::metadata(entry.path()).is_ok_and(|m| m.is_file()),
+                Symlinks::Follow => entry.file_type().is_file(),
+            }
+        } else {
+            entry.file_type().is_file()
+        };
+
+        is_file && (self.include.is_empty() || self.include.is_match(self.relative(entry.path())))
+    }
+
+    fn relative<'a>(&self, path: &'a Path) -> &'a Path {
+        path.strip_prefix(&self.root).unwrap_or(path)
+    }
+}
+
+fn classify(
+    paths: &Mutex<Receiver<PathBuf>>,
+    entries: &SyncSender<Entry>,
+    counters: &Counters,
+    infer: &Infer,
+) {
+    loop {
+        let next = paths
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .recv();
+        let Ok(path) = next else {
+            // The walk is complete.
+            return;
+        };
+
+        let kind = infer.get_from_path(&path);
+        counters.record(&kind);
+        if entries.send((path, kind)).is_err() {
+            return;
+        }
+    }
+}
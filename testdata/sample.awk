@@ -0,0 +1,3 @@
+#!/usr/bin/gawk -f
+
+BEGIN { print "Hello, world!" }
@@ -0,0 +1,3 @@
+#!/usr/bin/env -S node --no-warnings
+
+console.log("Hello, world!");
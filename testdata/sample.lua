@@ -0,0 +1,3 @@
+#!/usr/bin/env lua5.4
+
+print("Hello, world!")
@@ -0,0 +1,3 @@
+#!/usr/bin/php8.2
+<?php
+echo "Hello, world!\n";
@@ -0,0 +1,4 @@
+#!/usr/bin/perl -w
+use strict;
+
+print "Hello, world!\n";
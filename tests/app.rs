@@ -2,6 +2,62 @@ mod common;
 
 test_format!(App, "application/x-executable", "elf", elf, "sample_elf");
 
+test_format!(
+    App,
+    "application/x-executable",
+    "elf",
+    elf_mips,
+    "sample_elf_mips"
+);
+
+test_format!(
+    App,
+    "application/x-object",
+    "o",
+    elf_relocatable,
+    "sample_elf_relocatable.o"
+);
+
+test_format!(
+    App,
+    "application/x-sharedlib",
+    "so",
+    elf_shared,
+    "sample_elf_shared.so"
+);
+
+test_format!(
+    App,
+    "application/x-sharedlib",
+    "so",
+    elf_ppc_shared,
+    "sample_elf_ppc_shared.so"
+);
+
+test_format!(
+    App,
+    "application/x-pie-executable",
+    "elf",
+    elf_pie,
+    "sample_elf_pie"
+);
+
+test_format!(
+    App,
+    "application/x-pie-executable",
+    "elf",
+    elf_static_pie,
+    "sample_elf_static_pie"
+);
+
+test_format!(
+    App,
+    "application/x-coredump",
+    "core",
+    elf_core,
+    "sample_elf_core"
+);
+
 test_format!(
     App,
     "application/vnd.microsoft.portable-executable",
@@ -10,6 +66,32 @@ test_format!(
     "sample.exe"
 );
 
+test_format!(
+    App,
+    "application/vnd.microsoft.portable-executable",
+    "dll",
+    dll,
+    "sample.dll"
+);
+
+test_format!(
+    App,
+    "application/vnd.microsoft.portable-executable",
+    "dll",
+    dll_x86,
+    "sample_x86.dll"
+);
+
+test_format!(
+    App,
+    "application/vnd.microsoft.portable-executable",
+    "sys",
+    sys,
+    "sample.sys"
+);
+
+test_format!(App, "application/efi", "efi", efi, "sample.efi");
+
 test_format!(
     App,
     "application/x-mach-binary",
@@ -42,14 +124,118 @@ test_format!(
     "sample_mach_fat"
 );
 
+test_format!(
+    App,
+    "application/x-mach-dylib",
+    "dylib",
+    mach_dylib,
+    "sample.dylib"
+);
+
+test_format!(
+    App,
+    "application/x-mach-dylib",
+    "dylib",
+    mach_fat_dylib,
+    "sample_mach_fat.dylib"
+);
+
+test_format!(
+    App,
+    "application/x-mach-bundle",
+    "bundle",
+    mach_bundle,
+    "sample.bundle"
+);
+
 test_format!(App, "application/java", "class", java, "sample.class");
 
+test_format!(
+    App,
+    "application/java",
+    "class",
+    java8,
+    "sample_java8.class"
+);
+
+test_format!(App, "application/x-java-jmod", "jmod", jmod, "sample.jmod");
+
+test_format!(
+    App,
+    "application/x-java-jimage",
+    "jimage",
+    jimage,
+    "sample.jimage"
+);
+
+test_format!(
+    App,
+    "application/x-kotlin-metadata",
+    "kotlin_module",
+    kotlin_module,
+    "sample.kotlin_module"
+);
+
+#[test]
+fn kotlin_metadata_needs_module() {
+    // a version header alone is not enough
+    let mut buf = [0; 64];
+    buf[3] = 3;
+    buf[7] = 1;
+    buf[11] = 9;
+    assert!(!infer::app::is_kotlin_metadata(&buf));
+    assert_ne!(
+        infer::get(&buf).map(|kind| kind.mime_type()),
+        Some("application/x-kotlin-metadata")
+    );
+
+    // a module of the root package, without the flags of older versions
+    let buf = b"\x00\x00\x00\x03\x00\x00\x00\x01\x00\x00\x00\x03\x00\x00\x00\x00\x0A\x0A\x0A\x00\x12\x06MainKt";
+    assert!(infer::app::is_kotlin_metadata(buf));
+}
+
 test_format!(App, "application/wasm", "wasm", wasm, "sample.wasm");
 
 test_format!(App, "application/x-x509-ca-cert", "der", der, "sample.der");
 
 test_format!(App, "application/x-x509-ca-cert", "pem", pem, "sample.pem");
 
+test_format!(App, "application/x-iso9660-image", "iso", iso, "sample.iso");
+
+test_format!(App, "application/x-udf-image", "iso", udf, "sample_udf.iso");
+
+test_format!(
+    App,
+    "application/x-apple-diskimage",
+    "dmg",
+    dmg,
+    "sample.dmg"
+);
+
+test_format!(App, "application/x-vhd-disk", "vhd", vhd, "sample.vhd");
+
+test_format!(App, "application/x-vhdx-disk", "vhdx", vhdx, "sample.vhdx");
+
+test_format!(App, "application/x-vmdk-disk", "vmdk", vmdk, "sample.vmdk");
+
+test_format!(
+    App,
+    "application/x-vmdk-disk",
+    "vmdk",
+    vmdk_descriptor,
+    "sample_descriptor.vmdk"
+);
+
+test_format!(App, "application/x-vdi-disk", "vdi", vdi, "sample.vdi");
+
+test_format!(
+    App,
+    "application/x-android-sparse-image",
+    "img",
+    android_sparse,
+    "sample.img"
+);
+
 test_format!(
     App,
     "application/x-qemu-disk",
@@ -57,3 +243,248 @@ test_format!(
     qcow2,
     "sample.qcow2"
 );
+
+test_format!(App, "application/java-archive", "jar", jar, "sample.jar");
+
+test_format!(
+    App,
+    "application/vnd.android.package-archive",
+    "apk",
+    apk,
+    "sample.apk"
+);
+
+test_format!(
+    App,
+    "application/x-android-app-bundle",
+    "aab",
+    aab,
+    "sample.aab"
+);
+
+test_format!(App, "application/x-ios-app", "ipa", ipa, "sample.ipa");
+
+test_format!(App, "application/x-xpinstall", "xpi", xpi, "sample.xpi");
+
+test_format!(App, "application/vsix", "vsix", vsix, "sample.vsix");
+
+mod elf_info {
+    use infer::elf::{info, Class, Endianness, Machine, ObjectType, OsAbi};
+
+    #[test]
+    fn header() {
+        let elf = info(include_bytes!("../testdata/sample_elf_ppc_shared.so")).unwrap();
+        assert_eq!(elf.class(), Class::Elf32);
+        assert_eq!(elf.endianness(), Endianness::Big);
+        assert_eq!(elf.machine(), Machine::PowerPc);
+        assert_eq!(elf.os_abi(), OsAbi::SystemV);
+        assert_eq!(elf.object_type(), ObjectType::SharedLibrary);
+
+        let elf = info(include_bytes!("../testdata/sample_elf_static_pie")).unwrap();
+        assert_eq!(elf.machine().name(), "riscv");
+        assert_eq!(elf.object_type(), ObjectType::PieExecutable);
+    }
+
+    #[test]
+    fn program_headers_cut_off() {
+        let buf = include_bytes!("../testdata/sample_elf_pie");
+        let elf = info(&buf[..64]).unwrap();
+        assert_eq!(elf.object_type(), ObjectType::SharedLibrary);
+    }
+
+    #[test]
+    fn program_header_offset_overflow() {
+        // ET_DYN on x86-64 with `e_phoff` at the end of the address space
+        let mut buf = [0; 128];
+        buf[..8].copy_from_slice(&[0x7F, b'E', b'L', b'F', 2, 1, 1, 0]);
+        buf[16..22].copy_from_slice(&[3, 0, 0x3E, 0, 1, 0]);
+        buf[32..40].copy_from_slice(&[0xFF; 8]);
+        buf[52..58].copy_from_slice(&[64, 0, 56, 0, 1, 0]);
+
+        let elf = info(&buf).unwrap();
+        assert_eq!(elf.object_type(), ObjectType::SharedLibrary);
+        assert_eq!(infer::get(&buf).unwrap().extension(), "so");
+    }
+
+    /// Returns an x86-64 `ET_DYN` file with an interpreter and the given dynamic entries.
+    fn with_interpreter(dynamic: &[(u64, u64)]) -> Vec<u8> {
+        let mut buf = vec![0; 176];
+        buf[..8].copy_from_slice(&[0x7F, b'E', b'L', b'F', 2, 1, 1, 0]);
+        buf[16..22].copy_from_slice(&[3, 0, 0x3E, 0, 1, 0]);
+        buf[32] = 64;
+        buf[52..58].copy_from_slice(&[64, 0, 56, 0, 2, 0]);
+        // PT_INTERP, then PT_DYNAMIC at the end of the program headers
+        buf[64] = 3;
+        buf[120] = 2;
+        buf[128] = 176;
+        for &(tag, value) in dynamic.iter().chain(&[(0, 0)]) {
+            buf.extend_from_slice(&tag.to_le_bytes());
+            buf.extend_from_slice(&value.to_le_bytes());
+        }
+        buf
+    }
+
+    #[test]
+    fn interpreter() {
+        const DT_SONAME: u64 = 14;
+        const DT_DEBUG: u64 = 21;
+        const DT_FLAGS_1: u64 = 0x6FFF_FFFB;
+        const DF_1_PIE: u64 = 0x0800_0000;
+
+        // shared libraries like libc.so.6 and ld.so have an interpreter too
+        let buf = with_interpreter(&[(DT_SONAME, 1)]);
+        assert_eq!(info(&buf).unwrap().object_type(), ObjectType::SharedLibrary);
+        assert_eq!(infer::get(&buf).unwrap().extension(), "so");
+
+        let buf = with_interpreter(&[(DT_DEBUG, 0)]);
+        assert_eq!(info(&buf).unwrap().object_type(), ObjectType::PieExecutable);
+
+        let buf = with_interpreter(&[(DT_SONAME, 1), (DT_FLAGS_1, DF_1_PIE)]);
+        assert_eq!(info(&buf).unwrap().object_type(), ObjectType::PieExecutable);
+    }
+
+    #[test]
+    fn invalid() {
+        let mut buf = *include_bytes!("../testdata/sample_elf_mips");
+        assert!(info(&buf[..51]).is_none());
+        buf[4] = 3;
+        assert!(info(&buf).is_none());
+    }
+}
+
+mod pe_info {
+    use infer::pe::{info, Format, Machine, Subsystem};
+
+    #[test]
+    fn header() {
+        let pe = info(include_bytes!("../testdata/sample_x86.dll")).unwrap();
+        assert_eq!(pe.machine(), Machine::I386);
+        assert_eq!(pe.machine().name(), "x86");
+        assert_eq!(pe.format(), Format::Pe32);
+        assert_eq!(pe.subsystem(), Subsystem::WindowsConsole);
+        assert!(pe.is_dll());
+
+        let pe = info(include_bytes!("../testdata/sample.efi")).unwrap();
+        assert_eq!(pe.machine(), Machine::Arm64);
+        assert!(pe.subsystem().is_efi());
+    }
+
+    #[test]
+    fn header_cut_off() {
+        let buf = include_bytes!("../testdata/sample.dll");
+        assert!(info(&buf[..100]).is_none());
+        assert!(infer::app::is_exe(&buf[..100]));
+        assert!(!infer::app::is_dll(&buf[..100]));
+    }
+}
+
+mod macho_info {
+    use infer::macho::{info, slices, Cpu, FileType, Width};
+
+    #[test]
+    fn header() {
+        let macho = info(include_bytes!("../testdata/sample_mach_ppc")).unwrap();
+        assert_eq!(macho.cpu(), Cpu::PowerPc);
+        assert_eq!(macho.width(), Width::Bits32);
+        assert_eq!(macho.file_type(), FileType::Executable);
+
+        let macho = info(include_bytes!("../testdata/sample.bundle")).unwrap();
+        assert_eq!(macho.cpu().name(), "aarch64");
+        assert_eq!(macho.width(), Width::Bits64);
+        assert_eq!(macho.file_type(), FileType::Bundle);
+    }
+
+    #[test]
+    fn universal() {
+        let buf = include_bytes!("../testdata/sample_mach_fat.dylib");
+        let all: Vec<_> = slices(buf).unwrap().collect();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].cpu(), Cpu::X86_64);
+        assert_eq!(all[0].offset(), 0x1000);
+        assert_eq!(all[1].cpu(), Cpu::Arm64);
+        // arm64e, without the pointer authentication ABI bit
+        assert_eq!(all[1].cpu_subtype(), 2);
+        assert_eq!(all[1].size(), 0x1000);
+        assert_eq!(all[1].info().unwrap().file_type(), FileType::Dylib);
+
+        assert_eq!(info(buf).unwrap().cpu(), Cpu::X86_64);
+        assert!(slices(include_bytes!("../testdata/sample.dylib")).is_none());
+        assert!(slices(include_bytes!("../testdata/sample.class")).is_none());
+    }
+
+    #[test]
+    fn universal_cut_off() {
+        let buf = include_bytes!("../testdata/sample_mach_fat.dylib");
+        assert_eq!(slices(&buf[..40]).unwrap().count(), 1);
+        assert!(slices(&buf[..0x1000])
+            .unwrap()
+            .all(|slice| slice.info().is_none()));
+        assert!(info(&buf[..0x1000]).is_none());
+        assert!(infer::app::is_mach(&buf[..0x1000]));
+        assert!(!infer::app::is_mach_dylib(&buf[..0x1000]));
+    }
+}
+
+mod class_file {
+    use infer::java::info;
+
+    #[test]
+    fn version() {
+        let class = info(include_bytes!("../testdata/sample_java8.class")).unwrap();
+        assert_eq!(class.major_version(), 52);
+        assert_eq!(class.minor_version(), 0);
+        assert_eq!(class.release(), 8);
+        assert!(!class.is_preview());
+
+        let class = info(b"\xCA\xFE\xBA\xBE\xFF\xFF\x00\x41").unwrap();
+        assert_eq!(class.release(), 21);
+        assert!(class.is_preview());
+    }
+
+    #[test]
+    fn universal_binary() {
+        assert!(info(include_bytes!("../testdata/sample_mach_fat")).is_none());
+    }
+}
+
+mod disk_image {
+    use infer::app::{is_dmg, is_iso, is_udf, is_vhd};
+
+    #[test]
+    fn volume_descriptors_beyond_prefix() {
+        let buf = include_bytes!("../testdata/sample.iso");
+        assert!(is_iso(buf));
+        assert!(!is_iso(&buf[..8192]));
+    }
+
+    #[test]
+    fn udf_bridge() {
+        // ISO 9660 descriptors followed by the UDF ones
+        let mut buf = include_bytes!("../testdata/sample.iso")[..0x9000].to_vec();
+        buf.extend_from_slice(&include_bytes!("../testdata/sample_udf.iso")[0x8000..]);
+        assert!(is_iso(&buf));
+        assert!(is_udf(&buf));
+        assert_eq!(
+            infer::get(&buf).unwrap().mime_type(),
+            "application/x-udf-image"
+        );
+
+        assert!(!is_udf(include_bytes!("../testdata/sample.iso")));
+    }
+
+    #[test]
+    fn footer() {
+        let buf = include_bytes!("../testdata/sample.dmg");
+        assert!(is_dmg(buf));
+        // trailing bytes after the footer
+        let mut padded = buf.to_vec();
+        padded.push(0);
+        assert!(!is_dmg(&padded));
+
+        // fixed disks created before Virtual PC 2004 have a 511 byte footer
+        let buf = include_bytes!("../testdata/sample.vhd");
+        assert!(is_vhd(buf));
+        assert!(is_vhd(&buf[..buf.len() - 1]));
+        assert!(!is_vhd(&buf[..buf.len() - 2]));
+    }
+}
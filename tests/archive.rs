@@ -26,6 +26,9 @@ test_format!(
     "sample.skippable.zst"
 );
 test_format!(Archive, "application/x-par2", "par2", par2, "sample.par2");
+test_format!(Archive, "application/gzip", "gz", gz, "sample.tar.gz");
+test_format!(Archive, "application/x-xz", "xz", xz, "sample.tar.xz");
+test_format!(Archive, "application/x-bzip2", "bz2", bz2, "sample.tar.bz2");
 
 #[test]
 fn zstd_many_empty_skippable_frames_do_not_recurse() {
@@ -62,3 +65,39 @@ fn lz4_skippable_frames_before_real_frame_match() {
 
     assert!(infer::archive::is_lz4(&input));
 }
+
+test_format!(
+    Archive,
+    "application/x-ole-storage",
+    "msi",
+    msi,
+    "sample.msi"
+);
+
+test_format!(Archive, "application/x-ms-patch", "msp", msp, "sample.msp");
+
+test_format!(
+    Archive,
+    "application/x-ms-transform",
+    "mst",
+    mst,
+    "sample.mst"
+);
+
+test_format!(
+    Archive,
+    "application/x-ms-thumbnail-cache",
+    "db",
+    thumbs_db,
+    "sample_thumbs.db"
+);
+
+#[test]
+fn truncated_ole_storage() {
+    // the directory of the compound file is not contained in the first 8 KiB
+    let buf = include_bytes!("../testdata/sample.doc");
+    let kind = infer::get(&buf[..8192]).expect("compound file header matches");
+
+    assert!(infer::archive::is_ole_storage(&buf[..8192]));
+    assert_eq!(kind.mime_type(), "application/x-ole-storage");
+}
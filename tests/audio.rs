@@ -4,3 +4,105 @@ test_format!(Audio, "audio/mpeg", "mp3", mp3, "sample.mp3");
 test_format!(Audio, "audio/x-dsf", "dsf", dsf, "sample.dsf");
 test_format!(Audio, "audio/x-ape", "ape", ape, "sample.ape");
 test_format!(Audio, "audio/opus", "opus", opus, "sample_48kbps.opus");
+test_format!(Audio, "audio/mpeg", "mp3", mp3_vbr, "sample_vbr.mp3");
+test_format!(Audio, "audio/x-wav", "wav", wav, "sample.wav");
+test_format!(Audio, "audio/x-aiff", "aiff", aiff, "sample.aiff");
+test_format!(Audio, "audio/x-flac", "flac", flac, "sample.flac");
+test_format!(Audio, "audio/ogg", "ogg", ogg_vorbis, "sample.oga");
+
+test_format!(Audio, "audio/x-m4b", "m4b", m4b, "sample.m4b");
+
+test_format!(Audio, "audio/mp4", "f4a", f4a, "sample.f4a");
+
+test_format!(Audio, "audio/x-rf64", "rf64", rf64, "sample.rf64");
+
+test_format!(Audio, "audio/x-bw64", "bw64", bw64, "sample.bw64");
+
+test_format!(Audio, "audio/x-soundfont", "sf2", sf2, "sample.sf2");
+
+test_format!(Audio, "audio/dls", "dls", dls, "sample.dls");
+
+test_format!(Audio, "audio/midi", "rmi", rmi, "sample.rmi");
+
+test_format!(Audio, "audio/qcelp", "qcp", qcp, "sample.qcp");
+
+mod stream {
+    use core::time::Duration;
+    use infer::audio::info;
+
+    fn params(buf: &[u8]) -> (u32, u16, Option<u8>) {
+        let info = info(buf).unwrap();
+        (info.sample_rate(), info.channels(), info.bit_depth())
+    }
+
+    #[test]
+    fn uncompressed() {
+        let wav = info(include_bytes!("../testdata/sample.wav")).unwrap();
+        assert_eq!(
+            (wav.sample_rate(), wav.channels(), wav.bit_depth()),
+            (22050, 2, Some(16))
+        );
+        assert_eq!(wav.duration(), Some(Duration::from_nanos(249_977_324)));
+
+        let rf64 = info(include_bytes!("../testdata/sample.rf64")).unwrap();
+        assert_eq!(rf64.sample_rate(), 44100);
+
+        let aiff = info(include_bytes!("../testdata/sample.aiff")).unwrap();
+        assert_eq!(
+            (aiff.sample_rate(), aiff.channels(), aiff.bit_depth()),
+            (11025, 1, Some(8))
+        );
+    }
+
+    #[test]
+    fn lossless() {
+        let flac = info(include_bytes!("../testdata/sample.flac")).unwrap();
+        assert_eq!(
+            (flac.sample_rate(), flac.channels(), flac.bit_depth()),
+            (96000, 6, Some(24))
+        );
+        assert_eq!(flac.duration(), Some(Duration::from_secs(2)));
+
+        assert_eq!(
+            params(include_bytes!("../testdata/sample.ape")),
+            (44100, 2, Some(16))
+        );
+
+        let dsf = info(include_bytes!("../testdata/sample.dsf")).unwrap();
+        assert_eq!(
+            (dsf.sample_rate(), dsf.channels(), dsf.bit_depth()),
+            (2_822_400, 2, Some(1))
+        );
+        assert_eq!(dsf.duration(), Some(Duration::from_nanos(11_609_977)));
+    }
+
+    #[test]
+    fn ogg() {
+        assert_eq!(
+            params(include_bytes!("../testdata/sample_48kbps.opus")),
+            (48000, 2, None)
+        );
+        assert_eq!(
+            params(include_bytes!("../testdata/sample.oga")),
+            (32000, 2, None)
+        );
+    }
+
+    #[test]
+    fn mp3() {
+        let mp3 = info(include_bytes!("../testdata/sample.mp3")).unwrap();
+        assert_eq!((mp3.sample_rate(), mp3.channels()), (44100, 2));
+        assert_eq!(mp3.duration(), None);
+
+        // 250 frames of 1152 samples
+        let vbr = info(include_bytes!("../testdata/sample_vbr.mp3")).unwrap();
+        assert_eq!(vbr.duration(), Some(Duration::from_nanos(6_530_612_244)));
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(info(b"ID3\x03\x00\x00\x00\x00\x00\x00").is_none());
+        assert!(info(b"\xFF\xF1\x50\x80").is_none());
+        assert!(info(&include_bytes!("../testdata/sample.wav")[..36]).is_none());
+    }
+}
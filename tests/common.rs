@@ -20,6 +20,18 @@ macro_rules! test_format {
                 assert_eq!(expected_kind, kind);
             }
 
+            #[cfg(feature = "mmap")]
+            #[test]
+            fn get_from_path_mmap() {
+                let expected_kind =
+                    Type::new(MatcherType::$exp_matchert, $exp_mimet, $exp_ext, matcher);
+                let kind = infer::get_from_path_mmap(concat!("testdata/", $file))
+                    .expect("test file read")
+                    .expect("test file matches");
+
+                assert_eq!(expected_kind, kind);
+            }
+
             #[test]
             fn get() {
                 let expected_kind =
@@ -0,0 +1,62 @@
+#![cfg(feature = "decompress")]
+
+macro_rules! test_compressed {
+    ($name:ident, $file:expr, $exp_ext:expr) => {
+        #[test]
+        fn $name() {
+            let buf = include_bytes!(concat!("../testdata/", $file));
+            let kind = infer::decompress::get(buf).expect("payload type is known");
+
+            assert_eq!(kind.payload().mime_type(), "application/x-tar");
+            assert_eq!(kind.extension(), $exp_ext);
+        }
+    };
+}
+
+test_compressed!(gz, "sample.tar.gz", "tar.gz");
+test_compressed!(xz, "sample.tar.xz", "tar.xz");
+test_compressed!(bz2, "sample.tar.bz2", "tar.bz2");
+test_compressed!(zst, "sample.tar.zst", "tar.zst.zst");
+test_compressed!(lz4, "sample.tar.lz4", "tar.zst.lz4");
+
+#[test]
+fn layers_outermost_first() {
+    let buf = include_bytes!("../testdata/sample.tar.lz4");
+    let kind = infer::decompress::get(buf).expect("payload type is known");
+    let layers: Vec<_> = kind.layers().iter().map(|t| t.extension()).collect();
+
+    assert_eq!(layers, ["lz4", "zst"]);
+}
+
+#[test]
+fn truncated_stream() {
+    let buf = include_bytes!("../testdata/sample.tar.xz");
+    let kind = infer::decompress::get(&buf[..buf.len() - 16]).expect("payload type is known");
+
+    assert_eq!(kind.extension(), "tar.xz");
+}
+
+#[test]
+fn limit() {
+    let buf = include_bytes!("../testdata/sample.tar.gz");
+
+    // the tar magic is at offset 257
+    assert!(infer::decompress::get_with_limit(buf, 256).is_none());
+    assert!(infer::decompress::get_with_limit(buf, 512).is_some());
+}
+
+#[test]
+fn not_compressed() {
+    let buf = include_bytes!("../testdata/sample.jpg");
+    assert!(infer::decompress::get(buf).is_none());
+}
+
+#[test]
+fn unknown_payload() {
+    // gzip stream of [0x00, 0x01, 0x02, 0x03]
+    let buf = [
+        0x1F, 0x8B, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x03, 0x63, 0x60, 0x64, 0x62, 0x06,
+        0x00, 0x13, 0x86, 0xB9, 0x8B, 0x04, 0x00, 0x00, 0x00,
+    ];
+    assert!(infer::decompress::get(&buf).is_none());
+}
@@ -1,6 +1,5 @@
 mod common;
 
-#[cfg(feature = "std")]
 macro_rules! test_format_get_only {
     ($exp_matchert:ident, $exp_mimet:expr, $exp_ext:expr, $format:ident, $file:expr) => {
         mod $format {
@@ -10,6 +9,18 @@ macro_rules! test_format_get_only {
                 false
             }
 
+            #[cfg(feature = "mmap")]
+            #[test]
+            fn get_from_path_mmap() {
+                let expected_kind =
+                    Type::new(MatcherType::$exp_matchert, $exp_mimet, $exp_ext, matcher);
+                let kind = infer::get_from_path_mmap(concat!("testdata/", $file))
+                    .expect("test file read")
+                    .expect("test file matches");
+
+                assert_eq!(expected_kind, kind);
+            }
+
             #[test]
             fn get() {
                 let expected_kind =
@@ -23,7 +34,6 @@ macro_rules! test_format_get_only {
     };
 }
 
-#[cfg(feature = "std")]
 test_format_get_only!(Doc, "application/msword", "doc", doc, "sample.doc");
 
 test_format!(
@@ -34,7 +44,6 @@ test_format!(
     "sample.docx"
 );
 
-#[cfg(feature = "std")]
 test_format_get_only!(Doc, "application/vnd.ms-excel", "xls", xls, "sample.xls");
 
 test_format!(
@@ -45,7 +54,6 @@ test_format!(
     "sample.xlsx"
 );
 
-#[cfg(feature = "std")]
 test_format_get_only!(
     Doc,
     "application/vnd.ms-powerpoint",
@@ -61,3 +69,47 @@ test_format!(
     pptx,
     "sample.pptx"
 );
+
+test_format!(
+    Doc,
+    "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+    "docx",
+    docx_reordered,
+    "sample_reordered.docx"
+);
+
+test_format!(
+    Doc,
+    "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+    "xlsx",
+    xlsx_zip64,
+    "sample_zip64.xlsx"
+);
+
+test_format!(Doc, "application/vnd.ms-outlook", "msg", msg, "sample.msg");
+
+test_format!(Doc, "application/vnd.visio", "vsd", vsd, "sample.vsd");
+
+test_format!(
+    Doc,
+    "application/vnd.ms-publisher",
+    "pub",
+    publisher,
+    "sample.pub"
+);
+
+test_format!(
+    Doc,
+    "application/encrypted",
+    "cfb",
+    encrypted_ooxml,
+    "sample_encrypted.docx"
+);
+
+#[test]
+fn ppt_prefix() {
+    // the directory of this compound file is contained in the first 8 KiB
+    let buf = include_bytes!("../testdata/sample.ppt");
+
+    assert!(infer::doc::is_ppt(&buf[..8192]));
+}
@@ -0,0 +1,208 @@
+mod common;
+
+test_format!(
+    Filesystem,
+    "application/x-ext2-image",
+    "ext2",
+    ext2,
+    "sample.ext2"
+);
+
+test_format!(
+    Filesystem,
+    "application/x-ext3-image",
+    "ext3",
+    ext3,
+    "sample.ext3"
+);
+
+test_format!(
+    Filesystem,
+    "application/x-ext4-image",
+    "ext4",
+    ext4,
+    "sample.ext4"
+);
+
+test_format!(
+    Filesystem,
+    "application/x-xfs-image",
+    "xfs",
+    xfs,
+    "sample.xfs"
+);
+
+test_format!(
+    Filesystem,
+    "application/x-btrfs-image",
+    "btrfs",
+    btrfs,
+    "sample.btrfs"
+);
+
+test_format!(
+    Filesystem,
+    "application/vnd.squashfs",
+    "squashfs",
+    squashfs,
+    "sample.squashfs"
+);
+
+test_format!(
+    Filesystem,
+    "application/x-erofs-image",
+    "erofs",
+    erofs,
+    "sample.erofs"
+);
+
+test_format!(
+    Filesystem,
+    "application/x-f2fs-image",
+    "f2fs",
+    f2fs,
+    "sample.f2fs"
+);
+
+test_format!(
+    Filesystem,
+    "application/x-exfat-image",
+    "exfat",
+    exfat,
+    "sample.exfat"
+);
+
+test_format!(
+    Filesystem,
+    "application/x-ntfs-image",
+    "ntfs",
+    ntfs,
+    "sample.ntfs"
+);
+
+test_format!(
+    Filesystem,
+    "application/x-fat12-image",
+    "fat12",
+    fat12,
+    "sample.fat12"
+);
+
+test_format!(
+    Filesystem,
+    "application/x-fat16-image",
+    "fat16",
+    fat16,
+    "sample.fat16"
+);
+
+test_format!(
+    Filesystem,
+    "application/x-fat32-image",
+    "fat32",
+    fat32,
+    "sample.fat32"
+);
+
+test_format!(
+    Filesystem,
+    "application/x-hfsplus-image",
+    "hfs",
+    hfs_plus,
+    "sample.hfs"
+);
+
+test_format!(
+    Filesystem,
+    "application/x-apfs-image",
+    "apfs",
+    apfs,
+    "sample.apfs"
+);
+
+test_format!(
+    Filesystem,
+    "application/x-zfs-image",
+    "zfs",
+    zfs,
+    "sample.zfs"
+);
+
+test_format!(
+    Filesystem,
+    "application/x-luks-image",
+    "luks",
+    luks,
+    "sample.luks"
+);
+
+mod superblock {
+    use infer::filesystem::{info, Kind};
+
+    #[test]
+    fn versions() {
+        let ext4 = info(include_bytes!("../testdata/sample.ext4")).unwrap();
+        assert_eq!(ext4.kind().name(), "ext4");
+        assert_eq!(ext4.version(), Some((1, 0)));
+
+        let xfs = info(include_bytes!("../testdata/sample.xfs")).unwrap();
+        assert_eq!(xfs.version(), Some((5, 0)));
+        assert_eq!(xfs.block_size(), Some(4096));
+
+        let f2fs = info(include_bytes!("../testdata/sample.f2fs")).unwrap();
+        assert_eq!(f2fs.version(), Some((1, 16)));
+
+        let exfat = info(include_bytes!("../testdata/sample.exfat")).unwrap();
+        assert_eq!(exfat.version(), Some((1, 0)));
+        assert_eq!(exfat.block_size(), Some(4096));
+
+        let zfs = info(include_bytes!("../testdata/sample.zfs")).unwrap();
+        assert_eq!(zfs.kind(), Kind::Zfs);
+        assert_eq!(zfs.version(), Some((5000, 0)));
+
+        let luks = info(include_bytes!("../testdata/sample.luks")).unwrap();
+        assert_eq!(luks.kind().name(), "crypto_LUKS");
+        assert_eq!(luks.version(), Some((1, 0)));
+    }
+
+    #[test]
+    fn ext_features() {
+        let mut buf = include_bytes!("../testdata/sample.ext3").to_vec();
+        assert_eq!(info(&buf).unwrap().kind(), Kind::Ext3);
+
+        // extents are not supported by ext3
+        buf[1024 + 0x60] |= 0x40;
+        assert_eq!(info(&buf).unwrap().kind(), Kind::Ext4);
+
+        // without the journal, and the magic alone is not enough
+        let mut buf = include_bytes!("../testdata/sample.ext2").to_vec();
+        assert_eq!(info(&buf).unwrap().kind(), Kind::Ext2);
+        buf[1024 + 0x18] = 0xFF;
+        assert!(info(&buf).is_none());
+    }
+
+    #[test]
+    fn fat_cluster_count() {
+        let fat12 = info(include_bytes!("../testdata/sample.fat12")).unwrap();
+        assert_eq!(fat12.kind(), Kind::Fat12);
+        assert_eq!(fat12.block_size(), Some(512));
+
+        let fat32 = info(include_bytes!("../testdata/sample.fat32")).unwrap();
+        assert_eq!(fat32.kind(), Kind::Fat32);
+        assert_eq!(fat32.kind().name(), "vfat");
+        assert_eq!(fat32.block_size(), Some(4096));
+
+        // NTFS and exFAT boot sectors have no FAT parameters
+        let ntfs = info(include_bytes!("../testdata/sample.ntfs")).unwrap();
+        assert_eq!(ntfs.kind(), Kind::Ntfs);
+        assert_eq!(ntfs.block_size(), Some(4096));
+    }
+
+    #[test]
+    fn superblock_beyond_prefix() {
+        let buf = include_bytes!("../testdata/sample.btrfs");
+        assert_eq!(info(buf).unwrap().kind(), Kind::Btrfs);
+        assert!(info(&buf[..8192]).is_none());
+        assert!(!infer::is_filesystem(&buf[..8192]));
+    }
+}
@@ -0,0 +1,143 @@
+#![cfg(feature = "http")]
+
+use http::{Request, Response};
+use infer::http::{sniff_headers, sniff_request, sniff_response};
+
+const PNG: &[u8] = include_bytes!("../testdata/sample.png");
+
+fn with_headers(headers: &[(&str, &str)]) -> Response<()> {
+    let mut builder = Response::builder();
+    for (name, value) in headers {
+        builder = builder.header(*name, *value);
+    }
+    builder.body(()).unwrap()
+}
+
+#[test]
+fn matching() {
+    let response = with_headers(&[("Content-Type", "image/png")]);
+    let sniffed = sniff_response(&response, PNG);
+    assert_eq!(sniffed.declared_content_type(), Some("image/png"));
+    assert_eq!(sniffed.effective_content_type(), "image/png");
+    assert!(!sniffed.is_no_sniff());
+    assert!(!sniffed.is_mismatch());
+}
+
+#[test]
+fn mismatch() {
+    let response = with_headers(&[("Content-Type", "text/html; charset=utf-8")]);
+    let sniffed = sniff_response(&response, PNG);
+    assert_eq!(sniffed.effective_content_type(), "text/html; charset=utf-8");
+    assert!(sniffed.is_mismatch());
+}
+
+#[test]
+fn no_sniff() {
+    let response = with_headers(&[
+        ("Content-Type", "image/jpeg"),
+        ("X-Content-Type-Options", "NoSniff, foo"),
+    ]);
+    let sniffed = sniff_response(&response, PNG);
+    assert!(sniffed.is_no_sniff());
+    assert_eq!(sniffed.effective_content_type(), "image/jpeg");
+    assert!(sniffed.is_mismatch());
+
+    // only the first header value is considered
+    let response = with_headers(&[
+        ("X-Content-Type-Options", "foo"),
+        ("X-Content-Type-Options", "nosniff"),
+    ]);
+    assert!(!sniff_response(&response, PNG).is_no_sniff());
+}
+
+#[test]
+fn missing_content_type() {
+    let request = Request::post("/upload").body(()).unwrap();
+    let sniffed = sniff_request(&request, PNG);
+    assert_eq!(sniffed.declared_content_type(), None);
+    assert_eq!(sniffed.effective_content_type(), "image/png");
+    assert_eq!(
+        sniffed.detected().map(|kind| kind.mime_type()),
+        Some("image/png")
+    );
+    assert!(!sniffed.is_mismatch());
+}
+
+#[test]
+fn compatible_types() {
+    let cases: &[(&str, &[u8])] = &[
+        ("application/octet-stream", PNG),
+        ("text/css", b"body { color: red }\n"),
+        (
+            "application/atom+xml",
+            include_bytes!("../testdata/sample.xml"),
+        ),
+        (
+            "application/ld+json",
+            br#"{"@context": "https://schema.org"}"#,
+        ),
+        ("IMAGE/PNG", PNG),
+        ("image/png", include_bytes!("../testdata/sample.apng")),
+        (
+            "image/webp",
+            include_bytes!("../testdata/sample_animated.webp"),
+        ),
+        (
+            "application/json",
+            include_bytes!("../testdata/sample.geojson"),
+        ),
+        ("text/plain", include_bytes!("../testdata/sample.csv")),
+        (
+            "application/javascript",
+            b"#!/usr/bin/env node\nconsole.log(1)\n",
+        ),
+        ("application/xml", include_bytes!("../testdata/sample.rss")),
+        (
+            "application/json",
+            include_bytes!("../testdata/sample.ndjson"),
+        ),
+        ("application/x-ndjson", br#"{"a": 1}"#),
+    ];
+    for &(content_type, body) in cases {
+        let response = with_headers(&[("Content-Type", content_type)]);
+        assert!(
+            !sniff_headers(response.headers(), body).is_mismatch(),
+            "{}",
+            content_type
+        );
+    }
+}
+
+#[test]
+fn aliases() {
+    let woff = b"wOFF\x00\x01\x00\x00";
+    let woff2 = b"wOF2\x00\x01\x00\x00";
+    let cases: &[(&str, &[u8])] = &[
+        ("audio/wav", include_bytes!("../testdata/sample.wav")),
+        ("audio/wave", include_bytes!("../testdata/sample.wav")),
+        ("audio/flac", include_bytes!("../testdata/sample.flac")),
+        ("font/woff", woff),
+        ("font/woff2", woff2),
+        ("font/ttf", include_bytes!("../testdata/sample.ttf")),
+        ("image/x-icon", include_bytes!("../testdata/sample.ico")),
+        ("video/avi", include_bytes!("../testdata/sample.avi")),
+        (
+            "application/x-gzip",
+            include_bytes!("../testdata/sample.tar.gz"),
+        ),
+        ("IMAGE/JPG", include_bytes!("../testdata/sample.jpg")),
+    ];
+    for &(content_type, body) in cases {
+        let response = with_headers(&[("Content-Type", content_type)]);
+        assert!(
+            !sniff_headers(response.headers(), body).is_mismatch(),
+            "{}",
+            content_type
+        );
+    }
+
+    // aliases of another type still mismatch
+    let response = with_headers(&[("Content-Type", "audio/x-flac")]);
+    let body = include_bytes!("../testdata/sample.wav");
+    assert!(sniff_headers(response.headers(), body).is_mismatch());
+}
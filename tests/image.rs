@@ -4,8 +4,36 @@ test_format!(Image, "image/jpeg", "jpg", jpg, "sample.jpg");
 
 test_format!(Image, "image/png", "png", png, "sample.png");
 
+test_format!(Image, "image/apng", "apng", apng, "sample.apng");
+
 test_format!(Image, "image/gif", "gif", gif, "sample.gif");
 
+test_format!(
+    Image,
+    "image/x-animated-gif",
+    "gif",
+    animated_gif,
+    "sample_animated.gif"
+);
+
+test_format!(Image, "image/webp", "webp", webp, "sample.webp");
+
+test_format!(
+    Image,
+    "image/webp",
+    "webp",
+    webp_lossless,
+    "sample_lossless.webp"
+);
+
+test_format!(
+    Image,
+    "image/x-animated-webp",
+    "webp",
+    animated_webp,
+    "sample_animated.webp"
+);
+
 test_format!(Image, "image/tiff", "tif", tif, "sample.tif");
 
 test_format!(Image, "image/tiff", "tif", tif2, "sample2.tif");
@@ -16,6 +44,14 @@ test_format!(Image, "image/tiff", "tif", tif4, "sample4.tif");
 
 test_format!(Image, "image/tiff", "tif", tif5, "sample5.tif");
 
+test_format!(
+    Image,
+    "image/tiff",
+    "tif",
+    tif_multipage,
+    "sample_multipage.tiff"
+);
+
 test_format!(Image, "image/bmp", "bmp", bmp, "sample.bmp");
 
 test_format!(Image, "image/vnd.adobe.photoshop", "psd", psd, "sample.psd");
@@ -37,3 +73,172 @@ test_format!(Image, "image/vnd.djvu", "djvu", djvu1, "sample_single.djvu");
 test_format!(Image, "image/vnd.djvu", "djvu", djvu2, "sample_multi.djvu");
 
 test_format!(Image, "image/vnd.dwg", "dwg", dwg, "sample.dwg");
+
+test_format!(Image, "image/jp2", "jp2", jp2, "sample.jp2");
+
+test_format!(Image, "image/jpx", "jpx", jpx, "sample.jpx");
+
+test_format!(Image, "image/jpm", "jpm", jpm, "sample.jpm");
+
+test_format!(Image, "image/x-canon-cr3", "cr3", cr3, "sample.cr3");
+
+test_format!(Image, "image/heif-sequence", "heifs", heifs, "sample.heifs");
+
+test_format!(Image, "image/heic-sequence", "heics", heics, "sample.heics");
+
+test_format!(Image, "image/avif-sequence", "avifs", avifs, "sample.avifs");
+
+test_format!(
+    Image,
+    "application/x-navi-animation",
+    "ani",
+    ani,
+    "sample.ani"
+);
+
+test_format!(
+    Image,
+    "application/vnd.corel-draw",
+    "cdr",
+    cdr,
+    "sample.cdr"
+);
+
+test_format!(Image, "image/svg+xml", "svg", svg, "sample.svg");
+
+mod dimensions {
+    use infer::image::{info, ColorType};
+
+    fn size(buf: &[u8]) -> (u32, u32) {
+        let image = info(buf).unwrap();
+        (image.width(), image.height())
+    }
+
+    #[test]
+    fn samples() {
+        for buf in [
+            &include_bytes!("../testdata/sample.png")[..],
+            include_bytes!("../testdata/sample.jpg"),
+            include_bytes!("../testdata/sample.gif"),
+            include_bytes!("../testdata/sample.bmp"),
+            include_bytes!("../testdata/sample.psd"),
+            include_bytes!("../testdata/sample.tif"),
+            include_bytes!("../testdata/sample4.tif"),
+            include_bytes!("../testdata/sample.avif"),
+        ] {
+            let image = info(buf).unwrap();
+            assert_eq!((image.width(), image.height()), (256, 170));
+            assert_eq!(image.bit_depth(), Some(8));
+        }
+    }
+
+    #[test]
+    fn png() {
+        let image = info(include_bytes!("../testdata/sample.png")).unwrap();
+        assert_eq!(image.color_type(), Some(ColorType::Rgb));
+        assert_eq!(image.frames(), Some(1));
+    }
+
+    #[test]
+    fn gif() {
+        let image = info(include_bytes!("../testdata/sample.gif")).unwrap();
+        assert_eq!(image.color_type(), Some(ColorType::Indexed));
+        assert_eq!(image.frames(), Some(1));
+
+        let buf = include_bytes!("../testdata/sample.gif");
+        assert_eq!(info(&buf[..1000]).unwrap().frames(), None);
+    }
+
+    #[test]
+    fn webp() {
+        assert_eq!(size(include_bytes!("../testdata/sample.webp")), (64, 48));
+
+        let image = info(include_bytes!("../testdata/sample_lossless.webp")).unwrap();
+        assert_eq!((image.width(), image.height()), (32, 20));
+        assert_eq!(image.color_type(), Some(ColorType::Rgba));
+
+        let image = info(include_bytes!("../testdata/sample_animated.webp")).unwrap();
+        assert_eq!((image.width(), image.height()), (40, 30));
+        assert_eq!(image.frames(), Some(2));
+    }
+
+    #[test]
+    fn animations() {
+        let image = info(include_bytes!("../testdata/sample.apng")).unwrap();
+        assert_eq!((image.width(), image.height()), (2, 2));
+        assert_eq!(image.frames(), Some(2));
+
+        let image = info(include_bytes!("../testdata/sample_animated.gif")).unwrap();
+        assert_eq!(image.frames(), Some(2));
+    }
+
+    #[test]
+    fn animation_cut_off() {
+        // the second image and the trailer are missing
+        let buf = include_bytes!("../testdata/sample_animated.gif");
+        assert!(infer::image::is_animated_gif(&buf[..50]));
+        assert_eq!(info(&buf[..50]).unwrap().frames(), None);
+        assert!(!infer::image::is_animated_gif(&buf[..30]));
+
+        // the IHDR chunk alone does not tell
+        let buf = include_bytes!("../testdata/sample.apng");
+        assert!(!infer::image::is_apng(&buf[..33]));
+        assert!(infer::image::is_apng(&buf[..45]));
+    }
+
+    #[test]
+    fn ico() {
+        let image = info(include_bytes!("../testdata/sample.ico")).unwrap();
+        assert_eq!((image.width(), image.height()), (256, 256));
+        assert_eq!(image.frames(), Some(1));
+    }
+
+    #[test]
+    fn tiff_pages() {
+        let buf = include_bytes!("../testdata/sample_multipage.tiff");
+        let image = info(buf).unwrap();
+        assert_eq!((image.width(), image.height()), (4, 2));
+        assert_eq!(image.color_type(), Some(ColorType::Gray));
+        assert_eq!(image.frames(), Some(2));
+
+        assert_eq!(info(&buf[..130]).unwrap().frames(), None);
+    }
+
+    #[test]
+    fn heif_primary_item() {
+        // the thumbnail comes first
+        let image = info(include_bytes!("../testdata/sample2.heic")).unwrap();
+        assert_eq!((image.width(), image.height()), (1600, 739));
+        assert_eq!(image.bit_depth(), Some(10));
+    }
+
+    #[test]
+    fn jxl() {
+        assert_eq!(
+            size(include_bytes!("../testdata/spline_on_first_frame.jxl")),
+            (32, 32)
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(info(b"\x89PNG\r\n\x1a\n").is_none());
+        assert!(info(b"\xFF\xD8\xFF\xDA").is_none());
+        assert!(info(include_bytes!("../testdata/sample.svg")).is_none());
+    }
+
+    #[test]
+    fn box_length_overflow() {
+        let ftyp = b"\0\0\0\x14ftypavif\0\0\0\0mif1";
+        // `meta` box with the largest 64-bit size
+        let mut buf = ftyp.to_vec();
+        buf.extend_from_slice(b"\0\0\0\x01meta\xFF\xFF\xFF\xFF\xFF\xFF\xFF\xFF\0\0\0\0");
+        assert!(info(&buf).is_none());
+
+        // the same for a property
+        let mut buf = ftyp.to_vec();
+        buf.extend_from_slice(b"\0\0\0\x2Cmeta\0\0\0\0\0\0\0\x20iprp\0\0\0\x18ipco");
+        buf.extend_from_slice(b"\0\0\0\x01ispe\xFF\xFF\xFF\xFF\xFF\xFF\xFF\xFF");
+        assert!(info(&buf).is_none());
+    }
+}
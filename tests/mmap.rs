@@ -0,0 +1,61 @@
+#![cfg(feature = "mmap")]
+
+use std::path::PathBuf;
+
+/// Returns a path in the temporary directory that is unique to this process and test.
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("infer-mmap-{}-{}", std::process::id(), name))
+}
+
+#[test]
+fn mmap_empty_file() {
+    let path = temp_path("empty");
+    std::fs::write(&path, b"").expect("temp file written");
+    let kind = infer::get_from_path_mmap(&path).expect("empty file read");
+    std::fs::remove_file(&path).expect("temp file removed");
+
+    assert!(kind.is_none());
+}
+
+#[cfg(unix)]
+#[test]
+fn mmap_special_file_fallback() {
+    let kind = infer::get_from_path_mmap("/dev/null").expect("special file read");
+    assert!(kind.is_none());
+}
+
+#[test]
+fn mmap_missing_file() {
+    assert!(infer::get_from_path_mmap("testdata/does-not-exist").is_err());
+}
+
+#[test]
+fn mmap_trailer() {
+    // the koly trailer of the disk image is in its last 512 bytes
+    let kind = infer::get_from_path_mmap("testdata/sample.dmg")
+        .expect("test file read")
+        .expect("test file matches");
+    assert_eq!(kind.mime_type(), "application/x-apple-diskimage");
+
+    let kind = infer::get_from_path_mmap("testdata/sample.iso")
+        .expect("test file read")
+        .expect("test file matches");
+    assert_eq!(kind.mime_type(), "application/x-iso9660-image");
+}
+
+#[test]
+fn mmap_beyond_prefix() {
+    // the directory of these compound files is past their first 8 KiB
+    for (file, mime) in [
+        ("testdata/sample.doc", "application/msword"),
+        ("testdata/sample.xls", "application/vnd.ms-excel"),
+    ] {
+        let kind = infer::get_from_path(file).expect("test file read");
+        assert_ne!(kind.map(|kind| kind.mime_type()), Some(mime));
+
+        let kind = infer::get_from_path_mmap(file)
+            .expect("test file read")
+            .expect("test file matches");
+        assert_eq!(kind.mime_type(), mime);
+    }
+}
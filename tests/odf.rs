@@ -23,3 +23,35 @@ test_format!(
     odp,
     "sample.odp"
 );
+
+test_format!(
+    Doc,
+    "application/vnd.oasis.opendocument.text",
+    "fodt",
+    fodt,
+    "sample.fodt"
+);
+
+test_format!(
+    Doc,
+    "application/vnd.oasis.opendocument.spreadsheet",
+    "fods",
+    fods,
+    "sample.fods"
+);
+
+test_format!(
+    Doc,
+    "application/vnd.oasis.opendocument.presentation",
+    "fodp",
+    fodp,
+    "sample.fodp"
+);
+
+test_format!(
+    Doc,
+    "application/vnd.oasis.opendocument.graphics",
+    "fodg",
+    fodg,
+    "sample.fodg"
+);
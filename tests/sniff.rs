@@ -0,0 +1,155 @@
+use infer::sniff::{identify_unknown, sniff, sniff_in_context, Context};
+
+#[test]
+fn unknown() {
+    let samples: &[(&[u8], &str)] = &[
+        (include_bytes!("../testdata/sample.png"), "image/png"),
+        (include_bytes!("../testdata/sample.gif"), "image/gif"),
+        (include_bytes!("../testdata/sample.jpg"), "image/jpeg"),
+        (include_bytes!("../testdata/sample.bmp"), "image/bmp"),
+        (include_bytes!("../testdata/sample.ico"), "image/x-icon"),
+        (include_bytes!("../testdata/sample.mp3"), "audio/mpeg"),
+        (include_bytes!("../testdata/sample.mp4"), "video/mp4"),
+        (include_bytes!("../testdata/sample.webm"), "video/webm"),
+        (include_bytes!("../testdata/sample.avi"), "video/avi"),
+        (
+            include_bytes!("../testdata/sample.tar.gz"),
+            "application/x-gzip",
+        ),
+        (include_bytes!("../testdata/sample.docx"), "application/zip"),
+        (include_bytes!("../testdata/sample.pdf"), "application/pdf"),
+        (include_bytes!("../testdata/sample.html"), "text/html"),
+        (include_bytes!("../testdata/sample.xml"), "text/xml"),
+        (include_bytes!("../testdata/sample.txt"), "text/plain"),
+        (
+            include_bytes!("../testdata/sample_utf16le.txt"),
+            "text/plain",
+        ),
+        (
+            include_bytes!("../testdata/sample.wasm"),
+            "application/octet-stream",
+        ),
+    ];
+    for &(buf, mime) in samples {
+        assert_eq!(sniff(buf, None, false), mime);
+        assert_eq!(sniff(buf, Some("application/unknown"), false), mime);
+    }
+}
+
+#[test]
+fn html() {
+    assert_eq!(identify_unknown(b"  \n<!DOCTYPE html>", true), "text/html");
+    assert_eq!(identify_unknown(b"<BoDy class=x>", true), "text/html");
+    assert_eq!(identify_unknown(b"<!-- comment -->", true), "text/html");
+    assert_eq!(identify_unknown(b"<bodyx>", true), "text/plain");
+    assert_eq!(identify_unknown(b"<html>", false), "text/plain");
+    assert_eq!(sniff(b"<html>", None, true), "text/plain");
+    assert_eq!(sniff(b"%PDF-1.7\n", None, true), "text/plain");
+}
+
+#[test]
+fn no_sniff() {
+    let buf = include_bytes!("../testdata/sample.png");
+    assert_eq!(sniff(buf, Some("image/gif"), true), "image/gif");
+    assert_eq!(sniff(buf, Some("text/plain"), true), "text/plain");
+    assert_eq!(sniff(buf, Some("*/*"), true), "image/png");
+}
+
+#[test]
+fn apache_bug() {
+    let buf = include_bytes!("../testdata/sample.png");
+    assert_eq!(
+        sniff(buf, Some("text/plain; charset=UTF-8"), false),
+        "application/octet-stream"
+    );
+    assert_eq!(sniff(b"hello", Some("text/plain"), false), "text/plain");
+    // byte order marks are checked before binary data
+    assert_eq!(
+        sniff(b"\xFF\xFEh\x00i\x00", Some("text/plain"), false),
+        "text/plain"
+    );
+    assert_eq!(
+        sniff(b"\xFE\xFF\x00h\x00i", Some("text/plain"), false),
+        "text/plain"
+    );
+    assert_eq!(
+        sniff(b"\x00\xFF\xFEh", Some("text/plain"), false),
+        "application/octet-stream"
+    );
+    // only the exact header values trigger the check
+    assert_eq!(
+        sniff(buf, Some("text/plain;charset=utf-8"), false),
+        "text/plain;charset=utf-8"
+    );
+}
+
+#[test]
+fn supplied() {
+    let png = include_bytes!("../testdata/sample.png");
+    assert_eq!(sniff(png, Some("image/jpeg"), false), "image/png");
+    assert_eq!(
+        sniff(b"not an image", Some("image/jpeg"), false),
+        "image/jpeg"
+    );
+    assert_eq!(
+        sniff(png, Some("application/json"), false),
+        "application/json"
+    );
+    assert_eq!(sniff(png, Some("image/svg+xml"), false), "image/svg+xml");
+
+    let mp3 = include_bytes!("../testdata/sample.mp3");
+    assert_eq!(sniff(mp3, Some("video/mp4"), false), "audio/mpeg");
+    assert_eq!(sniff(mp3, Some("application/ogg"), false), "audio/mpeg");
+}
+
+#[test]
+fn feeds() {
+    let rss = include_bytes!("../testdata/sample.rss");
+    assert_eq!(sniff(rss, Some("text/html"), false), "application/rss+xml");
+    let atom = include_bytes!("../testdata/sample.atom");
+    assert_eq!(
+        sniff(atom, Some("text/html; charset=utf-8"), false),
+        "application/atom+xml"
+    );
+
+    let rdf = br#"<?xml version="1.0"?>
+<!-- RSS 1.0 -->
+<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#" xmlns="http://purl.org/rss/1.0/">"#;
+    assert_eq!(sniff(rdf, Some("text/html"), false), "application/rss+xml");
+
+    let html = include_bytes!("../testdata/sample.html");
+    assert_eq!(sniff(html, Some("text/html"), false), "text/html");
+}
+
+#[test]
+fn contexts() {
+    let png = include_bytes!("../testdata/sample.png");
+    assert_eq!(
+        sniff_in_context(Context::Image, png, None),
+        Some("image/png")
+    );
+    assert_eq!(
+        sniff_in_context(Context::Image, b"text", Some("image/png")),
+        Some("image/png")
+    );
+    assert_eq!(sniff_in_context(Context::Image, b"text", None), None);
+    assert_eq!(
+        sniff_in_context(Context::Image, png, Some("image/svg+xml")),
+        Some("image/svg+xml")
+    );
+
+    let webm = include_bytes!("../testdata/sample.webm");
+    assert_eq!(
+        sniff_in_context(Context::AudioVideo, webm, Some("video/mp4")),
+        Some("video/webm")
+    );
+
+    let ttf = include_bytes!("../testdata/sample.ttf");
+    assert_eq!(sniff_in_context(Context::Font, ttf, None), Some("font/ttf"));
+
+    assert_eq!(
+        sniff_in_context(Context::Style, b"body { color: red }", Some("text/plain")),
+        Some("text/plain")
+    );
+    assert_eq!(sniff_in_context(Context::Style, png, None), None);
+}
@@ -6,4 +6,429 @@ test_format!(Text, "text/xml", "xml", xml, "sample.xml");
 
 test_format!(Text, "text/xml", "xml", xml2, "sample2.xml");
 
+test_format!(Text, "application/rss+xml", "rss", rss, "sample.rss");
+
+test_format!(Text, "application/atom+xml", "atom", atom, "sample.atom");
+
+test_format!(
+    Text,
+    "application/vnd.google-earth.kml+xml",
+    "kml",
+    kml,
+    "sample.kml"
+);
+
+test_format!(Text, "application/gpx+xml", "gpx", gpx, "sample.gpx");
+
+test_format!(
+    Text,
+    "application/xhtml+xml",
+    "xhtml",
+    xhtml,
+    "sample.xhtml"
+);
+
+test_format!(Text, "application/xslt+xml", "xsl", xsl, "sample.xsl");
+
+test_format!(Text, "application/x-plist", "plist", plist, "sample.plist");
+
+test_format!(
+    Text,
+    "application/vnd.recordare.musicxml+xml",
+    "musicxml",
+    musicxml,
+    "sample.musicxml"
+);
+
+test_format!(Text, "application/xliff+xml", "xlf", xlf, "sample.xlf");
+
+test_format!(Text, "application/docbook+xml", "dbk", dbk, "sample.dbk");
+
+test_format!(
+    Text,
+    "application/docbook+xml",
+    "dbk",
+    dbk_doctype,
+    "sample_docbook4.xml"
+);
+
+test_format!(
+    Text,
+    "application/vnd.android.resource+xml",
+    "xml",
+    android_xml,
+    "sample_android.xml"
+);
+
+test_format!(Text, "text/x-maven+xml", "pom", pom, "sample.pom");
+
+test_format!(Text, "application/json", "json", json, "sample.json");
+
+test_format!(
+    Text,
+    "application/x-ndjson",
+    "ndjson",
+    ndjson,
+    "sample.ndjson"
+);
+
+test_format!(
+    Text,
+    "application/geo+json",
+    "geojson",
+    geojson,
+    "sample.geojson"
+);
+
+test_format!(
+    Text,
+    "application/topo+json",
+    "topojson",
+    topojson,
+    "sample.topojson"
+);
+
+test_format!(
+    Text,
+    "application/x-ipynb+json",
+    "ipynb",
+    ipynb,
+    "sample.ipynb"
+);
+
+test_format!(Text, "model/gltf+json", "gltf", gltf, "sample.gltf");
+
+test_format!(
+    Text,
+    "application/schema+json",
+    "json",
+    json_schema,
+    "sample.schema.json"
+);
+
+test_format!(
+    Text,
+    "application/manifest+json",
+    "webmanifest",
+    webmanifest,
+    "sample.webmanifest"
+);
+
+test_format!(Text, "application/json", "map", source_map, "sample.js.map");
+
+test_format!(Text, "text/csv", "csv", csv, "sample.csv");
+
+test_format!(
+    Text,
+    "text/csv",
+    "csv",
+    csv_semicolon,
+    "sample_semicolon.csv"
+);
+
+test_format!(Text, "text/csv", "csv", csv_pipe, "sample_pipe.csv");
+
+test_format!(Text, "text/tab-separated-values", "tsv", tsv, "sample.tsv");
+
 test_format!(Text, "text/x-shellscript", "sh", sh, "sample.sh");
+
+test_format!(Text, "text/x-python", "py", py, "sample.py");
+
+test_format!(Text, "text/javascript", "js", js, "sample.js");
+
+test_format!(Text, "text/x-perl", "pl", pl, "sample.pl");
+
+test_format!(Text, "application/x-ruby", "rb", rb, "sample.rb");
+
+test_format!(Text, "application/x-php", "php", php, "sample.php");
+
+test_format!(Text, "text/x-lua", "lua", lua, "sample.lua");
+
+test_format!(Text, "application/x-awk", "awk", awk, "sample.awk");
+
+test_format!(Text, "text/plain", "txt", txt, "sample.txt");
+
+test_format!(Text, "text/plain", "txt", txt_utf16le, "sample_utf16le.txt");
+
+test_format!(Text, "text/plain", "txt", txt_utf16be, "sample_utf16be.txt");
+
+test_format!(Text, "text/plain", "txt", txt_cp1252, "sample_cp1252.txt");
+
+mod charset {
+    use infer::text::{charset, Charset};
+
+    #[test]
+    fn samples() {
+        let samples: &[(&[u8], Charset)] = &[
+            (include_bytes!("../testdata/sample.txt"), Charset::Utf8),
+            (include_bytes!("../testdata/sample.sh"), Charset::Ascii),
+            (
+                include_bytes!("../testdata/sample_utf16le.txt"),
+                Charset::Utf16Le,
+            ),
+            (
+                include_bytes!("../testdata/sample_utf16be.txt"),
+                Charset::Utf16Be,
+            ),
+            (
+                include_bytes!("../testdata/sample_cp1252.txt"),
+                Charset::Windows1252,
+            ),
+        ];
+
+        for (buf, expected) in samples {
+            assert_eq!(charset(buf), Some(*expected));
+        }
+    }
+
+    #[test]
+    fn binary() {
+        assert_eq!(charset(include_bytes!("../testdata/sample.jpg")), None);
+        assert_eq!(charset(include_bytes!("../testdata/sample.exe")), None);
+        assert_eq!(charset(b""), None);
+    }
+
+    #[test]
+    fn bom_only() {
+        assert_eq!(charset(b"\xFF\xFE"), None);
+        assert_eq!(charset(b"\xFE\xFF"), None);
+        assert_eq!(charset(b"\xEF\xBB\xBF"), None);
+        assert_eq!(charset(b"\xFF\xFEh\x00"), Some(Charset::Utf16Le));
+        assert!(!infer::text::is_plain_text(b"\xFF\xFE"));
+    }
+
+    #[test]
+    fn bounded() {
+        // only the first 8 KiB are inspected
+        let mut buf = b"plain text\n".repeat(1000);
+        buf.extend_from_slice(&[0x00, 0x01, 0x02]);
+        assert_eq!(charset(&buf), Some(Charset::Ascii));
+    }
+
+    #[test]
+    fn truncated_utf8() {
+        // the buffer ends in the middle of a multi-byte sequence
+        assert_eq!(charset(&"caf\u{e9}".as_bytes()[..4]), Some(Charset::Utf8));
+        assert_eq!(charset(b"caf\xC3\x28"), Some(Charset::Iso8859_1));
+    }
+}
+
+#[test]
+fn html5_with_xhtml_namespace() {
+    let buf = b"<!DOCTYPE html>\n<html xmlns=\"http://www.w3.org/1999/xhtml\" lang=\"en\">\n<head>";
+    assert_eq!(infer::get(buf).unwrap().mime_type(), "text/html");
+    assert!(!infer::text::is_xhtml(buf));
+
+    // with an XML declaration it is XHTML served as XML
+    let buf =
+        b"<?xml version=\"1.0\"?>\n<!DOCTYPE html>\n<html xmlns=\"http://www.w3.org/1999/xhtml\">";
+    assert_eq!(
+        infer::get(buf).unwrap().mime_type(),
+        "application/xhtml+xml"
+    );
+}
+
+mod root {
+    use infer::xml::root;
+
+    #[test]
+    fn prolog() {
+        let root = root(include_bytes!("../testdata/sample.plist")).unwrap();
+        assert!(root.has_declaration());
+        assert_eq!(root.name(), b"plist");
+        assert_eq!(root.attribute(b"version"), Some(&b"1.0"[..]));
+        assert!(root.doctype().unwrap().starts_with(b"plist PUBLIC"));
+    }
+
+    #[test]
+    fn prefixed() {
+        let root = root(include_bytes!("../testdata/sample.xsl")).unwrap();
+        assert_eq!(root.prefix(), Some(&b"xsl"[..]));
+        assert_eq!(root.local_name(), b"stylesheet");
+        assert_eq!(
+            root.namespace(),
+            Some(&b"http://www.w3.org/1999/XSL/Transform"[..])
+        );
+    }
+
+    #[test]
+    fn internal_subset() {
+        let root = root(include_bytes!("../testdata/sample_docbook4.xml")).unwrap();
+        assert_eq!(root.name(), b"article");
+    }
+
+    #[test]
+    fn not_markup() {
+        assert!(root(b"").is_none());
+        assert!(root(b"plain text").is_none());
+        assert!(root(b"<?xml version=\"1.0\"?><!-- unterminated").is_none());
+        assert!(root(b"<1abc>").is_none());
+    }
+}
+
+mod validator {
+    #[test]
+    fn truncated() {
+        let buf = include_bytes!("../testdata/sample.json");
+        for len in 20..buf.len() {
+            assert!(infer::text::is_json(&buf[..len]), "cut off at {}", len);
+        }
+
+        let buf = include_bytes!("../testdata/sample.ipynb");
+        let kind = infer::get(&buf[..40]).unwrap();
+        assert_eq!(kind.extension(), "ipynb");
+    }
+
+    #[test]
+    fn invalid() {
+        let samples: &[&[u8]] = &[
+            b"",
+            b"{",
+            b"[1, 2] trailing",
+            b"{\"key\": value}",
+            b"{'key': 1}",
+            b"[01]",
+            b"[1.]",
+            b"[\"\\x\"]",
+            b"[\"tab\there\"]",
+            b"{\"a\": 1,}",
+            b"\"string\"",
+            b"42",
+        ];
+        for buf in samples {
+            assert!(!infer::text::is_json(buf), "{:?}", buf);
+        }
+    }
+
+    #[test]
+    fn nesting() {
+        let mut buf = [b'['; 200];
+        buf[198..].copy_from_slice(b"1]");
+        assert!(!infer::text::is_json(&buf));
+        assert!(infer::text::is_json(&buf[134..]));
+    }
+
+    #[test]
+    fn bounded() {
+        // only the first 8 KiB are inspected, so what follows does not matter
+        let mut buf = b"{\"type\": \"FeatureCollection\", \"features\": [".to_vec();
+        while buf.len() < 8192 {
+            buf.extend_from_slice(b"{\"type\": \"Feature\"}, ");
+        }
+        buf.extend_from_slice(b"not json");
+        assert!(infer::text::is_json(&buf));
+        assert!(infer::text::is_geojson(&buf));
+
+        let mut buf = b"{\"id\": 1}\n".repeat(1000);
+        buf.extend_from_slice(b"not json\n");
+        assert!(infer::text::is_ndjson(&buf));
+    }
+
+    #[test]
+    fn single_line_is_not_ndjson() {
+        assert!(!infer::text::is_ndjson(b"{\"id\": 1}\n"));
+        assert!(!infer::text::is_ndjson(b"{\"id\":\n1}\n{\"id\": 2}\n"));
+    }
+}
+
+mod csv_dialect {
+    use infer::text::csv_dialect;
+
+    #[test]
+    fn samples() {
+        let samples: &[(&[u8], u8, Option<u8>)] = &[
+            (include_bytes!("../testdata/sample.csv"), b',', Some(b'"')),
+            (include_bytes!("../testdata/sample.tsv"), b'\t', None),
+            (
+                include_bytes!("../testdata/sample_semicolon.csv"),
+                b';',
+                None,
+            ),
+            (include_bytes!("../testdata/sample_pipe.csv"), b'|', None),
+        ];
+        for &(buf, delimiter, quote) in samples {
+            let dialect = csv_dialect(buf).unwrap();
+            assert_eq!(dialect.delimiter(), delimiter);
+            assert_eq!(dialect.quote(), quote);
+            assert!(dialect.has_header());
+        }
+    }
+
+    #[test]
+    fn no_header() {
+        let dialect = csv_dialect(b"1,2,3\n4,5,6\n").unwrap();
+        assert!(!dialect.has_header());
+
+        let dialect = csv_dialect(b"alice,paris\nbob,lyon\n").unwrap();
+        assert!(!dialect.has_header());
+    }
+
+    #[test]
+    fn truncated() {
+        let buf = include_bytes!("../testdata/sample.csv");
+        for len in 60..buf.len() {
+            assert!(csv_dialect(&buf[..len]).is_some(), "cut off at {}", len);
+        }
+    }
+
+    #[test]
+    fn inconsistent() {
+        assert!(csv_dialect(b"a,b,c\n1,2\n3,4,5\n").is_none());
+        assert!(csv_dialect(b"a,b\n\"1\"x,2\n").is_none());
+        assert!(csv_dialect(b"single line, no newline").is_none());
+        assert!(csv_dialect(include_bytes!("../testdata/sample.txt")).is_none());
+    }
+
+    #[test]
+    fn prose() {
+        let buf = b"Hello, world.\nGoodbye, friend.\n";
+        assert!(csv_dialect(buf).is_none());
+        assert_eq!(infer::get(buf).unwrap().mime_type(), "text/plain");
+        assert!(
+            csv_dialect(b"Yes, I know, really.\nNo, you don't, sadly.\nOk, fine, then.\n")
+                .is_none()
+        );
+
+        // numbers and quoted fields may have spaces
+        assert!(csv_dialect(b"1, 2, 3\n4, 5, 6\n").is_some());
+        assert!(csv_dialect(b"\"Smith, John\",42\n\"Doe, Jane\",37\n").is_some());
+    }
+}
+
+mod interpreter {
+    use infer::text::interpreter;
+
+    #[test]
+    fn env() {
+        let samples: &[(&[u8], &[u8])] = &[
+            (b"#!/usr/bin/env python3\n", b"python"),
+            (b"#!/usr/bin/env -S node --experimental-modules\n", b"node"),
+            (b"#!/usr/bin/env -Sruby -w\n", b"ruby"),
+            (b"#!/usr/bin/env -i PATH=/bin perl\n", b"perl"),
+            (b"#!/usr/bin/env -u HOME -- lua\n", b"lua"),
+        ];
+        for &(buf, name) in samples {
+            assert_eq!(interpreter(buf), Some(name));
+        }
+    }
+
+    #[test]
+    fn unknown() {
+        assert_eq!(interpreter(b"#!/usr/bin/env\n"), None);
+        assert_eq!(interpreter(b"#!\n/bin/sh\n"), None);
+    }
+
+    #[test]
+    fn shell_fallback() {
+        for buf in [
+            &b"#!/usr/bin/fish\necho hi\n"[..],
+            b"#!/bin/busybox sh\necho hi\n",
+            b"#!/usr/bin/tclsh\nputs hi\n",
+            b"#!\n/bin/sh\n",
+        ] {
+            assert!(infer::text::is_shellscript(buf));
+            assert_eq!(infer::get(buf).unwrap().mime_type(), "text/x-shellscript");
+        }
+        assert!(!infer::text::is_shellscript(b"#!/usr/bin/env python3\n"));
+    }
+}
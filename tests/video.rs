@@ -12,3 +12,13 @@ test_format!(Video, "video/quicktime", "mov", mov2, "sample2.mov");
 test_format!(Video, "video/x-msvideo", "avi", avi, "sample.avi");
 
 test_format!(Video, "video/x-flv", "flv", flv, "sample.flv");
+
+test_format!(Video, "video/3gpp", "3gp", three_gp, "sample.3gp");
+
+test_format!(Video, "video/3gpp2", "3g2", three_g2, "sample.3g2");
+
+test_format!(Video, "video/x-f4v", "f4v", f4v, "sample.f4v");
+
+test_format!(Video, "video/iso.segment", "m4s", m4s, "sample.m4s");
+
+test_format!(Video, "video/mj2", "mj2", mj2, "sample.mj2");
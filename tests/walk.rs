@@ -0,0 +1,74 @@
+#![cfg(feature = "walk")]
+
+use infer::walk::{Symlinks, Walk};
+use infer::{MatcherType, Type};
+
+fn matcher(_buf: &[u8]) -> bool {
+    false
+}
+
+#[test]
+fn walk_include() {
+    let mut entries = Walk::new("testdata")
+        .threads(4)
+        .include("*.tif")
+        .expect("valid glob")
+        .run()
+        .expect("valid glob set");
+
+    let mut paths = Vec::new();
+    for (path, kind) in &mut entries {
+        let kind = kind.expect("test file read").expect("test file matches");
+        assert_eq!(kind.mime_type(), "image/tiff");
+        paths.push(path);
+    }
+    paths.sort();
+
+    assert_eq!(paths.len(), 5);
+    assert!(paths[0].ends_with("sample.tif"));
+
+    let tiff = Type::new(MatcherType::Image, "image/tiff", "tif", matcher);
+    let counters = entries.counters();
+    assert_eq!(counters.files(), 5);
+    assert_eq!(counters.count(&tiff), 5);
+    assert_eq!(counters.unknown(), 0);
+    assert_eq!(counters.errors(), 0);
+    assert_eq!(counters.types().len(), 1);
+}
+
+#[test]
+fn walk_exclude() {
+    let entries = Walk::new("testdata")
+        .symlinks(Symlinks::Follow)
+        .exclude("*.tif")
+        .expect("valid glob")
+        .exclude("sample_mach_*")
+        .expect("valid glob")
+        .run()
+        .expect("valid glob set");
+
+    let paths: Vec<_> = entries.map(|(path, _)| path).collect();
+    assert!(!paths.is_empty());
+    assert!(paths.iter().all(|p| {
+        let name = p.file_name().unwrap().to_str().unwrap();
+        !name.ends_with(".tif") && !name.starts_with("sample_mach_")
+    }));
+}
+
+#[test]
+fn walk_missing_root() {
+    let mut entries = Walk::new("testdata/does-not-exist")
+        .threads(1)
+        .run()
+        .expect("valid glob set");
+
+    let (_, kind) = entries.next().expect("root error is reported");
+    assert!(kind.is_err());
+    assert!(entries.next().is_none());
+    assert_eq!(entries.counters().errors(), 1);
+}
+
+#[test]
+fn walk_invalid_glob() {
+    assert!(Walk::new("testdata").include("a[").is_err());
+}
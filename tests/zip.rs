@@ -0,0 +1,104 @@
+use infer::zip::{Archive, Container};
+
+macro_rules! test_container {
+    ($name:ident, $file:expr, $exp:expr) => {
+        #[test]
+        fn $name() {
+            let buf = include_bytes!(concat!("../testdata/", $file));
+            assert_eq!(infer::zip::container(buf), Some($exp));
+        }
+    };
+}
+
+test_container!(docx, "sample.docx", Container::DOCX);
+test_container!(docx_reordered, "sample_reordered.docx", Container::DOCX);
+test_container!(xlsx, "sample.xlsx", Container::XLSX);
+test_container!(xlsx_zip64, "sample_zip64.xlsx", Container::XLSX);
+test_container!(pptx, "sample.pptx", Container::PPTX);
+test_container!(odt, "sample.odt", Container::ODT);
+test_container!(ods, "sample.ods", Container::ODS);
+test_container!(odp, "sample.odp", Container::ODP);
+test_container!(epub, "sample.epub", Container::EPUB);
+test_container!(ora, "sample.ora", Container::ORA);
+test_container!(jar, "sample.jar", Container::JAR);
+test_container!(apk, "sample.apk", Container::APK);
+test_container!(aab, "sample.aab", Container::AAB);
+test_container!(ipa, "sample.ipa", Container::IPA);
+test_container!(xpi, "sample.xpi", Container::XPI);
+test_container!(vsix, "sample.vsix", Container::VSIX);
+
+#[test]
+fn unsigned_manifest() {
+    let buf = include_bytes!("../testdata/sample_manifest.zip");
+    assert_eq!(infer::zip::container(buf), None);
+    assert_eq!(infer::get(buf).unwrap().mime_type(), "application/zip");
+}
+
+#[test]
+fn entries() {
+    let buf = include_bytes!("../testdata/sample_reordered.docx");
+    let archive = Archive::new(buf).expect("central directory found");
+    let names: Vec<_> = archive.entries().map(|e| e.name()).collect();
+
+    assert_eq!(names.len(), 12);
+    assert_eq!(names[0], b"docProps/thumbnail.jpeg");
+    assert_eq!(names[11], b"word/styles.xml");
+    assert!(archive.find(b"[Content_Types].xml").is_some());
+    assert!(archive.contains_prefix(b"word/"));
+}
+
+#[test]
+fn stored_data() {
+    let buf = include_bytes!("../testdata/sample.odt");
+    let archive = Archive::new(buf).expect("central directory found");
+    let mimetype = archive.find(b"mimetype").expect("mimetype entry");
+
+    assert_eq!(
+        archive.stored_data(&mimetype),
+        Some(&b"application/vnd.oasis.opendocument.text"[..])
+    );
+}
+
+#[test]
+fn prefix_and_central_directory() {
+    // the first 8 KiB followed by the central directory, as read by `get_from_path`
+    let buf = include_bytes!("../testdata/sample.odt");
+    let archive = Archive::new(buf).expect("central directory found");
+    let cd_start = buf.len()
+        - 22
+        - archive
+            .entries()
+            .map(|e| 46 + e.name().len())
+            .sum::<usize>();
+
+    let mut partial = buf[..8192].to_vec();
+    partial.extend_from_slice(&buf[cd_start..]);
+
+    assert_eq!(infer::zip::container(&partial), Some(Container::ODT));
+    assert!(infer::odf::is_odt(&partial));
+}
+
+#[test]
+fn not_zip() {
+    let buf = include_bytes!("../testdata/sample.jpg");
+    assert!(Archive::new(buf).is_none());
+    assert_eq!(infer::zip::container(buf), None);
+}
+
+#[test]
+fn truncated_central_directory() {
+    let buf = include_bytes!("../testdata/sample.docx");
+    assert_eq!(infer::zip::container(&buf[..buf.len() - 30]), None);
+}
+
+#[test]
+fn local_file_headers_only() {
+    // without the central directory, packages are classified by the leading entries
+    let buf = include_bytes!("../testdata/sample.jar");
+    let archive = Archive::new(buf).expect("central directory found");
+    let cd_len: usize = archive.entries().map(|e| 46 + e.name().len()).sum();
+    let buf = &buf[..buf.len() - 22 - cd_len];
+
+    assert!(Archive::new(buf).is_none());
+    assert!(infer::app::is_jar(buf));
+}